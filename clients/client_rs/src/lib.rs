@@ -0,0 +1,350 @@
+//! Typed client for `server_rs`, built on the request and response types it
+//! shares with the server in `server_types`.
+
+use anyhow::{anyhow, Context};
+use reqwest::{Method, StatusCode, Url};
+
+pub use server_types::{
+    CreatedSearch, Difficulty, GuessRequest, GuessResult, Hint, HintRequest, HintResponse,
+    JobStatus, JobView, LeaderboardEntry, LeaderboardPage, LeaderboardResult,
+    LeaderboardSubmission, LinkMode, NavigationMove, NavigationRequest, NavigationStatus,
+    NavigationView, Outcome, PlayerStats, Puzzle, PuzzleView, RandomArticle, Score, SearchError,
+    SearchProgress, SearchRequest, SearchResponse, SearchStatus,
+};
+
+/// Every route the client calls, as `OpenAPI` path templates
+pub const ROUTES: [(Method, &str); 15] = [
+    (Method::GET, "/{starting_page}"),
+    (Method::POST, "/searches"),
+    (Method::GET, "/searches/{id}"),
+    (Method::DELETE, "/searches/{id}"),
+    (Method::GET, "/random"),
+    (Method::GET, "/daily/{date}"),
+    (Method::POST, "/guesses"),
+    (Method::GET, "/players/{id}/stats"),
+    (Method::POST, "/navigations"),
+    (Method::GET, "/navigations/{id}"),
+    (Method::POST, "/navigations/{id}/moves"),
+    (Method::POST, "/hints"),
+    (Method::POST, "/leaderboard/daily"),
+    (Method::GET, "/leaderboard/daily/{date}"),
+    (Method::GET, "/leaderboard"),
+];
+
+pub struct Client {
+    http: reqwest::Client,
+    base_url: Url,
+}
+
+impl Client {
+    /// Create a client of the server at `base_url`, e.g. `http://127.0.0.1:3000`
+    ///
+    /// # Errors
+    ///
+    /// Errors if `base_url` is not a valid base URL.
+    pub fn new(base_url: &str) -> anyhow::Result<Self> {
+        let base_url = Url::parse(base_url).context("Invalid server URL")?;
+        if base_url.cannot_be_a_base() {
+            return Err(anyhow!("Server URL '{base_url}' cannot be a base"));
+        }
+
+        Ok(Self {
+            http: reqwest::Client::new(),
+            base_url,
+        })
+    }
+
+    /// Search for the shortest path from `starting_page` to Kevin Bacon,
+    /// waiting until the search finishes
+    ///
+    /// # Errors
+    ///
+    /// Errors if the request fails or the server's reply is not a
+    /// `SearchResponse`. A search that finds no path is not an error; see
+    /// `SearchResponse::status`.
+    pub async fn search(
+        &self,
+        starting_page: &str,
+        link_mode: LinkMode,
+    ) -> anyhow::Result<SearchResponse> {
+        let response = self
+            .request(&ROUTES[0], &[starting_page])
+            .query(&[("links", link_mode)])
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    /// Queue a background search, returning the id to poll it by
+    ///
+    /// # Errors
+    ///
+    /// Errors if the request fails or the server rejects it.
+    pub async fn create_search(
+        &self,
+        start: &str,
+        link_mode: LinkMode,
+    ) -> anyhow::Result<CreatedSearch> {
+        let request = SearchRequest {
+            start: start.to_string(),
+            links: link_mode,
+        };
+        let response = self
+            .request(&ROUTES[1], &[])
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    /// Current state of a background search; `None` if it doesn't exist or
+    /// has expired
+    ///
+    /// # Errors
+    ///
+    /// Errors if the request fails or the server's reply is not a `JobView`.
+    pub async fn get_search(&self, id: u64) -> anyhow::Result<Option<JobView>> {
+        let response = self.request(&ROUTES[2], &[&id.to_string()]).send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(response.error_for_status()?.json().await?))
+    }
+
+    /// Cancel a background search, returning whether it existed
+    ///
+    /// # Errors
+    ///
+    /// Errors if the request fails.
+    pub async fn cancel_search(&self, id: u64) -> anyhow::Result<bool> {
+        let response = self.request(&ROUTES[3], &[&id.to_string()]).send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        response.error_for_status()?;
+        Ok(true)
+    }
+
+    /// A random starting article of the given difficulty, or of any when
+    /// `None`, whose answer the server has already cached
+    ///
+    /// # Errors
+    ///
+    /// Errors if the request fails or no such article was found in time.
+    pub async fn random(
+        &self,
+        difficulty: Option<Difficulty>,
+        link_mode: LinkMode,
+    ) -> anyhow::Result<RandomArticle> {
+        let mut request = self.request(&ROUTES[4], &[]).query(&[("links", link_mode)]);
+        if let Some(difficulty) = difficulty {
+            request = request.query(&[("difficulty", difficulty)]);
+        }
+        let response = request.send().await?.error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    /// The puzzle of `date`, written as `YYYY-MM-DD`. Its answer is included
+    /// for past days, or once `player` has submitted a guess at it.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the request fails, the date is invalid or the server has no
+    /// daily puzzles.
+    pub async fn daily(&self, date: &str, player: Option<&str>) -> anyhow::Result<PuzzleView> {
+        let mut request = self.request(&ROUTES[5], &[date]);
+        if let Some(player) = player {
+            request = request.query(&[("player", player)]);
+        }
+        let response = request.send().await?.error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    /// Score a guess and record it in the player's statistics
+    ///
+    /// # Errors
+    ///
+    /// Errors if the request fails, the player id is invalid or no path from
+    /// the start was found.
+    pub async fn guess(&self, request: &GuessRequest) -> anyhow::Result<GuessResult> {
+        let response = self
+            .request(&ROUTES[6], &[])
+            .json(request)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    /// Statistics of the player with the anonymous id `player`
+    ///
+    /// # Errors
+    ///
+    /// Errors if the request fails or the player id is invalid.
+    pub async fn player_stats(&self, player: &str) -> anyhow::Result<PlayerStats> {
+        let response = self
+            .request(&ROUTES[7], &[player])
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    /// Start navigating from an article to Kevin Bacon
+    ///
+    /// # Errors
+    ///
+    /// Errors if the request fails or the start article's links can't be
+    /// retrieved.
+    pub async fn start_navigation(
+        &self,
+        request: &NavigationRequest,
+    ) -> anyhow::Result<NavigationView> {
+        let response = self
+            .request(&ROUTES[8], &[])
+            .json(request)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    /// Resume a navigation session, or `None` if it doesn't exist
+    ///
+    /// # Errors
+    ///
+    /// Errors if the request fails.
+    pub async fn get_navigation(&self, id: &str) -> anyhow::Result<Option<NavigationView>> {
+        let response = self.request(&ROUTES[9], &[id]).send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(response.error_for_status()?.json().await?))
+    }
+
+    /// Follow one of the current article's links
+    ///
+    /// # Errors
+    ///
+    /// Errors if the request fails, the session has ended or `title` is not
+    /// linked from the current article.
+    pub async fn navigate_to(&self, id: &str, title: &str) -> anyhow::Result<NavigationView> {
+        let request = NavigationMove {
+            title: title.to_string(),
+        };
+        let response = self
+            .request(&ROUTES[10], &[id])
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    /// Reveal the next hint about the shortest path from a start; each hint
+    /// used lowers the player's next score for it
+    ///
+    /// # Errors
+    ///
+    /// Errors if the request fails, every hint was already revealed or no
+    /// path from the start was found.
+    pub async fn hint(&self, request: &HintRequest) -> anyhow::Result<HintResponse> {
+        let response = self
+            .request(&ROUTES[11], &[])
+            .json(request)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    /// Submit a guess at today's daily puzzle, less any hints used on it
+    ///
+    /// # Errors
+    ///
+    /// Errors if the request fails, the player id or name is invalid, the
+    /// player already submitted today or the server has no daily puzzles.
+    pub async fn submit_daily(
+        &self,
+        submission: &LeaderboardSubmission,
+    ) -> anyhow::Result<LeaderboardResult> {
+        let response = self
+            .request(&ROUTES[12], &[])
+            .json(submission)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    /// Page `page`, from 1, of the leaderboard of `date`'s puzzle, written as
+    /// `YYYY-MM-DD`, with `per_page` entries of up to 100
+    ///
+    /// # Errors
+    ///
+    /// Errors if the request fails, the date or page is invalid or the server
+    /// has no daily puzzles.
+    pub async fn daily_leaderboard(
+        &self,
+        date: &str,
+        page: u32,
+        per_page: u32,
+    ) -> anyhow::Result<LeaderboardPage> {
+        let response = self
+            .request(&ROUTES[13], &[date])
+            .query(&[("page", page), ("per_page", per_page)])
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    /// Page `page`, from 1, of the all-time leaderboard, with `per_page`
+    /// entries of up to 100
+    ///
+    /// # Errors
+    ///
+    /// Errors if the request fails, the page is invalid or the server has no
+    /// daily puzzles.
+    pub async fn leaderboard(&self, page: u32, per_page: u32) -> anyhow::Result<LeaderboardPage> {
+        let response = self
+            .request(&ROUTES[14], &[])
+            .query(&[("page", page), ("per_page", per_page)])
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    /// Build a request to `route`, filling its `{...}` segments with `args`
+    /// in order
+    fn request(
+        &self,
+        (method, template): &(Method, &str),
+        args: &[&str],
+    ) -> reqwest::RequestBuilder {
+        let mut url = self.base_url.clone();
+        {
+            let mut segments = url
+                .path_segments_mut()
+                .expect("base URL was checked when the client was created");
+            segments.pop_if_empty();
+
+            let mut args = args.iter();
+            for segment in template.trim_start_matches('/').split('/') {
+                if segment.starts_with('{') {
+                    segments.push(
+                        args.next()
+                            .expect("every route parameter needs an argument"),
+                    );
+                } else {
+                    segments.push(segment);
+                }
+            }
+        }
+
+        self.http.request(method.clone(), url)
+    }
+}
@@ -0,0 +1,206 @@
+use client_rs::{
+    Client, Difficulty, GuessRequest, Hint, HintRequest, JobStatus, LeaderboardSubmission,
+    LinkMode, NavigationRequest, NavigationStatus, Outcome, SearchStatus, ROUTES,
+};
+use crawler_common::{InMemoryLinks, LinkFilter};
+use server_rs::{
+    router, ApiDoc, AppState, Backend, Crawler, DailyPuzzles, FixedSampler, Leaderboard,
+};
+use std::{sync::Arc, time::Duration};
+use titles::KEVIN_BACON;
+use tokio::net::TcpListener;
+use utoipa::OpenApi;
+
+async fn spawn_server() -> Client {
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["Middle"]),
+        ("Middle", vec![KEVIN_BACON]),
+        ("A/B", vec![KEVIN_BACON]),
+    ]);
+    let daily = DailyPuzzles::new(links.clone(), 0).unwrap();
+    let crawler = Crawler::from_link_source(Backend::Async, 2, Arc::new(links));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let sampler = Arc::new(FixedSampler::new(vec!["Start".to_string()]));
+        axum::serve(
+            listener,
+            router(
+                AppState::new(crawler)
+                    .with_sampler(sampler)
+                    .with_daily_puzzles(daily)
+                    .with_leaderboard(Leaderboard::open_in_memory().unwrap()),
+            ),
+        )
+        .await
+        .unwrap();
+    });
+    Client::new(&format!("http://{addr}")).unwrap()
+}
+
+#[test]
+fn routes_are_documented() {
+    let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+    for (method, path) in ROUTES {
+        let method = method.as_str().to_lowercase();
+        assert!(
+            spec["paths"][path].get(&method).is_some(),
+            "{method} {path} is missing from the OpenAPI document"
+        );
+    }
+}
+
+#[tokio::test]
+async fn search() {
+    let client = spawn_server().await;
+
+    let response = client.search("Start", LinkMode::Body).await.unwrap();
+
+    assert_eq!(response.status, SearchStatus::Found);
+    assert_eq!(response.hops, Some(2));
+    assert_eq!(response.link_mode, LinkMode::Body);
+    assert_eq!(response.filter, LinkFilter::default());
+}
+
+#[tokio::test]
+async fn search_title_with_slash() {
+    let client = spawn_server().await;
+
+    let response = client.search("A/B", LinkMode::All).await.unwrap();
+
+    assert_eq!(response.starting_page, "A/B");
+    assert_eq!(response.hops, Some(1));
+}
+
+#[tokio::test]
+async fn background_search() {
+    let client = spawn_server().await;
+
+    let created = client.create_search("Start", LinkMode::All).await.unwrap();
+    assert_eq!(created.status, JobStatus::Queued);
+
+    let mut job = client.get_search(created.id).await.unwrap().unwrap();
+    for _ in 0..100 {
+        if job.status == JobStatus::Done {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        job = client.get_search(created.id).await.unwrap().unwrap();
+    }
+    assert_eq!(job.status, JobStatus::Done);
+    assert_eq!(job.path.map(|path| path.len()), Some(3));
+
+    assert!(client.cancel_search(created.id).await.unwrap());
+    assert!(!client.cancel_search(created.id).await.unwrap());
+    assert_eq!(client.get_search(created.id).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn random() {
+    let client = spawn_server().await;
+
+    let article = client
+        .random(Some(Difficulty::Easy), LinkMode::All)
+        .await
+        .unwrap();
+
+    assert_eq!(article.title, "Start");
+    assert_eq!(article.difficulty, Some(Difficulty::Easy));
+}
+
+#[tokio::test]
+async fn daily() {
+    let client = spawn_server().await;
+
+    let puzzle = client.daily("2026-10-19", Some("player-1")).await.unwrap();
+
+    assert_eq!(puzzle.target, KEVIN_BACON);
+    assert!(client.daily("someday", None).await.is_err());
+}
+
+#[tokio::test]
+async fn guess() {
+    let client = spawn_server().await;
+    let request = GuessRequest {
+        player: "player-1".to_string(),
+        start: "Start".to_string(),
+        guess: 3,
+        links: LinkMode::All,
+    };
+
+    let result = client.guess(&request).await.unwrap();
+    let stats = client.player_stats("player-1").await.unwrap();
+
+    assert_eq!(result.score.outcome, Outcome::OffByOne);
+    assert_eq!(stats, result.stats);
+    assert_eq!(stats.games, 1);
+    assert!(client.player_stats("not valid").await.is_err());
+}
+
+#[tokio::test]
+async fn navigate() {
+    let client = spawn_server().await;
+    let request = NavigationRequest {
+        start: Some("Start".to_string()),
+        links: LinkMode::All,
+    };
+
+    let session = client.start_navigation(&request).await.unwrap();
+    client.navigate_to(&session.id, "Middle").await.unwrap();
+    let finished = client.navigate_to(&session.id, KEVIN_BACON).await.unwrap();
+
+    assert_eq!(finished.status, NavigationStatus::Finished);
+    assert_eq!(finished.extra_hops, Some(0));
+    assert_eq!(
+        client.get_navigation(&session.id).await.unwrap(),
+        Some(finished)
+    );
+    assert!(client.navigate_to(&session.id, "Start").await.is_err());
+    assert_eq!(client.get_navigation("unknown").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn hint() {
+    let client = spawn_server().await;
+    let request = HintRequest {
+        player: "player-1".to_string(),
+        start: "Start".to_string(),
+        links: LinkMode::All,
+    };
+
+    let first = client.hint(&request).await.unwrap();
+    let second = client.hint(&request).await.unwrap();
+
+    assert!(matches!(first.hints[0], Hint::HopBound { .. }));
+    assert_eq!(
+        second.hints[1],
+        Hint::Article {
+            hop: 1,
+            title: "Middle".to_string(),
+            paths_through: 1,
+        }
+    );
+    assert_eq!(second.remaining, 0);
+    assert!(client.hint(&request).await.is_err());
+}
+
+#[tokio::test]
+async fn leaderboard() {
+    let client = spawn_server().await;
+    let submission = LeaderboardSubmission {
+        player: "player-1".to_string(),
+        name: "Ann".to_string(),
+        guess: 2,
+    };
+
+    let result = client.submit_daily(&submission).await.unwrap();
+    assert_eq!(result.rank, 1);
+    assert!(client.submit_daily(&submission).await.is_err());
+
+    let date = result.puzzle.date.to_string();
+    let page = client.daily_leaderboard(&date, 1, 10).await.unwrap();
+    assert_eq!(page.entries[0].name, "Ann");
+    assert_eq!(client.leaderboard(1, 10).await.unwrap().total, 1);
+}
@@ -0,0 +1,156 @@
+//! Compares a breadth first search keeping owned titles in its parents map and
+//! queue against one keeping interned ids, on a synthetic link graph. Reports
+//! the time each takes and the most memory each holds at once.
+//!
+//! Run with `cargo bench --bench interner`.
+
+use crawler_common::{Parents, TitleId};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    collections::{HashMap, VecDeque},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+/// Articles in the synthetic graph
+const ARTICLES: usize = 200_000;
+/// Links from each article
+const LINKS: usize = 20;
+const RUNS: u32 = 5;
+
+/// Counts the bytes allocated, and the most allocated at once
+struct Counting;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(allocated, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Titles of the articles and the articles each links to
+struct Graph {
+    titles: Vec<String>,
+    links: Vec<Vec<usize>>,
+}
+
+impl Graph {
+    /// A graph with random links, the same on every run
+    fn synthetic() -> Self {
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = move || {
+            // splitmix64
+            seed = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        };
+
+        let titles = (0..ARTICLES)
+            .map(|i| format!("Synthetic article number {i}"))
+            .collect();
+        let links = (0..ARTICLES)
+            .map(|_| {
+                (0..LINKS)
+                    .map(|_| usize::try_from(next() % ARTICLES as u64).unwrap())
+                    .collect()
+            })
+            .collect();
+        Self { titles, links }
+    }
+
+    /// The titles `title` links to, owned as a fetch would return them
+    fn linked_titles(&self, index: usize) -> Vec<String> {
+        self.links[index]
+            .iter()
+            .map(|&linked| self.titles[linked].clone())
+            .collect()
+    }
+}
+
+/// The search as done before titles were interned
+fn search_titles(graph: &Graph, start: &str, index: &HashMap<&str, usize>) -> usize {
+    let mut parents: HashMap<String, String> = HashMap::new();
+    let mut queue = VecDeque::from([start.to_string()]);
+
+    while let Some(cur_title) = queue.pop_front() {
+        for linked_title in graph.linked_titles(index[cur_title.as_str()]) {
+            if parents.contains_key(&linked_title) {
+                continue;
+            }
+            parents.insert(linked_title.clone(), cur_title.clone());
+            queue.push_back(linked_title);
+        }
+    }
+    parents.len()
+}
+
+fn search_ids(graph: &Graph, start: &str, index: &HashMap<&str, usize>) -> usize {
+    let mut parents = Parents::new();
+    let mut queue: VecDeque<TitleId> = VecDeque::from([parents.intern(start)]);
+
+    while let Some(cur_id) = queue.pop_front() {
+        let cur_index = index[&**parents.title(cur_id)];
+        for linked_title in graph.linked_titles(cur_index) {
+            let linked_id = parents.intern(&linked_title);
+            if !parents.insert(linked_id, cur_id) {
+                continue;
+            }
+            queue.push_back(linked_id);
+        }
+    }
+    parents.len()
+}
+
+/// The fastest of several runs, and the most memory held above what was
+/// allocated before the runs
+fn measure(search: impl Fn() -> usize) -> (Duration, usize, usize) {
+    let mut fastest = Duration::MAX;
+    let mut found = 0;
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    PEAK.store(before, Ordering::Relaxed);
+
+    for _ in 0..RUNS {
+        let started = Instant::now();
+        found = black_box(search());
+        fastest = fastest.min(started.elapsed());
+    }
+    (fastest, PEAK.load(Ordering::Relaxed) - before, found)
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn report(name: &str, (time, peak, found): (Duration, usize, usize)) {
+    println!(
+        "{name:<8} {found} titles found in {time:>10.2?}, peak {:>8.1} MiB",
+        peak as f64 / (1024.0 * 1024.0)
+    );
+}
+
+fn main() {
+    let graph = Graph::synthetic();
+    let index: HashMap<&str, usize> = graph
+        .titles
+        .iter()
+        .enumerate()
+        .map(|(i, title)| (title.as_str(), i))
+        .collect();
+    let start = graph.titles[0].as_str();
+
+    println!("{ARTICLES} articles with {LINKS} links each, best of {RUNS} runs");
+    report("strings", measure(|| search_titles(&graph, start, &index)));
+    report("interned", measure(|| search_ids(&graph, start, &index)));
+}
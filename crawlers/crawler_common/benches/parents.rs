@@ -0,0 +1,127 @@
+//! Throughput of recording found titles from many workers at once, with the
+//! parents map behind one lock against `ShardedParents`.
+//!
+//! Run with `cargo bench --bench parents`.
+
+use crawler_common::{Parents, ShardedParents, TitleId};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard, PoisonError},
+    thread,
+};
+
+/// Distinct titles workers find between them
+const TITLES: usize = 50_000;
+/// Pages each worker fetches
+const PAGES: usize = 200;
+/// Links on each page
+const LINKS: usize = 100;
+
+/// The titles linked from each page each worker fetches, with repeats across
+/// pages and workers as in a real crawl
+fn pages(titles: &[String], workers: usize) -> Vec<Vec<Vec<&str>>> {
+    let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move || {
+        // xorshift64
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        usize::try_from(seed % titles.len() as u64).unwrap()
+    };
+
+    (0..workers)
+        .map(|_| {
+            (0..PAGES)
+                .map(|_| (0..LINKS).map(|_| titles[next()].as_str()).collect())
+                .collect()
+        })
+        .collect()
+}
+
+/// Run a worker per list of pages, each recording the titles linked from
+/// each page, one at a time as the crawlers do
+fn run(pages: &[Vec<Vec<&str>>], record: impl Fn(&str, &[&str]) + Sync) {
+    thread::scope(|scope| {
+        for worker_pages in pages {
+            let record = &record;
+            scope.spawn(move || {
+                for (page, links) in worker_pages.iter().enumerate() {
+                    record(links[page % LINKS], links);
+                }
+            });
+        }
+    });
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn parents(c: &mut Criterion) {
+    let titles: Vec<String> = (0..TITLES)
+        .map(|i| format!("Synthetic article number {i}"))
+        .collect();
+    let mut group = c.benchmark_group("parents");
+    group.sample_size(20);
+
+    for workers in [1, 2, 4, 8, 16, 32] {
+        let pages = pages(&titles, workers);
+        group.throughput(Throughput::Elements((workers * PAGES * LINKS) as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("mutex_string_map", workers),
+            &pages,
+            |b, pages| {
+                b.iter(|| {
+                    let parents: Mutex<HashMap<String, String>> = Mutex::default();
+                    run(pages, |cur_title, links| {
+                        for &linked_title in links {
+                            let mut p = lock(&parents);
+                            if !p.contains_key(linked_title) {
+                                p.insert(linked_title.to_string(), cur_title.to_string());
+                            }
+                        }
+                    });
+                    parents
+                });
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("mutex_interned", workers),
+            &pages,
+            |b, pages| {
+                b.iter(|| {
+                    let parents: Mutex<Parents> = Mutex::default();
+                    run(pages, |cur_title, links| {
+                        let cur_id: TitleId = lock(&parents).intern(cur_title);
+                        for linked_title in links {
+                            let mut p = lock(&parents);
+                            let linked_id = p.intern(linked_title);
+                            p.insert(linked_id, cur_id);
+                        }
+                    });
+                    parents
+                });
+            },
+        );
+
+        group.bench_with_input(BenchmarkId::new("sharded", workers), &pages, |b, pages| {
+            b.iter(|| {
+                let parents = ShardedParents::new();
+                run(pages, |cur_title, links| {
+                    let cur_id = parents.intern(cur_title);
+                    for linked_title in links {
+                        parents.insert(linked_title, cur_id);
+                    }
+                });
+                parents
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parents);
+criterion_main!(benches);
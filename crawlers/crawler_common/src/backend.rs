@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// Which crawler implementation runs searches
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// `crawler_rs`, run on the blocking thread pool
+    Blocking,
+    /// `crawler_rs_async`
+    #[default]
+    Async,
+    /// `crawler_rs_async_channels`
+    Channels,
+    /// `crawler_rs_pipelined`
+    Pipelined,
+    /// `crawler_rs_graph`, searching an offline graph on the blocking thread
+    /// pool
+    Graph,
+}
+
+impl FromStr for Backend {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "blocking" => Ok(Self::Blocking),
+            "async" => Ok(Self::Async),
+            "channels" => Ok(Self::Channels),
+            "pipelined" => Ok(Self::Pipelined),
+            "graph" => Ok(Self::Graph),
+            _ => Err(anyhow::anyhow!(
+                "Unknown crawler backend '{s}'; expected blocking, async, channels, pipelined or graph"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Blocking => "blocking",
+            Self::Async => "async",
+            Self::Channels => "channels",
+            Self::Pipelined => "pipelined",
+            Self::Graph => "graph",
+        })
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{LinkFilter, LinkMode};
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::Path};
+
+/// The state of an unfinished crawl, saved to disk so a crawl stopped part
+/// way can be resumed instead of started over.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub start: String,
+    pub target: String,
+    pub link_mode: LinkMode,
+    pub filter: LinkFilter,
+    /// Depth of the titles in `frontier`
+    pub depth: usize,
+    /// Titles at `depth` still to be expanded
+    pub frontier: Vec<String>,
+    /// Titles found at `depth + 1`, expanded after `frontier`
+    pub next_frontier: Vec<String>,
+    /// The title each found title was first linked from
+    pub parents: HashMap<String, String>,
+    pub pages_fetched: usize,
+}
+
+impl Checkpoint {
+    /// The state of a crawl from `start` before any page is fetched
+    #[must_use]
+    pub fn new(start: &str, target: &str, link_mode: LinkMode, filter: &LinkFilter) -> Self {
+        Self {
+            start: start.to_string(),
+            target: target.to_string(),
+            link_mode,
+            filter: filter.clone(),
+            depth: 0,
+            frontier: vec![start.to_string()],
+            next_frontier: Vec::new(),
+            parents: HashMap::new(),
+            pages_fetched: 0,
+        }
+    }
+
+    /// Load a checkpoint saved with `save`
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file can't be read or decoded.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let bytes = fs::read(path)
+            .with_context(|| format!("Failed to read checkpoint {}", path.display()))?;
+        serde_json::from_slice(&bytes)
+            .with_context(|| format!("Failed to decode checkpoint {}", path.display()))
+    }
+
+    /// Save the checkpoint as JSON. The file is replaced in one step, so a
+    /// crawl stopped while saving leaves the previous checkpoint intact.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file can't be written.
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let partial = path.with_extension("partial");
+        fs::write(&partial, serde_json::to_vec(self)?)
+            .with_context(|| format!("Failed to write checkpoint {}", partial.display()))?;
+        fs::rename(&partial, path)
+            .with_context(|| format!("Failed to replace checkpoint {}", path.display()))
+    }
+
+    /// Check the checkpoint was saved by a crawl for the same target, link
+    /// mode and link filter, as resuming a different crawl would give a wrong
+    /// path
+    ///
+    /// # Errors
+    ///
+    /// Errors if the target, link mode or link filter differ.
+    pub fn ensure_matches(
+        &self,
+        target: &str,
+        link_mode: LinkMode,
+        filter: &LinkFilter,
+    ) -> anyhow::Result<()> {
+        if self.target != target || self.link_mode != link_mode {
+            return Err(anyhow!(
+                "Checkpoint is of a crawl to {} following {:?} links, not {target} following {link_mode:?} links",
+                self.target,
+                self.link_mode
+            ));
+        }
+        if &self.filter != filter {
+            return Err(anyhow!(
+                "Checkpoint is of a crawl with another link filter: {:?}",
+                self.filter
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Progress reported by a crawler while it searches.
+///
+/// Depths count link hops from the starting page, which is at depth 0.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CrawlEvent {
+    /// The links of a page were fetched
+    PageFetched {
+        title: String,
+        depth: usize,
+        link_count: usize,
+    },
+    /// The links of a page could not be fetched; the page is skipped
+    FetchFailed {
+        title: String,
+        depth: usize,
+        error: String,
+    },
+    /// Every page at `depth` has been expanded
+    DepthCompleted {
+        depth: usize,
+        pages_fetched: usize,
+        frontier_size: usize,
+    },
+    /// The target was discovered `depth` hops from the start
+    TargetFound { depth: usize },
+}
+
+/// Callback receiving every event of a crawl
+pub type EventHandler = Arc<dyn Fn(CrawlEvent) + Send + Sync>;
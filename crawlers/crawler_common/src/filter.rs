@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use wiki_response::Link;
+
+/// The main article namespace
+pub const MAIN_NAMESPACE: u32 = 0;
+
+/// Namespace prefixes of English Wikipedia titles and their numbers
+const NAMESPACES: [(&str, u32); 26] = [
+    ("Talk", 1),
+    ("User", 2),
+    ("User talk", 3),
+    ("Wikipedia", 4),
+    ("WP", 4),
+    ("Project", 4),
+    ("Wikipedia talk", 5),
+    ("File", 6),
+    ("Image", 6),
+    ("File talk", 7),
+    ("MediaWiki", 8),
+    ("MediaWiki talk", 9),
+    ("Template", 10),
+    ("Template talk", 11),
+    ("Help", 12),
+    ("Help talk", 13),
+    ("Category", 14),
+    ("Category talk", 15),
+    ("Portal", 100),
+    ("Portal talk", 101),
+    ("Draft", 118),
+    ("Draft talk", 119),
+    ("TimedText", 710),
+    ("TimedText talk", 711),
+    ("Module", 828),
+    ("Module talk", 829),
+];
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Rules deciding which linked titles a crawl is allowed to follow.
+///
+/// The namespaces are sent to the API as `plnamespace`; every other rule is
+/// applied client-side to the returned titles.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct LinkFilter {
+    pub namespaces: BTreeSet<u32>,
+    pub exclude_disambiguation: bool,
+    pub exclude_lists: bool,
+    pub exclude_dates: bool,
+    pub deny_list: BTreeSet<String>,
+}
+
+impl Default for LinkFilter {
+    fn default() -> Self {
+        Self {
+            namespaces: BTreeSet::from([MAIN_NAMESPACE]),
+            exclude_disambiguation: false,
+            exclude_lists: false,
+            exclude_dates: false,
+            deny_list: BTreeSet::new(),
+        }
+    }
+}
+
+impl LinkFilter {
+    /// Value for the `plnamespace` query parameter
+    #[must_use]
+    pub fn namespace_param(&self) -> String {
+        self.namespaces
+            .iter()
+            .map(u32::to_string)
+            .collect::<Vec<_>>()
+            .join("|")
+    }
+
+    /// Whether a link returned by the API passes this filter
+    #[must_use]
+    pub fn allows(&self, link: &Link) -> bool {
+        self.namespaces.contains(&link.ns) && self.allows_title(&link.title)
+    }
+
+    /// Whether a title without a namespace number, such as one from a
+    /// `LinkSource`, passes this filter. The namespace is read from the
+    /// title's prefix.
+    #[must_use]
+    pub fn allows_source_title(&self, title: &str) -> bool {
+        self.namespaces.contains(&namespace_of(title)) && self.allows_title(title)
+    }
+
+    /// Whether a title passes the title-based rules of this filter.
+    ///
+    /// Disambiguation pages are recognised by their `(disambiguation)`
+    /// suffix, since `prop=links` does not report page properties.
+    #[must_use]
+    pub fn allows_title(&self, title: &str) -> bool {
+        if self.deny_list.contains(title) {
+            return false;
+        }
+        if self.exclude_disambiguation && title.ends_with("(disambiguation)") {
+            return false;
+        }
+        if self.exclude_lists && (title.starts_with("List of ") || title.starts_with("Lists of ")) {
+            return false;
+        }
+        if self.exclude_dates && is_date_title(title) {
+            return false;
+        }
+        true
+    }
+}
+
+/// The namespace a title belongs to, judging by its prefix. Titles without a
+/// known prefix, like "Star Wars: Episode IV", are in the main namespace.
+#[must_use]
+pub fn namespace_of(title: &str) -> u32 {
+    title
+        .split_once(':')
+        .and_then(|(prefix, _)| {
+            NAMESPACES
+                .iter()
+                .find(|(name, _)| *name == prefix)
+                .map(|&(_, ns)| ns)
+        })
+        .unwrap_or(MAIN_NAMESPACE)
+}
+
+/// Whether the title names a year, decade, century, or calendar date article,
+/// e.g. "1984", "1980s", "1984 in film", "19th century", "May 5", "5 May".
+fn is_date_title(title: &str) -> bool {
+    let title = title
+        .strip_prefix("AD ")
+        .or_else(|| title.strip_suffix(" BC"))
+        .or_else(|| title.strip_suffix(" AD"))
+        .unwrap_or(title);
+
+    let (head, rest) = title.split_once(' ').unwrap_or((title, ""));
+
+    if MONTHS.contains(&head) {
+        return is_number(rest);
+    }
+    if MONTHS.contains(&rest) {
+        return is_number(head);
+    }
+
+    if rest == "century" || rest == "millennium" {
+        return is_ordinal(head);
+    }
+
+    (is_number(head) || is_decade(head)) && (rest.is_empty() || rest.starts_with("in "))
+}
+
+fn is_number(s: &str) -> bool {
+    !s.is_empty() && s.len() <= 4 && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_decade(s: &str) -> bool {
+    s.strip_suffix('s')
+        .is_some_and(|n| is_number(n) && n.ends_with('0'))
+}
+
+fn is_ordinal(s: &str) -> bool {
+    ["st", "nd", "rd", "th"]
+        .iter()
+        .any(|suffix| s.strip_suffix(suffix).is_some_and(is_number))
+}
@@ -0,0 +1,233 @@
+use crate::InMemoryLinks;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    str::FromStr,
+    sync::Arc,
+};
+
+/// An estimate of how likely a title is to lead to the target quickly.
+///
+/// Crawls searching in a `SearchOrder` other than `Breadth` fetch titles with
+/// higher scores first. Closures taking a title and returning a score are
+/// heuristics too, which is how heuristics are weighted and combined.
+pub trait Heuristic: Send + Sync {
+    fn score(&self, title: &str) -> u64;
+}
+
+impl<F: Fn(&str) -> u64 + Send + Sync> Heuristic for F {
+    fn score(&self, title: &str) -> u64 {
+        self(title)
+    }
+}
+
+/// The order a crawl fetches the pages it has found in
+#[derive(Clone, Default)]
+pub enum SearchOrder {
+    /// Breadth first, in the order pages were found
+    #[default]
+    Breadth,
+    /// Breadth first, fetching the pages of each depth with the highest
+    /// scores first. Paths found are still shortest, but found sooner when
+    /// the heuristic is good.
+    Prioritized(Arc<dyn Heuristic>),
+    /// Always fetch the page with the highest score found so far, whatever
+    /// its depth. Paths are found quickly, but may not be shortest.
+    BestFirst(Arc<dyn Heuristic>),
+}
+
+impl SearchOrder {
+    /// Whether paths found in this order are always shortest
+    #[must_use]
+    pub fn finds_shortest(&self) -> bool {
+        self.kind().finds_shortest()
+    }
+
+    /// The order without its heuristic
+    #[must_use]
+    pub fn kind(&self) -> SearchOrderKind {
+        match self {
+            Self::Breadth => SearchOrderKind::Breadth,
+            Self::Prioritized(_) => SearchOrderKind::Prioritized,
+            Self::BestFirst(_) => SearchOrderKind::BestFirst,
+        }
+    }
+}
+
+/// A `SearchOrder` named without its heuristic, as chosen in configuration
+/// and reported alongside results
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum SearchOrderKind {
+    #[default]
+    Breadth,
+    Prioritized,
+    BestFirst,
+}
+
+impl SearchOrderKind {
+    /// Whether paths found in this order are always shortest
+    #[must_use]
+    pub fn finds_shortest(self) -> bool {
+        self != Self::BestFirst
+    }
+
+    /// The order scoring titles with `heuristic`, which breadth first
+    /// ignores
+    #[must_use]
+    pub fn with_heuristic(self, heuristic: Arc<dyn Heuristic>) -> SearchOrder {
+        match self {
+            Self::Breadth => SearchOrder::Breadth,
+            Self::Prioritized => SearchOrder::Prioritized(heuristic),
+            Self::BestFirst => SearchOrder::BestFirst(heuristic),
+        }
+    }
+}
+
+impl FromStr for SearchOrderKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "breadth" => Ok(Self::Breadth),
+            "prioritized" => Ok(Self::Prioritized),
+            "best-first" => Ok(Self::BestFirst),
+            _ => Err(anyhow::anyhow!(
+                "Unknown search order '{s}'; expected breadth, prioritized or best-first"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SearchOrderKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Breadth => "breadth",
+            Self::Prioritized => "prioritized",
+            Self::BestFirst => "best-first",
+        })
+    }
+}
+
+impl fmt::Debug for SearchOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Breadth => "Breadth",
+            Self::Prioritized(_) => "Prioritized",
+            Self::BestFirst(_) => "BestFirst",
+        })
+    }
+}
+
+/// Scores titles by how many of the target's categories they share
+#[derive(Debug, Clone, Default)]
+pub struct SharedCategories {
+    target: HashSet<String>,
+    categories: HashMap<String, Vec<String>>,
+}
+
+impl SharedCategories {
+    /// Compare the categories of each title in `categories` with
+    /// `target_categories`. Titles without an entry score 0.
+    #[must_use]
+    pub fn new(
+        target_categories: impl IntoIterator<Item = String>,
+        categories: HashMap<String, Vec<String>>,
+    ) -> Self {
+        Self {
+            target: target_categories.into_iter().collect(),
+            categories,
+        }
+    }
+}
+
+impl Heuristic for SharedCategories {
+    fn score(&self, title: &str) -> u64 {
+        self.categories.get(title).map_or(0, |categories| {
+            categories
+                .iter()
+                .filter(|category| self.target.contains(*category))
+                .count() as u64
+        })
+    }
+}
+
+/// Scores the titles known to link to the target highest, then the titles
+/// known to link to those
+#[derive(Debug, Clone, Default)]
+pub struct TargetInbound {
+    scores: HashMap<String, u64>,
+}
+
+impl TargetInbound {
+    /// Score the titles linking to the target 2, and those linking to them 1
+    #[must_use]
+    pub fn new(
+        linking: impl IntoIterator<Item = String>,
+        linking_to_linking: impl IntoIterator<Item = String>,
+    ) -> Self {
+        let mut scores: HashMap<String, u64> = linking_to_linking
+            .into_iter()
+            .map(|title| (title, 1))
+            .collect();
+        scores.extend(linking.into_iter().map(|title| (title, 2)));
+        Self { scores }
+    }
+
+    /// Find the titles linking to `target`, and to those, in `links`
+    #[must_use]
+    pub fn from_links(links: &InMemoryLinks, target: &str) -> Self {
+        let linking_to = |titles: &HashSet<&str>| -> HashSet<String> {
+            links
+                .links()
+                .iter()
+                .filter(|(_, linked)| linked.iter().any(|title| titles.contains(title.as_str())))
+                .map(|(title, _)| title.clone())
+                .collect()
+        };
+        let linking = linking_to(&HashSet::from([target]));
+        let linking_to_linking = linking_to(&linking.iter().map(String::as_str).collect());
+        Self::new(linking, linking_to_linking)
+    }
+}
+
+impl Heuristic for TargetInbound {
+    fn score(&self, title: &str) -> u64 {
+        self.scores.get(title).copied().unwrap_or(0)
+    }
+}
+
+/// Scores titles by how popular they are, such as by page views or by how
+/// many titles link to them
+#[derive(Debug, Clone, Default)]
+pub struct Popularity {
+    popularity: HashMap<String, u64>,
+}
+
+impl Popularity {
+    /// Titles without an entry score 0
+    #[must_use]
+    pub fn new(popularity: HashMap<String, u64>) -> Self {
+        Self { popularity }
+    }
+
+    /// Count the titles linking to each title in `links`
+    #[must_use]
+    pub fn from_links(links: &InMemoryLinks) -> Self {
+        let mut popularity: HashMap<String, u64> = HashMap::new();
+        for linked in links.links().values() {
+            for title in linked {
+                *popularity.entry(title.clone()).or_default() += 1;
+            }
+        }
+        Self { popularity }
+    }
+}
+
+impl Heuristic for Popularity {
+    fn score(&self, title: &str) -> u64 {
+        self.popularity.get(title).copied().unwrap_or(0)
+    }
+}
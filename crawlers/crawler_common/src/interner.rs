@@ -0,0 +1,160 @@
+use std::{collections::HashMap, sync::Arc};
+
+/// Small id standing for a title in a `TitleInterner`
+pub type TitleId = u32;
+
+/// Each distinct title stored once, and referred to by a `TitleId` so
+/// crawls keep ids rather than copies of the title in their maps and queues.
+#[derive(Debug, Default, Clone)]
+pub struct TitleInterner {
+    ids: HashMap<Arc<str>, TitleId>,
+    titles: Vec<Arc<str>>,
+}
+
+impl TitleInterner {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The id of `title`, adding it if it's new
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than `u32::MAX` titles are interned.
+    pub fn intern(&mut self, title: &str) -> TitleId {
+        if let Some(&id) = self.ids.get(title) {
+            return id;
+        }
+        let id = TitleId::try_from(self.titles.len()).expect("title ids should fit in u32");
+        let title: Arc<str> = Arc::from(title);
+        self.titles.push(title.clone());
+        self.ids.insert(title, id);
+        id
+    }
+
+    /// The id of `title`, if it was interned
+    #[must_use]
+    pub fn get(&self, title: &str) -> Option<TitleId> {
+        self.ids.get(title).copied()
+    }
+
+    /// The title `id` stands for
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` wasn't given out by this interner.
+    #[must_use]
+    pub fn title(&self, id: TitleId) -> &Arc<str> {
+        &self.titles[id as usize]
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.titles.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.titles.is_empty()
+    }
+}
+
+/// The title each title found by a crawl was first linked from, by id
+#[derive(Debug, Default, Clone)]
+pub struct Parents {
+    titles: TitleInterner,
+    /// Indexed by id, as ids are given out in order
+    linked_from: Vec<Option<TitleId>>,
+    found: usize,
+}
+
+impl Parents {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rebuild from a map of titles to the titles they were linked from, as
+    /// saved in a checkpoint
+    #[must_use]
+    pub fn from_titles(parents: &HashMap<String, String>) -> Self {
+        let mut map = Self::new();
+        for (child, parent) in parents {
+            let child = map.intern(child);
+            let parent = map.intern(parent);
+            map.insert(child, parent);
+        }
+        map
+    }
+
+    /// Every title found mapped to the title it was linked from
+    #[must_use]
+    pub fn to_titles(&self) -> HashMap<String, String> {
+        self.linked_from
+            .iter()
+            .enumerate()
+            .filter_map(|(child, parent)| {
+                let parent = (*parent)?;
+                Some((
+                    self.titles.titles[child].to_string(),
+                    self.title(parent).to_string(),
+                ))
+            })
+            .collect()
+    }
+
+    /// See `TitleInterner::intern`
+    pub fn intern(&mut self, title: &str) -> TitleId {
+        self.titles.intern(title)
+    }
+
+    /// See `TitleInterner::title`
+    #[must_use]
+    pub fn title(&self, id: TitleId) -> &Arc<str> {
+        self.titles.title(id)
+    }
+
+    /// Record that `child` was linked from `parent`, unless `child` was
+    /// already found. Returns whether it is new.
+    pub fn insert(&mut self, child: TitleId, parent: TitleId) -> bool {
+        let child = child as usize;
+        if child >= self.linked_from.len() {
+            self.linked_from.resize(child + 1, None);
+        }
+        if self.linked_from[child].is_some() {
+            return false;
+        }
+        self.linked_from[child] = Some(parent);
+        self.found += 1;
+        true
+    }
+
+    /// Titles found so far
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.found
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.found == 0
+    }
+
+    /// The path from `start` to `target` following parents back from
+    /// `target`, if `target` was found
+    #[must_use]
+    pub fn path(&self, start: &str, target: &str) -> Option<Vec<String>> {
+        let start = self.titles.get(start)?;
+        let mut id = self.titles.get(target)?;
+        let mut path = vec![self.title(id).to_string()];
+
+        while id != start {
+            id = (*self.linked_from.get(id as usize)?)?;
+            path.push(self.title(id).to_string());
+        }
+
+        path.reverse();
+        Some(path)
+    }
+}
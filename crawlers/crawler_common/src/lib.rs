@@ -0,0 +1,43 @@
+mod backend;
+mod checkpoint;
+mod events;
+mod filter;
+mod heuristic;
+mod interner;
+mod query;
+mod sharded;
+mod source;
+mod title;
+mod wikitext;
+
+pub use backend::Backend;
+pub use checkpoint::Checkpoint;
+pub use events::{CrawlEvent, EventHandler};
+pub use filter::{namespace_of, LinkFilter, MAIN_NAMESPACE};
+pub use heuristic::{
+    Heuristic, Popularity, SearchOrder, SearchOrderKind, SharedCategories, TargetInbound,
+};
+pub use interner::{Parents, TitleId, TitleInterner};
+pub use query::{
+    categories_params, links_params, random_params, template_links_params, wikitext_params,
+    LinkMode, API_URL,
+};
+pub use sharded::ShardedParents;
+pub use source::{InMemoryLinks, LinkSource};
+#[cfg(feature = "test-support")]
+pub use source::{Latency, Stalls};
+pub use title::canonical_title;
+pub use wikitext::{body_links, wikitext_links};
+
+use serde::Serialize;
+
+/// A found path along with the settings that produced it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CrawlResult {
+    pub path: Vec<String>,
+    pub filter: LinkFilter,
+    pub link_mode: LinkMode,
+    /// Whether the path is known to be a shortest one. Best-first searches
+    /// find paths that may not be.
+    pub shortest: bool,
+}
@@ -0,0 +1,86 @@
+use crate::LinkFilter;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Endpoint of the `MediaWiki` Action API
+pub const API_URL: &str = "https://en.wikipedia.org/w/api.php";
+
+/// Which of an article's links are followed during a crawl
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum LinkMode {
+    /// Every link on the page, including those from navigation templates
+    #[default]
+    All,
+    /// Only links written in the article body; links that only come from
+    /// transcluded templates such as navboxes are dropped, but links the body
+    /// also writes itself are kept
+    Body,
+}
+
+/// Query parameters listing the links of the article with the given title
+#[must_use]
+pub fn links_params(title: &str, filter: &LinkFilter) -> HashMap<String, String> {
+    HashMap::from([
+        ("action".to_string(), "query".to_string()),
+        ("titles".to_string(), title.to_string()),
+        ("prop".to_string(), "links".to_string()),
+        ("pllimit".to_string(), "max".to_string()),
+        ("plnamespace".to_string(), filter.namespace_param()),
+        ("format".to_string(), "json".to_string()),
+    ])
+}
+
+/// Query parameters sampling `count` random articles from the filter's
+/// namespaces
+#[must_use]
+pub fn random_params(count: usize, filter: &LinkFilter) -> HashMap<String, String> {
+    HashMap::from([
+        ("action".to_string(), "query".to_string()),
+        ("list".to_string(), "random".to_string()),
+        ("rnlimit".to_string(), count.to_string()),
+        ("rnnamespace".to_string(), filter.namespace_param()),
+        ("format".to_string(), "json".to_string()),
+    ])
+}
+
+/// Query parameters listing the visible categories of the article with the
+/// given title
+#[must_use]
+pub fn categories_params(title: &str) -> HashMap<String, String> {
+    HashMap::from([
+        ("action".to_string(), "query".to_string()),
+        ("titles".to_string(), title.to_string()),
+        ("prop".to_string(), "categories".to_string()),
+        ("clshow".to_string(), "!hidden".to_string()),
+        ("cllimit".to_string(), "max".to_string()),
+        ("format".to_string(), "json".to_string()),
+    ])
+}
+
+/// Query parameters listing the links of every template transcluded by the
+/// article with the given title
+#[must_use]
+pub fn template_links_params(title: &str, filter: &LinkFilter) -> HashMap<String, String> {
+    let mut params = links_params(title, filter);
+    params.extend([
+        ("generator".to_string(), "templates".to_string()),
+        ("gtllimit".to_string(), "max".to_string()),
+    ]);
+    params
+}
+
+/// Query parameters fetching the wikitext source of the article with the
+/// given title
+#[must_use]
+pub fn wikitext_params(title: &str) -> HashMap<String, String> {
+    HashMap::from([
+        ("action".to_string(), "query".to_string()),
+        ("titles".to_string(), title.to_string()),
+        ("prop".to_string(), "revisions".to_string()),
+        ("rvprop".to_string(), "content".to_string()),
+        ("rvslots".to_string(), "main".to_string()),
+        ("format".to_string(), "json".to_string()),
+    ])
+}
@@ -0,0 +1,181 @@
+use crate::TitleId;
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, RandomState},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+};
+
+/// Shards titles are spread over, so workers finding titles at the same time
+/// rarely wait on one another
+const SHARDS: usize = 64;
+
+#[derive(Debug, Default)]
+struct Shard {
+    ids: HashMap<Arc<str>, TitleId>,
+    /// Indexed by the id divided by the shard count
+    titles: Vec<Arc<str>>,
+    linked_from: Vec<Option<TitleId>>,
+}
+
+/// `Parents` that many workers can add to at once. Titles are split into
+/// shards by hash, each behind its own lock, and the id of a title says which
+/// shard it is in.
+///
+/// A worker that panics while holding a shard can't leave it half updated, so
+/// the other workers carry on using it rather than panicking too.
+#[derive(Debug)]
+pub struct ShardedParents {
+    shards: Vec<Mutex<Shard>>,
+    hasher: RandomState,
+    found: AtomicUsize,
+}
+
+impl Default for ShardedParents {
+    fn default() -> Self {
+        Self {
+            shards: (0..SHARDS).map(|_| Mutex::default()).collect(),
+            hasher: RandomState::new(),
+            found: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl ShardedParents {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rebuild from a map of titles to the titles they were linked from, as
+    /// saved in a checkpoint
+    #[must_use]
+    pub fn from_titles(parents: &HashMap<String, String>) -> Self {
+        let map = Self::new();
+        for (child, parent) in parents {
+            let parent = map.intern(parent);
+            map.insert(child, parent);
+        }
+        map
+    }
+
+    /// Every title found mapped to the title it was linked from
+    #[must_use]
+    pub fn to_titles(&self) -> HashMap<String, String> {
+        let mut titles = HashMap::with_capacity(self.len());
+        for shard in 0..SHARDS {
+            let found: Vec<(Arc<str>, TitleId)> = {
+                let shard = self.shard(shard);
+                shard
+                    .titles
+                    .iter()
+                    .zip(&shard.linked_from)
+                    .filter_map(|(title, parent)| Some((title.clone(), (*parent)?)))
+                    .collect()
+            };
+            for (title, parent) in found {
+                titles.insert(title.to_string(), self.title(parent).to_string());
+            }
+        }
+        titles
+    }
+
+    /// The id of `title`, adding it if it's new
+    ///
+    /// # Panics
+    ///
+    /// Panics if a shard holds more titles than ids can tell apart.
+    pub fn intern(&self, title: &str) -> TitleId {
+        self.intern_in(self.shard_of(title), title).0
+    }
+
+    /// Record that `title` was linked from `parent`, unless it was already
+    /// found. Returns the id of `title` if it is new.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a shard holds more titles than ids can tell apart.
+    pub fn insert(&self, title: &str, parent: TitleId) -> Option<TitleId> {
+        let (id, mut shard) = self.intern_in(self.shard_of(title), title);
+        let linked_from = &mut shard.linked_from[id as usize / SHARDS];
+        if linked_from.is_some() {
+            return None;
+        }
+        *linked_from = Some(parent);
+        self.found.fetch_add(1, Ordering::Relaxed);
+        Some(id)
+    }
+
+    /// The title `id` stands for
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` wasn't given out by this map.
+    #[must_use]
+    pub fn title(&self, id: TitleId) -> Arc<str> {
+        let id = id as usize;
+        self.shard(id % SHARDS).titles[id / SHARDS].clone()
+    }
+
+    /// Titles found so far
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.found.load(Ordering::Relaxed)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The path from `start` to `target` following parents back from
+    /// `target`, if `target` was found
+    #[must_use]
+    pub fn path(&self, start: &str, target: &str) -> Option<Vec<String>> {
+        let mut title: Arc<str> = Arc::from(target);
+        let mut path = vec![target.to_string()];
+
+        while &*title != start {
+            let parent = {
+                let shard = self.shard(self.shard_of(&title));
+                let index = *shard.ids.get(&title)? as usize / SHARDS;
+                shard.linked_from[index]?
+            };
+            title = self.title(parent);
+            path.push(title.to_string());
+        }
+
+        path.reverse();
+        Some(path)
+    }
+
+    fn shard_of(&self, title: &str) -> usize {
+        // The remainder is below the shard count, so it fits
+        #[allow(clippy::cast_possible_truncation)]
+        let shard = (self.hasher.hash_one(title) % SHARDS as u64) as usize;
+        shard
+    }
+
+    fn shard(&self, shard: usize) -> MutexGuard<'_, Shard> {
+        self.shards[shard]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The id of `title` in its shard, along with the locked shard
+    fn intern_in(&self, shard_index: usize, title: &str) -> (TitleId, MutexGuard<'_, Shard>) {
+        let mut shard = self.shard(shard_index);
+        if let Some(&id) = shard.ids.get(title) {
+            return (id, shard);
+        }
+        let id = TitleId::try_from(shard.titles.len() * SHARDS + shard_index)
+            .expect("title ids should fit in u32");
+        let title: Arc<str> = Arc::from(title);
+        shard.titles.push(title.clone());
+        shard.linked_from.push(None);
+        shard.ids.insert(title, id);
+        (id, shard)
+    }
+}
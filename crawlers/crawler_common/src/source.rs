@@ -0,0 +1,146 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+#[cfg(feature = "test-support")]
+use std::time::Duration;
+
+/// Somewhere the links of an article can be looked up.
+///
+/// Crawlers fetch from the Wikipedia API unless given another source, which
+/// lets searches run against a local or in-memory graph.
+#[async_trait]
+pub trait LinkSource: Send + Sync {
+    /// Collect all titles linked to in the article with the given title.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the links of the article cannot be retrieved.
+    async fn linked_titles(&self, title: &str) -> anyhow::Result<Vec<String>>;
+}
+
+/// A fixed link graph held in memory. Titles without an entry have no links.
+///
+/// Serializes as a JSON object mapping each title to its linked titles, the
+/// format offline graphs are stored in.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct InMemoryLinks {
+    links: HashMap<String, Vec<String>>,
+}
+
+impl InMemoryLinks {
+    #[must_use]
+    pub fn new(links: HashMap<String, Vec<String>>) -> Self {
+        Self { links }
+    }
+
+    /// Every title with an entry, mapped to its linked titles
+    #[must_use]
+    pub fn links(&self) -> &HashMap<String, Vec<String>> {
+        &self.links
+    }
+
+    /// Build a graph from `(title, linked titles)` pairs
+    #[must_use]
+    pub fn from_edges<'a>(
+        edges: impl IntoIterator<Item = (&'a str, impl IntoIterator<Item = &'a str>)>,
+    ) -> Self {
+        Self::new(
+            edges
+                .into_iter()
+                .map(|(title, linked)| {
+                    (
+                        title.to_string(),
+                        linked.into_iter().map(str::to_string).collect(),
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+#[async_trait]
+impl LinkSource for InMemoryLinks {
+    async fn linked_titles(&self, title: &str) -> anyhow::Result<Vec<String>> {
+        Ok(self.links.get(title).cloned().unwrap_or_default())
+    }
+}
+
+/// Links of a graph, with each lookup taking as long as `delay` says, for
+/// testing and benchmarking crawlers against a slow source
+#[cfg(feature = "test-support")]
+pub struct Latency {
+    links: InMemoryLinks,
+    delay: Box<dyn Fn(&str) -> Duration + Send + Sync>,
+}
+
+#[cfg(feature = "test-support")]
+impl Latency {
+    #[must_use]
+    pub fn new(
+        links: InMemoryLinks,
+        delay: impl Fn(&str) -> Duration + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            links,
+            delay: Box::new(delay),
+        }
+    }
+
+    /// Delay only the lookups of the given titles
+    #[must_use]
+    pub fn of_titles<'a>(
+        links: InMemoryLinks,
+        delays: impl IntoIterator<Item = (&'a str, Duration)>,
+    ) -> Self {
+        let delays: HashMap<String, Duration> = delays
+            .into_iter()
+            .map(|(title, delay)| (title.to_string(), delay))
+            .collect();
+        Self::new(links, move |title| {
+            delays.get(title).copied().unwrap_or_default()
+        })
+    }
+}
+
+#[cfg(feature = "test-support")]
+#[async_trait]
+impl LinkSource for Latency {
+    async fn linked_titles(&self, title: &str) -> anyhow::Result<Vec<String>> {
+        let delay = (self.delay)(title);
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+        self.links.linked_titles(title).await
+    }
+}
+
+/// Links of a graph, except one title whose lookup never finishes, for
+/// testing crawls that are stopped part way
+#[cfg(feature = "test-support")]
+pub struct Stalls {
+    links: InMemoryLinks,
+    title: String,
+}
+
+#[cfg(feature = "test-support")]
+impl Stalls {
+    #[must_use]
+    pub fn new(links: InMemoryLinks, title: &str) -> Self {
+        Self {
+            links,
+            title: title.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "test-support")]
+#[async_trait]
+impl LinkSource for Stalls {
+    async fn linked_titles(&self, title: &str) -> anyhow::Result<Vec<String>> {
+        if title == self.title {
+            std::future::pending::<()>().await;
+        }
+        self.links.linked_titles(title).await
+    }
+}
@@ -0,0 +1,14 @@
+/// The form `MediaWiki` stores a title in: underscores read as spaces, runs of
+/// whitespace collapsed, and the first letter capitalised. Redirects are not
+/// followed.
+#[must_use]
+pub fn canonical_title(title: &str) -> String {
+    let spaced = title.replace('_', " ");
+    let collapsed = spaced.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let mut chars = collapsed.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => collapsed,
+    }
+}
@@ -0,0 +1,36 @@
+use crate::canonical_title;
+use std::{collections::HashSet, hash::BuildHasher};
+
+/// Titles linked with `[[...]]` in `wikitext`, in canonical form. Section
+/// anchors and labels are dropped, and links within links (such as in image
+/// captions) are found too.
+#[must_use]
+pub fn wikitext_links(wikitext: &str) -> HashSet<String> {
+    wikitext
+        .split("[[")
+        .skip(1)
+        .filter_map(|link| {
+            let target = link
+                .split(['|', ']', '[', '{', '}', '\n'])
+                .next()
+                .unwrap_or_default();
+            let target = target.trim().trim_start_matches(':');
+            let title = target.split('#').next().unwrap_or_default();
+            (!title.trim().is_empty()).then(|| canonical_title(title))
+        })
+        .collect()
+}
+
+/// Drop the titles in `linked_titles` that only come from transcluded
+/// templates such as navboxes: those in `template_titles` that `wikitext`,
+/// the article's own source, doesn't link to itself.
+#[must_use]
+pub fn body_links<S: BuildHasher>(
+    mut linked_titles: Vec<String>,
+    template_titles: &HashSet<String, S>,
+    wikitext: &str,
+) -> Vec<String> {
+    let written = wikitext_links(wikitext);
+    linked_titles.retain(|title| !template_titles.contains(title) || written.contains(title));
+    linked_titles
+}
@@ -0,0 +1,43 @@
+use crawler_common::{Checkpoint, LinkFilter, LinkMode};
+
+#[test]
+fn saves_and_loads() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("crawl.json");
+    let mut checkpoint = Checkpoint::new("Start", "Target", LinkMode::Body, &LinkFilter::default());
+    checkpoint.depth = 1;
+    checkpoint.frontier = vec!["Middle".to_string()];
+    checkpoint
+        .parents
+        .insert("Middle".to_string(), "Start".to_string());
+
+    checkpoint.save(&path).unwrap();
+    checkpoint.pages_fetched = 1;
+    checkpoint.save(&path).unwrap();
+
+    assert_eq!(Checkpoint::load(&path).unwrap(), checkpoint);
+    assert!(!path.with_extension("partial").exists());
+}
+
+#[test]
+fn only_matches_same_crawl() {
+    let filter = LinkFilter::default();
+    let checkpoint = Checkpoint::new("Start", "Target", LinkMode::All, &filter);
+    let other_filter = LinkFilter {
+        exclude_lists: true,
+        ..LinkFilter::default()
+    };
+
+    assert!(checkpoint
+        .ensure_matches("Target", LinkMode::All, &filter)
+        .is_ok());
+    assert!(checkpoint
+        .ensure_matches("Other", LinkMode::All, &filter)
+        .is_err());
+    assert!(checkpoint
+        .ensure_matches("Target", LinkMode::Body, &filter)
+        .is_err());
+    assert!(checkpoint
+        .ensure_matches("Target", LinkMode::All, &other_filter)
+        .is_err());
+}
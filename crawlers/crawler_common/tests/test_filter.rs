@@ -0,0 +1,113 @@
+use crawler_common::{namespace_of, LinkFilter, MAIN_NAMESPACE};
+use std::collections::BTreeSet;
+use wiki_response::Link;
+
+fn link(ns: u32, title: &str) -> Link {
+    Link {
+        ns,
+        title: title.to_string(),
+    }
+}
+
+#[test]
+fn default_allows_only_main_namespace() {
+    let filter = LinkFilter::default();
+
+    assert_eq!(filter.namespace_param(), "0");
+    assert!(filter.allows(&link(MAIN_NAMESPACE, "Kevin Bacon")));
+    assert!(!filter.allows(&link(14, "Category:American male film actors")));
+    assert!(filter.allows(&link(MAIN_NAMESPACE, "List of films")));
+    assert!(filter.allows(&link(MAIN_NAMESPACE, "1984")));
+}
+
+#[test]
+fn namespace_set() {
+    let filter = LinkFilter {
+        namespaces: BTreeSet::from([14, 0]),
+        ..LinkFilter::default()
+    };
+
+    assert_eq!(filter.namespace_param(), "0|14");
+    assert!(filter.allows(&link(14, "Category:American male film actors")));
+    assert!(!filter.allows(&link(10, "Template:Kevin Bacon")));
+}
+
+#[test]
+fn namespace_from_title_prefix() {
+    assert_eq!(namespace_of("Kevin Bacon"), MAIN_NAMESPACE);
+    assert_eq!(namespace_of("Star Wars: Episode IV"), MAIN_NAMESPACE);
+    assert_eq!(namespace_of("Category:American male film actors"), 14);
+    assert_eq!(namespace_of("Template talk:Kevin Bacon"), 11);
+
+    let filter = LinkFilter::default();
+    assert!(filter.allows_source_title("Kevin Bacon"));
+    assert!(!filter.allows_source_title("Category:American male film actors"));
+}
+
+#[test]
+fn exclude_disambiguation() {
+    let filter = LinkFilter {
+        exclude_disambiguation: true,
+        ..LinkFilter::default()
+    };
+
+    assert!(!filter.allows_title("Bacon (disambiguation)"));
+    assert!(filter.allows_title("Bacon"));
+}
+
+#[test]
+fn exclude_lists() {
+    let filter = LinkFilter {
+        exclude_lists: true,
+        ..LinkFilter::default()
+    };
+
+    assert!(!filter.allows_title("List of Kevin Bacon performances"));
+    assert!(!filter.allows_title("Lists of actors"));
+    assert!(filter.allows_title("Listen to Me (film)"));
+}
+
+#[test]
+fn exclude_dates() {
+    let filter = LinkFilter {
+        exclude_dates: true,
+        ..LinkFilter::default()
+    };
+
+    for title in [
+        "1984",
+        "1980s",
+        "1984 in film",
+        "1990s in music",
+        "20th century",
+        "2nd millennium",
+        "July 8",
+        "8 July",
+        "July 1958",
+        "44 BC",
+        "AD 33",
+    ] {
+        assert!(!filter.allows_title(title), "{title} should be excluded");
+    }
+
+    for title in [
+        "1917 (2019 film)",
+        "2001: A Space Odyssey (film)",
+        "Apollo 13 (film)",
+        "May (film)",
+        "20th Century Studios",
+    ] {
+        assert!(filter.allows_title(title), "{title} should be allowed");
+    }
+}
+
+#[test]
+fn deny_list() {
+    let filter = LinkFilter {
+        deny_list: BTreeSet::from(["United States".to_string()]),
+        ..LinkFilter::default()
+    };
+
+    assert!(!filter.allows(&link(MAIN_NAMESPACE, "United States")));
+    assert!(filter.allows(&link(MAIN_NAMESPACE, "United Kingdom")));
+}
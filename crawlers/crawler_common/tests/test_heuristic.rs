@@ -0,0 +1,88 @@
+use crawler_common::{
+    Heuristic, InMemoryLinks, Popularity, SearchOrder, SearchOrderKind, SharedCategories,
+    TargetInbound,
+};
+use std::{collections::HashMap, sync::Arc};
+
+fn links() -> InMemoryLinks {
+    InMemoryLinks::from_edges([
+        ("Start", vec!["Near", "Far"]),
+        ("Far", vec!["Near"]),
+        ("Near", vec!["Target"]),
+        ("Elsewhere", vec!["Target"]),
+    ])
+}
+
+#[test]
+fn scores_shared_categories() {
+    let heuristic = SharedCategories::new(
+        ["Actors".to_string(), "Films".to_string()],
+        HashMap::from([
+            (
+                "Footloose".to_string(),
+                vec!["Films".to_string(), "Actors".to_string()],
+            ),
+            ("Apollo 13".to_string(), vec!["Films".to_string()]),
+            ("Physics".to_string(), vec!["Science".to_string()]),
+        ]),
+    );
+
+    assert_eq!(heuristic.score("Footloose"), 2);
+    assert_eq!(heuristic.score("Apollo 13"), 1);
+    assert_eq!(heuristic.score("Physics"), 0);
+    assert_eq!(heuristic.score("Unknown"), 0);
+}
+
+#[test]
+fn scores_titles_linking_to_target() {
+    let heuristic = TargetInbound::from_links(&links(), "Target");
+
+    assert_eq!(heuristic.score("Near"), 2);
+    assert_eq!(heuristic.score("Elsewhere"), 2);
+    assert_eq!(heuristic.score("Start"), 1);
+    assert_eq!(heuristic.score("Far"), 1);
+    assert_eq!(heuristic.score("Target"), 0);
+}
+
+#[test]
+fn scores_popularity() {
+    let heuristic = Popularity::from_links(&links());
+
+    assert_eq!(heuristic.score("Near"), 2);
+    assert_eq!(heuristic.score("Target"), 2);
+    assert_eq!(heuristic.score("Far"), 1);
+    assert_eq!(heuristic.score("Start"), 0);
+}
+
+#[test]
+fn combines_heuristics() {
+    let inbound = TargetInbound::from_links(&links(), "Target");
+    let popularity = Popularity::from_links(&links());
+    let combined = move |title: &str| 10 * inbound.score(title) + popularity.score(title);
+
+    assert_eq!(combined.score("Near"), 22);
+    assert_eq!(combined.score("Far"), 11);
+}
+
+#[test]
+fn only_best_first_may_miss_shortest() {
+    let heuristic: Arc<dyn Heuristic> = Arc::new(|_: &str| 0);
+
+    assert!(SearchOrder::Breadth.finds_shortest());
+    assert!(SearchOrder::Prioritized(heuristic.clone()).finds_shortest());
+    assert!(!SearchOrder::BestFirst(heuristic).finds_shortest());
+}
+
+#[test]
+fn order_kinds_parse_back_from_their_names() {
+    let heuristic: Arc<dyn Heuristic> = Arc::new(|_: &str| 0);
+    for kind in [
+        SearchOrderKind::Breadth,
+        SearchOrderKind::Prioritized,
+        SearchOrderKind::BestFirst,
+    ] {
+        assert_eq!(kind.to_string().parse::<SearchOrderKind>().unwrap(), kind);
+        assert_eq!(kind.with_heuristic(heuristic.clone()).kind(), kind);
+    }
+    assert!("depth".parse::<SearchOrderKind>().is_err());
+}
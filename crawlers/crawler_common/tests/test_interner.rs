@@ -0,0 +1,55 @@
+use crawler_common::{Parents, TitleInterner};
+use std::collections::HashMap;
+
+#[test]
+fn interns_each_title_once() {
+    let mut titles = TitleInterner::new();
+
+    let a = titles.intern("A");
+    let b = titles.intern("B");
+
+    assert_ne!(a, b);
+    assert_eq!(titles.intern("A"), a);
+    assert_eq!(titles.len(), 2);
+    assert_eq!(titles.get("B"), Some(b));
+    assert_eq!(titles.get("C"), None);
+    assert_eq!(&**titles.title(b), "B");
+}
+
+#[test]
+fn keeps_first_parent() {
+    let mut parents = Parents::new();
+    let start = parents.intern("Start");
+    let a = parents.intern("A");
+    let b = parents.intern("B");
+    let target = parents.intern("Target");
+
+    assert!(parents.insert(a, start));
+    assert!(parents.insert(b, start));
+    assert!(parents.insert(target, a));
+    assert!(!parents.insert(target, b));
+
+    assert_eq!(
+        parents.path("Start", "Target"),
+        Some(vec![
+            "Start".to_string(),
+            "A".to_string(),
+            "Target".to_string()
+        ])
+    );
+    assert_eq!(parents.path("Start", "Missing"), None);
+}
+
+#[test]
+fn converts_to_and_from_titles() {
+    let titles = HashMap::from([
+        ("A".to_string(), "Start".to_string()),
+        ("Target".to_string(), "A".to_string()),
+    ]);
+
+    let parents = Parents::from_titles(&titles);
+
+    assert_eq!(parents.len(), 2);
+    assert_eq!(parents.to_titles(), titles);
+    assert_eq!(parents.path("Start", "Target").unwrap().len(), 3);
+}
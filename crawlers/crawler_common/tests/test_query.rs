@@ -0,0 +1,60 @@
+use crawler_common::{
+    categories_params, links_params, random_params, template_links_params, wikitext_params,
+    LinkFilter, LinkMode,
+};
+
+#[test]
+fn links_query() {
+    let params = links_params("Kevin Bacon", &LinkFilter::default());
+
+    assert_eq!(params["titles"], "Kevin Bacon");
+    assert_eq!(params["prop"], "links");
+    assert_eq!(params["plnamespace"], "0");
+    assert!(!params.contains_key("generator"));
+}
+
+#[test]
+fn template_links_query() {
+    let params = template_links_params("Kevin Bacon", &LinkFilter::default());
+
+    assert_eq!(params["titles"], "Kevin Bacon");
+    assert_eq!(params["generator"], "templates");
+    assert_eq!(params["prop"], "links");
+}
+
+#[test]
+fn link_mode_serde() {
+    assert_eq!(LinkMode::default(), LinkMode::All);
+    assert_eq!(
+        serde_json::from_str::<LinkMode>("\"body\"").unwrap(),
+        LinkMode::Body
+    );
+    assert_eq!(serde_json::to_string(&LinkMode::All).unwrap(), "\"all\"");
+}
+
+#[test]
+fn categories_query() {
+    let params = categories_params("Kevin Bacon");
+
+    assert_eq!(params["titles"], "Kevin Bacon");
+    assert_eq!(params["prop"], "categories");
+    assert_eq!(params["clshow"], "!hidden");
+}
+
+#[test]
+fn random_query() {
+    let params = random_params(5, &LinkFilter::default());
+
+    assert_eq!(params["list"], "random");
+    assert_eq!(params["rnlimit"], "5");
+    assert_eq!(params["rnnamespace"], "0");
+}
+
+#[test]
+fn wikitext_query() {
+    let params = wikitext_params("Kevin Bacon");
+
+    assert_eq!(params["titles"], "Kevin Bacon");
+    assert_eq!(params["prop"], "revisions");
+    assert_eq!(params["rvprop"], "content");
+}
@@ -0,0 +1,63 @@
+use crawler_common::ShardedParents;
+use std::{collections::HashMap, thread};
+
+#[test]
+fn keeps_first_parent() {
+    let parents = ShardedParents::new();
+    let start = parents.intern("Start");
+
+    let a = parents.insert("A", start).unwrap();
+    let b = parents.insert("B", start).unwrap();
+    assert!(parents.insert("Target", a).is_some());
+    assert_eq!(parents.insert("Target", b), None);
+
+    assert_eq!(parents.len(), 3);
+    assert_eq!(&*parents.title(b), "B");
+    assert_eq!(
+        parents.path("Start", "Target"),
+        Some(vec![
+            "Start".to_string(),
+            "A".to_string(),
+            "Target".to_string()
+        ])
+    );
+    assert_eq!(parents.path("Start", "Missing"), None);
+}
+
+#[test]
+fn finds_each_title_once_across_threads() {
+    let parents = ShardedParents::new();
+    let start = parents.intern("Start");
+    let titles: Vec<String> = (0..1000).map(|i| format!("Title {i}")).collect();
+
+    let new_counts: Vec<usize> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..8)
+            .map(|_| {
+                scope.spawn(|| {
+                    titles
+                        .iter()
+                        .filter(|title| parents.insert(title, start).is_some())
+                        .count()
+                })
+            })
+            .collect();
+        workers.into_iter().map(|w| w.join().unwrap()).collect()
+    });
+
+    assert_eq!(new_counts.iter().sum::<usize>(), titles.len());
+    assert_eq!(parents.len(), titles.len());
+}
+
+#[test]
+fn converts_to_and_from_titles() {
+    let titles = HashMap::from([
+        ("A".to_string(), "Start".to_string()),
+        ("Target".to_string(), "A".to_string()),
+    ]);
+
+    let parents = ShardedParents::from_titles(&titles);
+
+    assert_eq!(parents.len(), 2);
+    assert_eq!(parents.to_titles(), titles);
+    assert_eq!(parents.path("Start", "Target").unwrap().len(), 3);
+}
@@ -0,0 +1,18 @@
+use crawler_common::{InMemoryLinks, LinkSource};
+
+#[tokio::test]
+async fn in_memory_links() {
+    let links = InMemoryLinks::from_edges([("A", vec!["B", "C"]), ("B", vec![])]);
+
+    assert_eq!(links.linked_titles("A").await.unwrap(), ["B", "C"]);
+    assert!(links.linked_titles("B").await.unwrap().is_empty());
+    assert!(links.linked_titles("Missing").await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn in_memory_links_from_json() {
+    let links: InMemoryLinks = serde_json::from_str(r#"{"A": ["B"], "B": []}"#).unwrap();
+
+    assert_eq!(links.links().len(), 2);
+    assert_eq!(links.linked_titles("A").await.unwrap(), ["B"]);
+}
@@ -0,0 +1,13 @@
+use crawler_common::canonical_title;
+
+#[test]
+fn canonical_titles() {
+    assert_eq!(canonical_title("Kevin Bacon"), "Kevin Bacon");
+    assert_eq!(canonical_title("kevin_bacon"), "Kevin bacon");
+    assert_eq!(
+        canonical_title("  Footloose   (1984 film) "),
+        "Footloose (1984 film)"
+    );
+    assert_eq!(canonical_title("ångström"), "Ångström");
+    assert_eq!(canonical_title(""), "");
+}
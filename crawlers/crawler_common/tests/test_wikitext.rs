@@ -0,0 +1,42 @@
+use crawler_common::{body_links, wikitext_links};
+use std::collections::HashSet;
+
+const WIKITEXT: &str = "'''Kevin Bacon''' starred in [[Footloose (1984 film)|Footloose]] \
+    and [[mystic_River (film)#Cast|Mystic River]].\n\
+    [[File:Bacon.jpg|thumb|At the [[Sundance Film Festival]]]]\n\
+    {{Kevin Bacon}}";
+
+#[test]
+fn links_in_wikitext() {
+    assert_eq!(
+        wikitext_links(WIKITEXT),
+        HashSet::from([
+            "Footloose (1984 film)".to_string(),
+            "Mystic River (film)".to_string(),
+            "File:Bacon.jpg".to_string(),
+            "Sundance Film Festival".to_string(),
+        ])
+    );
+    assert!(wikitext_links("[[#History]] and [[ ]]").is_empty());
+}
+
+#[test]
+fn body_links_keep_links_also_in_navboxes() {
+    let linked = vec![
+        "Footloose (1984 film)".to_string(),
+        "Mystic River (film)".to_string(),
+        "Tremors (film)".to_string(),
+        "Hollow Man".to_string(),
+    ];
+    // The {{Kevin Bacon}} navbox links to both films, but only Mystic River
+    // is also written in the body
+    let navbox = HashSet::from([
+        "Mystic River (film)".to_string(),
+        "Tremors (film)".to_string(),
+    ]);
+
+    assert_eq!(
+        body_links(linked, &navbox, WIKITEXT),
+        ["Footloose (1984 film)", "Mystic River (film)", "Hollow Man"]
+    );
+}
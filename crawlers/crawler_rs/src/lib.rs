@@ -1,16 +1,35 @@
 use anyhow::{anyhow, Context};
+use crawler_common::{
+    body_links, links_params, template_links_params, wikitext_params, Checkpoint, CrawlEvent,
+    CrawlResult, EventHandler, LinkFilter, LinkMode, LinkSource, Parents, TitleId, API_URL,
+};
 use dotenvy::dotenv;
+use futures_executor::block_on;
 use reqwest::blocking::Client;
 use std::{
-    collections::{HashMap, VecDeque},
-    env,
+    collections::{HashMap, HashSet, VecDeque},
+    env, fs,
+    path::PathBuf,
+    sync::Arc,
     time::Duration,
 };
 use titles::KEVIN_BACON;
 use wiki_response::WikiResponse;
 
+/// Pages fetched between checkpoints within a depth
+const CHECKPOINT_PAGES: usize = 500;
+
+#[derive(Clone)]
 pub struct WikipediaCrawler {
-    client: Client,
+    /// Absent when links come from a `LinkSource`; a blocking client can't be
+    /// dropped inside an async runtime, which is where sources are used
+    client: Option<Client>,
+    filter: LinkFilter,
+    link_mode: LinkMode,
+    target: String,
+    on_event: Option<EventHandler>,
+    source: Option<Arc<dyn LinkSource>>,
+    checkpoint: Option<PathBuf>,
 }
 
 impl WikipediaCrawler {
@@ -33,7 +52,88 @@ impl WikipediaCrawler {
             .build()
             .context("Error creating http client")?;
 
-        Ok(Self { client })
+        Ok(Self {
+            client: Some(client),
+            filter: LinkFilter::default(),
+            link_mode: LinkMode::default(),
+            target: KEVIN_BACON.to_string(),
+            on_event: None,
+            source: None,
+            checkpoint: None,
+        })
+    }
+
+    /// Create a crawler that looks up links in the given source instead of
+    /// the Wikipedia API. Lookups block the calling thread until they finish.
+    #[must_use]
+    pub fn from_link_source(source: Arc<dyn LinkSource>) -> Self {
+        Self {
+            client: None,
+            filter: LinkFilter::default(),
+            link_mode: LinkMode::default(),
+            target: KEVIN_BACON.to_string(),
+            on_event: None,
+            source: Some(source),
+            checkpoint: None,
+        }
+    }
+
+    /// Restrict which linked titles the crawl follows
+    #[must_use]
+    pub fn with_filter(mut self, filter: LinkFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// The filter applied to linked titles
+    #[must_use]
+    pub fn filter(&self) -> &LinkFilter {
+        &self.filter
+    }
+
+    /// Choose whether links from transcluded templates are followed
+    #[must_use]
+    pub fn with_link_mode(mut self, link_mode: LinkMode) -> Self {
+        self.link_mode = link_mode;
+        self
+    }
+
+    /// Which links of each article are followed
+    #[must_use]
+    pub fn link_mode(&self) -> LinkMode {
+        self.link_mode
+    }
+
+    /// Search for a path to `target` instead of Kevin Bacon
+    #[must_use]
+    pub fn with_target(mut self, target: &str) -> Self {
+        self.target = target.to_string();
+        self
+    }
+
+    /// The article searched for
+    #[must_use]
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Save the state of every crawl to `path` as each depth completes and
+    /// every few hundred pages, so it can be resumed with `resume` if
+    /// stopped. The file is removed once the crawl finishes.
+    #[must_use]
+    pub fn with_checkpoint(mut self, path: impl Into<PathBuf>) -> Self {
+        self.checkpoint = Some(path.into());
+        self
+    }
+
+    /// Report progress of every crawl to the given callback
+    #[must_use]
+    pub fn with_event_handler(
+        mut self,
+        on_event: impl Fn(CrawlEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_event = Some(Arc::new(on_event));
+        self
     }
 
     /// Execute the main crawl process.
@@ -42,50 +142,148 @@ impl WikipediaCrawler {
     ///
     /// This function errors if it fails to find a successful path after
     /// exhausting all found links.
-    pub fn crawl(&self, start_title: &str) -> anyhow::Result<Vec<String>> {
-        if start_title == KEVIN_BACON {
-            return Ok(vec![KEVIN_BACON.to_string()]);
+    pub fn crawl(&self, start_title: &str) -> anyhow::Result<CrawlResult> {
+        if start_title == self.target {
+            return Ok(self.result(vec![self.target.clone()]));
         }
 
-        let mut queue = VecDeque::from([start_title.to_string()]);
-        let mut parents = HashMap::new();
+        self.search(Checkpoint::new(
+            start_title,
+            &self.target,
+            self.link_mode,
+            &self.filter,
+        ))
+    }
+
+    /// Continue a crawl from a checkpoint saved by an earlier crawl with the
+    /// same target and link mode.
+    ///
+    /// # Errors
+    ///
+    /// This function errors if the checkpoint is of a different crawl, or if
+    /// it fails to find a successful path after exhausting all found links.
+    pub fn resume(&self, checkpoint: Checkpoint) -> anyhow::Result<CrawlResult> {
+        checkpoint.ensure_matches(&self.target, self.link_mode, &self.filter)?;
+        self.search(checkpoint)
+    }
+
+    fn search(&self, checkpoint: Checkpoint) -> anyhow::Result<CrawlResult> {
+        let Checkpoint {
+            start,
+            mut depth,
+            frontier,
+            next_frontier,
+            parents,
+            mut pages_fetched,
+            ..
+        } = checkpoint;
+        let mut parents = Parents::from_titles(&parents);
+        parents.intern(&start);
+        let target = parents.intern(&self.target);
+        let mut queue = VecDeque::new();
+        for (titles, title_depth) in [(frontier, depth), (next_frontier, depth + 1)] {
+            for title in titles {
+                queue.push_back((parents.intern(&title), title_depth));
+            }
+        }
 
-        'search: while let Some(cur_title) = queue.pop_front() {
+        'search: while let Some((cur_id, cur_depth)) = queue.pop_front() {
+            if cur_depth > depth {
+                self.emit(CrawlEvent::DepthCompleted {
+                    depth,
+                    pages_fetched,
+                    frontier_size: queue.len() + 1,
+                });
+                depth = cur_depth;
+                queue.push_front((cur_id, cur_depth));
+                self.save_checkpoint(&start, depth, &queue, &parents, pages_fetched);
+                continue;
+            }
+
+            let cur_title = parents.title(cur_id).clone();
             let linked_titles = match self.get_linked_titles(&cur_title) {
                 Ok(linked_titles) => linked_titles,
                 Err(e) => {
-                    println!("Failed to get linked titles for page '{cur_title}': {e}");
+                    self.emit(CrawlEvent::FetchFailed {
+                        title: cur_title.to_string(),
+                        depth,
+                        error: e.to_string(),
+                    });
                     continue;
                 }
             };
+            pages_fetched += 1;
 
-            println!(
-                "Got linked titles for page '{cur_title}'; length: {}",
-                linked_titles.len()
-            );
+            self.emit(CrawlEvent::PageFetched {
+                title: cur_title.to_string(),
+                depth,
+                link_count: linked_titles.len(),
+            });
 
             for linked_title in linked_titles {
-                if parents.contains_key(&linked_title) {
+                let linked_id = parents.intern(&linked_title);
+                if !parents.insert(linked_id, cur_id) {
                     continue;
                 }
 
-                parents.insert(linked_title.to_string(), cur_title.to_string());
-
-                if linked_title == KEVIN_BACON {
-                    println!("Found target");
+                if linked_id == target {
+                    self.emit(CrawlEvent::TargetFound { depth: depth + 1 });
                     break 'search;
                 }
 
-                queue.push_back(linked_title.to_string());
+                queue.push_back((linked_id, depth + 1));
+            }
+
+            if pages_fetched % CHECKPOINT_PAGES == 0 {
+                self.save_checkpoint(&start, depth, &queue, &parents, pages_fetched);
             }
         }
 
-        println!("Crawl finished.");
+        if let Some(checkpoint) = &self.checkpoint {
+            let _ = fs::remove_file(checkpoint);
+        }
 
-        let path = Self::get_path(start_title, &parents);
-        println!("{:?}", path.as_ref());
+        let path = parents.path(&start, &self.target);
+        path.map(|path| self.result(path))
+            .ok_or_else(|| anyhow!("Could not find path to {}", self.target))
+    }
 
-        path.map_err(|_| anyhow::Error::msg("Could not find path to Kevin Bacon"))
+    /// Save the crawl's state if checkpoints are enabled. The crawl carries
+    /// on if saving fails, as it can still finish without one.
+    fn save_checkpoint(
+        &self,
+        start: &str,
+        depth: usize,
+        queue: &VecDeque<(TitleId, usize)>,
+        parents: &Parents,
+        pages_fetched: usize,
+    ) {
+        let Some(path) = &self.checkpoint else {
+            return;
+        };
+        let (frontier, next_frontier): (Vec<_>, Vec<_>) = queue
+            .iter()
+            .partition(|(_, title_depth)| *title_depth == depth);
+        let checkpoint = Checkpoint {
+            start: start.to_string(),
+            target: self.target.clone(),
+            link_mode: self.link_mode,
+            filter: self.filter.clone(),
+            depth,
+            frontier: frontier
+                .into_iter()
+                .map(|&(id, _)| parents.title(id).to_string())
+                .collect(),
+            next_frontier: next_frontier
+                .into_iter()
+                .map(|&(id, _)| parents.title(id).to_string())
+                .collect(),
+            parents: parents.to_titles(),
+            pages_fetched,
+        };
+        if let Err(e) = checkpoint.save(path) {
+            eprintln!("Failed to save checkpoint: {e:#}");
+        }
     }
 
     /// Collect all titles linked to in the article with the given title.
@@ -96,43 +294,52 @@ impl WikipediaCrawler {
     ///   - Any http request fails
     ///   - Any http response status is not within 200-299
     ///   - Returned JSON is invalid or otherwise not decodable
+    ///   - The crawler's link source fails to look up the article
     pub fn get_linked_titles(&self, title: &str) -> anyhow::Result<Vec<String>> {
-        let url = "https://en.wikipedia.org/w/api.php";
-        let mut params = HashMap::from([
-            ("action".to_string(), "query".to_string()),
-            ("titles".to_string(), title.to_string()),
-            ("prop".to_string(), "links".to_string()),
-            ("pllimit".to_string(), "max".to_string()),
-            ("format".to_string(), "json".to_string()),
-        ]);
+        if let Some(source) = &self.source {
+            let mut linked_titles = block_on(source.linked_titles(title))?;
+            linked_titles.retain(|linked_title| self.filter.allows_source_title(linked_title));
+            return Ok(linked_titles);
+        }
 
-        let mut linked_titles = Vec::new();
+        let mut linked_titles =
+            self.query_linked_titles(title, links_params(title, &self.filter))?;
 
-        loop {
-            let resp = self
-                .client
-                .get(url)
-                .query(&params)
-                .send()
-                .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
-
-            if !resp.status().is_success() {
-                return Err(anyhow!("HTTP error {} for page '{}'", resp.status(), title));
+        if self.link_mode == LinkMode::Body {
+            let template_titles: HashSet<String> = self
+                .query_linked_titles(title, template_links_params(title, &self.filter))?
+                .into_iter()
+                .collect();
+            if linked_titles
+                .iter()
+                .any(|linked_title| template_titles.contains(linked_title))
+            {
+                let wikitext = self.query_wikitext(title)?;
+                linked_titles = body_links(linked_titles, &template_titles, &wikitext);
             }
+        }
 
-            let body_text = resp
-                .text()
-                .map_err(|e| anyhow!("Failed to read response body for '{}': {}", title, e))?;
+        Ok(linked_titles)
+    }
 
-            let wiki_resp: WikiResponse = serde_json::from_str(&body_text)
-                .map_err(|e| anyhow!("Failed to decode JSON for page '{}': {}", title, e))?;
+    /// Run a links query, following continuations, and collect the titles
+    /// that pass the filter.
+    fn query_linked_titles(
+        &self,
+        title: &str,
+        mut params: HashMap<String, String>,
+    ) -> anyhow::Result<Vec<String>> {
+        let mut linked_titles = Vec::new();
+
+        loop {
+            let wiki_resp = self.query(title, &params)?;
 
             for page in wiki_resp.query.pages.values() {
                 if let Some(links) = &page.links {
                     linked_titles.extend(
                         links
                             .iter()
-                            .filter(|link| link.ns == 0)
+                            .filter(|link| self.filter.allows(link))
                             .map(|link| link.title.clone()),
                     );
                 }
@@ -149,24 +356,53 @@ impl WikipediaCrawler {
         Ok(linked_titles)
     }
 
-    fn get_path(
-        start_title: &str,
-        parents: &HashMap<String, String>,
-    ) -> anyhow::Result<Vec<String>> {
-        let mut path = vec![KEVIN_BACON.to_string()];
+    /// Fetch the wikitext source of the article with the given title
+    fn query_wikitext(&self, title: &str) -> anyhow::Result<String> {
+        let wiki_resp = self.query(title, &wikitext_params(title))?;
+        Ok(wiki_resp
+            .query
+            .pages
+            .into_values()
+            .filter_map(|page| page.revisions)
+            .flatten()
+            .map(|revision| revision.slots.main.content)
+            .collect())
+    }
 
-        while let Some(last) = path.last() {
-            if last == start_title {
-                break;
-            }
+    /// Send one query to the API and decode the response
+    #[allow(clippy::uninlined_format_args)]
+    fn query(&self, title: &str, params: &HashMap<String, String>) -> anyhow::Result<WikiResponse> {
+        let client = self.client.as_ref().context("Crawler has no http client")?;
+        let resp = client
+            .get(API_URL)
+            .query(params)
+            .send()
+            .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
 
-            let parent = parents
-                .get(last)
-                .context(format!("Parent of {last} should be present in parents map"))?;
-            path.push(parent.to_string());
+        if !resp.status().is_success() {
+            return Err(anyhow!("HTTP error {} for page '{}'", resp.status(), title));
         }
 
-        path.reverse();
-        Ok(path)
+        let body_text = resp
+            .text()
+            .map_err(|e| anyhow!("Failed to read response body for '{}': {}", title, e))?;
+
+        serde_json::from_str(&body_text)
+            .map_err(|e| anyhow!("Failed to decode JSON for page '{}': {}", title, e))
+    }
+
+    fn emit(&self, event: CrawlEvent) {
+        if let Some(on_event) = &self.on_event {
+            on_event(event);
+        }
+    }
+
+    fn result(&self, path: Vec<String>) -> CrawlResult {
+        CrawlResult {
+            path,
+            filter: self.filter.clone(),
+            link_mode: self.link_mode,
+            shortest: true,
+        }
     }
 }
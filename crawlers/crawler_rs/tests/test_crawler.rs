@@ -1,7 +1,9 @@
-use std::sync::LazyLock;
-
+use crawler_common::{Checkpoint, CrawlEvent, InMemoryLinks};
 use crawler_rs::WikipediaCrawler;
-use std::sync::LazyLock;
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, LazyLock, Mutex},
+};
 use titles::{
     AMANDA_CLAYTON, CITY_ON_A_HILL, CLINT_EASTWOOD, FOOTLOOSE, FRIDAY_THE_13TH,
     GINETTA_GT5_CHALLENGE, GRAN_TURISMO_5, GRAN_TURISMO_5_PROLOGUE, GT5, HERBERT_ROSS, KEVIN_BACON,
@@ -12,13 +14,13 @@ static CRAWLER: LazyLock<WikipediaCrawler> = LazyLock::new(|| WikipediaCrawler::
 
 #[test]
 fn starting_at_kevin_bacon() {
-    assert_eq!(CRAWLER.crawl(KEVIN_BACON).unwrap(), vec![KEVIN_BACON]);
+    assert_eq!(CRAWLER.crawl(KEVIN_BACON).unwrap().path, vec![KEVIN_BACON]);
 }
 
 #[test]
 fn one_hop_1() {
     assert_eq!(
-        CRAWLER.crawl(FOOTLOOSE).unwrap(),
+        CRAWLER.crawl(FOOTLOOSE).unwrap().path,
         vec![FOOTLOOSE, KEVIN_BACON]
     );
 }
@@ -26,7 +28,7 @@ fn one_hop_1() {
 #[test]
 fn one_hop_2() {
     assert_eq!(
-        CRAWLER.crawl(FRIDAY_THE_13TH).unwrap(),
+        CRAWLER.crawl(FRIDAY_THE_13TH).unwrap().path,
         vec![FRIDAY_THE_13TH, KEVIN_BACON]
     );
 }
@@ -34,7 +36,7 @@ fn one_hop_2() {
 #[test]
 fn one_hop_3() {
     assert_eq!(
-        CRAWLER.crawl(CITY_ON_A_HILL).unwrap(),
+        CRAWLER.crawl(CITY_ON_A_HILL).unwrap().path,
         vec![CITY_ON_A_HILL, KEVIN_BACON]
     );
 }
@@ -42,7 +44,7 @@ fn one_hop_3() {
 #[test]
 fn two_hops_1() {
     assert_eq!(
-        CRAWLER.crawl(AMANDA_CLAYTON).unwrap(),
+        CRAWLER.crawl(AMANDA_CLAYTON).unwrap().path,
         vec![AMANDA_CLAYTON, CITY_ON_A_HILL, KEVIN_BACON]
     );
 }
@@ -53,7 +55,7 @@ fn two_hops_2() {
     // Runs in 8.5-9s
 
     assert_eq!(
-        CRAWLER.crawl(HERBERT_ROSS).unwrap(),
+        CRAWLER.crawl(HERBERT_ROSS).unwrap().path,
         vec![HERBERT_ROSS, CLINT_EASTWOOD, KEVIN_BACON]
     );
 }
@@ -64,7 +66,7 @@ fn three_hops() {
     // Runs in 6-6.5s
 
     assert_eq!(
-        CRAWLER.crawl(THE_BET).unwrap(),
+        CRAWLER.crawl(THE_BET).unwrap().path,
         vec![THE_BET, AMANDA_CLAYTON, CITY_ON_A_HILL, KEVIN_BACON]
     );
 }
@@ -80,3 +82,82 @@ fn get_linked_titles() {
         ]
     );
 }
+
+#[test]
+fn crawl_events() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    let crawler = WikipediaCrawler::new()
+        .unwrap()
+        .with_event_handler(move |event| recorded.lock().unwrap().push(event));
+
+    crawler.crawl(FOOTLOOSE).unwrap();
+
+    let events = events.lock().unwrap();
+    assert!(matches!(
+        &events[0],
+        CrawlEvent::PageFetched { title, depth: 0, .. } if title == FOOTLOOSE
+    ));
+    assert_eq!(events.last(), Some(&CrawlEvent::TargetFound { depth: 1 }));
+}
+
+#[test]
+fn in_memory_shortest_path() {
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["Long", "Short"]),
+        ("Long", vec!["Longer"]),
+        ("Longer", vec![KEVIN_BACON]),
+        ("Short", vec![KEVIN_BACON]),
+    ]);
+    let crawler = WikipediaCrawler::from_link_source(Arc::new(links));
+
+    assert_eq!(
+        crawler.crawl("Start").unwrap().path,
+        vec!["Start", "Short", KEVIN_BACON]
+    );
+}
+
+#[test]
+fn in_memory_other_target() {
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["Middle", KEVIN_BACON]),
+        ("Middle", vec!["Goal"]),
+    ]);
+    let crawler = WikipediaCrawler::from_link_source(Arc::new(links)).with_target("Goal");
+
+    assert_eq!(
+        crawler.crawl("Start").unwrap().path,
+        vec!["Start", "Middle", "Goal"]
+    );
+    assert_eq!(crawler.crawl("Goal").unwrap().path, vec!["Goal"]);
+}
+
+#[test]
+fn resumes_from_checkpoint() {
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["A", "B"]),
+        ("A", vec!["C"]),
+        ("B", vec!["D"]),
+        ("D", vec![KEVIN_BACON]),
+    ]);
+    let dir = tempfile::tempdir().unwrap();
+    let checkpoint = dir.path().join("crawl.json");
+    let crawler = WikipediaCrawler::from_link_source(Arc::new(links)).with_checkpoint(&checkpoint);
+    // Stop the crawl part way through depth 2, as if the process were killed
+    let killed = crawler.clone().with_event_handler(|event| {
+        if matches!(event, CrawlEvent::PageFetched { depth: 2, .. }) {
+            panic!("killed");
+        }
+    });
+
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| killed.crawl("Start"))).is_err());
+    let saved = Checkpoint::load(&checkpoint).unwrap();
+    assert_eq!(saved.depth, 2);
+    assert_eq!(saved.frontier, ["C", "D"]);
+
+    assert_eq!(
+        crawler.resume(saved).unwrap().path,
+        crawler.crawl("Start").unwrap().path
+    );
+    assert!(!checkpoint.exists());
+}
@@ -1,10 +1,18 @@
 use anyhow::{anyhow, Context};
+use crawler_common::{
+    body_links, links_params, template_links_params, wikitext_params, Checkpoint, CrawlEvent,
+    CrawlResult, EventHandler, LinkFilter, LinkMode, LinkSource, ShardedParents, TitleId, API_URL,
+};
 use dotenvy::dotenv;
 use reqwest::Client;
 use std::{
-    collections::{HashMap, VecDeque},
-    env,
-    sync::{Arc, Mutex},
+    collections::{HashMap, HashSet, VecDeque},
+    env, fs,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
 };
 use titles::KEVIN_BACON;
 use tokio::{
@@ -13,14 +21,32 @@ use tokio::{
 };
 use wiki_response::WikiResponse;
 
+/// Tells workers to stop when dropped
+struct StopOnDrop(watch::Sender<bool>);
+
+impl Drop for StopOnDrop {
+    fn drop(&mut self) {
+        let _ = self.0.send(true);
+    }
+}
+
 #[derive(Clone)]
 pub struct WikipediaCrawler {
-    client: Client,
+    /// Absent when links come from a `LinkSource`
+    client: Option<Client>,
+    /// At least one, or nothing would take titles off the frontier
     worker_count: u8,
+    filter: LinkFilter,
+    link_mode: LinkMode,
+    target: String,
+    on_event: Option<EventHandler>,
+    source: Option<Arc<dyn LinkSource>>,
+    checkpoint: Option<PathBuf>,
 }
 
 impl WikipediaCrawler {
-    /// Create a new instance of an object to search pages
+    /// Create a new instance of an object to search pages with
+    /// `worker_count` workers, or one if zero
     ///
     /// # Errors
     ///
@@ -40,98 +66,245 @@ impl WikipediaCrawler {
             .context("Error creating http client")?;
 
         Ok(Self {
-            client,
-            worker_count,
+            client: Some(client),
+            worker_count: worker_count.max(1),
+            filter: LinkFilter::default(),
+            link_mode: LinkMode::default(),
+            target: KEVIN_BACON.to_string(),
+            on_event: None,
+            source: None,
+            checkpoint: None,
         })
     }
 
+    /// Create a crawler that looks up links in the given source instead of
+    /// the Wikipedia API, with `worker_count` workers or one if zero
+    #[must_use]
+    pub fn from_link_source(worker_count: u8, source: Arc<dyn LinkSource>) -> Self {
+        Self {
+            client: None,
+            worker_count: worker_count.max(1),
+            filter: LinkFilter::default(),
+            link_mode: LinkMode::default(),
+            target: KEVIN_BACON.to_string(),
+            on_event: None,
+            source: Some(source),
+            checkpoint: None,
+        }
+    }
+
+    /// Restrict which linked titles the crawl follows
+    #[must_use]
+    pub fn with_filter(mut self, filter: LinkFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// The filter applied to linked titles
+    #[must_use]
+    pub fn filter(&self) -> &LinkFilter {
+        &self.filter
+    }
+
+    /// Choose whether links from transcluded templates are followed
+    #[must_use]
+    pub fn with_link_mode(mut self, link_mode: LinkMode) -> Self {
+        self.link_mode = link_mode;
+        self
+    }
+
+    /// Which links of each article are followed
+    #[must_use]
+    pub fn link_mode(&self) -> LinkMode {
+        self.link_mode
+    }
+
+    /// Search for a path to `target` instead of Kevin Bacon
+    #[must_use]
+    pub fn with_target(mut self, target: &str) -> Self {
+        self.target = target.to_string();
+        self
+    }
+
+    /// The article searched for
+    #[must_use]
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Save the state of every crawl to `path` as each depth completes, so it
+    /// can be resumed with `resume` if stopped. The file is removed once the
+    /// crawl finishes.
+    #[must_use]
+    pub fn with_checkpoint(mut self, path: impl Into<PathBuf>) -> Self {
+        self.checkpoint = Some(path.into());
+        self
+    }
+
+    /// Report progress of every crawl to the given callback
+    #[must_use]
+    pub fn with_event_handler(
+        mut self,
+        on_event: impl Fn(CrawlEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_event = Some(Arc::new(on_event));
+        self
+    }
+
     /// Execute the main crawl process.
     ///
     /// # Errors
     ///
     /// This function errors if it fails to find a successful path after
     /// exhausting all found links.
+    pub async fn crawl(&self, start_title: &str) -> anyhow::Result<CrawlResult> {
+        if start_title == self.target {
+            return Ok(self.result(vec![self.target.clone()]));
+        }
+
+        self.search(Checkpoint::new(
+            start_title,
+            &self.target,
+            self.link_mode,
+            &self.filter,
+        ))
+        .await
+    }
+
+    /// Continue a crawl from a checkpoint saved by an earlier crawl with the
+    /// same target and link mode.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if locking the mutex guard fails, which says that a previous
-    /// mutex holder panicked while holding the mutex
-    pub async fn crawl(&self, start_title: &str) -> anyhow::Result<Vec<String>> {
-        if start_title == KEVIN_BACON {
-            return Ok(vec![KEVIN_BACON.to_string()]);
-        }
+    /// This function errors if the checkpoint is of a different crawl, or if
+    /// it fails to find a successful path after exhausting all found links.
+    pub async fn resume(&self, checkpoint: Checkpoint) -> anyhow::Result<CrawlResult> {
+        checkpoint.ensure_matches(&self.target, self.link_mode, &self.filter)?;
+        self.search(checkpoint).await
+    }
 
-        let frontier = Arc::new(Mutex::new(VecDeque::from([start_title.to_string()])));
-        let next_frontier = Arc::new(Mutex::new(VecDeque::new()));
+    async fn search(&self, checkpoint: Checkpoint) -> anyhow::Result<CrawlResult> {
+        let Checkpoint {
+            start,
+            depth,
+            frontier,
+            next_frontier,
+            parents,
+            pages_fetched,
+            ..
+        } = checkpoint;
+        let parents = Arc::new(ShardedParents::from_titles(&parents));
+        parents.intern(&start);
+        let frontier: VecDeque<TitleId> = frontier.iter().map(|t| parents.intern(t)).collect();
+        let next_frontier: VecDeque<TitleId> =
+            next_frontier.iter().map(|t| parents.intern(t)).collect();
+        let frontier = Arc::new(Mutex::new(frontier));
+        let next_frontier = Arc::new(Mutex::new(next_frontier));
 
         let (stop_tx, stop_rx) = watch::channel(false);
+        // Workers outlive this future, so stop them if the crawl is dropped
+        let _stop_on_drop = StopOnDrop(stop_tx.clone());
         let barrier = Arc::new(Barrier::new(self.worker_count as usize + 1));
 
-        let parents = Arc::new(Mutex::new(HashMap::new()));
+        let pages_fetched = Arc::new(AtomicUsize::new(pages_fetched));
+        let finished = Arc::new(AtomicBool::new(false));
 
-        for id in 0..self.worker_count as usize {
+        for _ in 0..self.worker_count {
             tokio::spawn(self.clone().worker(
-                id,
+                depth,
                 frontier.clone(),
                 next_frontier.clone(),
                 stop_tx.clone(),
                 stop_rx.clone(),
                 parents.clone(),
+                pages_fetched.clone(),
+                finished.clone(),
                 barrier.clone(),
             ));
         }
 
         // Wait for coordinator to exit
-        let Ok(Ok(())) =
-            tokio::spawn(
-                self.clone()
-                    .coordinator(frontier, next_frontier, stop_rx, barrier),
-            )
-            .await
+        let Ok(Ok(())) = tokio::spawn(self.clone().coordinator(
+            start.clone(),
+            depth,
+            frontier,
+            next_frontier,
+            stop_tx,
+            stop_rx,
+            parents.clone(),
+            pages_fetched,
+            finished,
+            barrier,
+        ))
+        .await
         else {
-            println!("Coordinator failed");
+            eprintln!("Coordinator failed");
             return Err(anyhow::Error::msg(""));
         };
 
-        println!("Crawl finished.");
-
-        let path = {
-            let p = parents.lock().unwrap();
-            Self::get_path(start_title, &p)
-        };
-        println!("{:?}", path.as_ref());
+        if let Some(checkpoint) = &self.checkpoint {
+            let _ = fs::remove_file(checkpoint);
+        }
 
-        path.map_err(|_| anyhow::Error::msg("Could not find path to Kevin Bacon"))
+        let path = parents.path(&start, &self.target);
+        path.map(|path| self.result(path))
+            .ok_or_else(|| anyhow!("Could not find path to {}", self.target))
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn coordinator(
         self,
-        frontier: Arc<Mutex<VecDeque<String>>>,
-        next_frontier: Arc<Mutex<VecDeque<String>>>,
+        start: String,
+        depth: usize,
+        frontier: Arc<Mutex<VecDeque<TitleId>>>,
+        next_frontier: Arc<Mutex<VecDeque<TitleId>>>,
+        stop_tx: watch::Sender<bool>,
         stop_rx: watch::Receiver<bool>,
+        parents: Arc<ShardedParents>,
+        pages_fetched: Arc<AtomicUsize>,
+        finished: Arc<AtomicBool>,
         barrier: Arc<Barrier>,
     ) -> anyhow::Result<()> {
-        let mut round = 0;
+        let mut round = depth;
 
         loop {
             barrier.wait().await; // Wait for workers for the round
             round += 1;
-            println!("[Coordinator] End of round {round}");
 
             if *stop_rx.borrow() {
-                println!("[Coordinator] Stopping");
+                finished.store(true, Ordering::Relaxed);
+                barrier.wait().await; // Release workers so they can exit
                 return Ok(());
             }
 
             {
-                let mut nf = next_frontier.lock().unwrap();
-                println!("[Coordinator] Titles collected: {}", nf.len());
-                let mut f = frontier.lock().unwrap();
+                let mut nf = lock(&next_frontier);
+                self.emit(CrawlEvent::DepthCompleted {
+                    depth: round - 1,
+                    pages_fetched: pages_fetched.load(Ordering::Relaxed),
+                    frontier_size: nf.len(),
+                });
+                let mut f = lock(&frontier);
 
                 f.extend(nf.drain(..));
+                if !f.is_empty() {
+                    self.save_checkpoint(
+                        &start,
+                        round,
+                        &f,
+                        &parents,
+                        pages_fetched.load(Ordering::Relaxed),
+                    );
+                }
+            }
+
+            if lock(&frontier).is_empty() {
+                let _ = stop_tx.send(true);
+                finished.store(true, Ordering::Relaxed);
+                barrier.wait().await; // Release workers so they can exit
+                return Ok(());
             }
 
-            println!("[Coordinator] Starting next round");
             barrier.wait().await; // Start next round
         }
     }
@@ -139,61 +312,109 @@ impl WikipediaCrawler {
     #[allow(clippy::too_many_arguments)]
     async fn worker(
         self,
-        id: usize,
-        frontier: Arc<Mutex<VecDeque<String>>>,
-        next_frontier: Arc<Mutex<VecDeque<String>>>,
+        mut depth: usize,
+        frontier: Arc<Mutex<VecDeque<TitleId>>>,
+        next_frontier: Arc<Mutex<VecDeque<TitleId>>>,
         stop_tx: watch::Sender<bool>,
-        stop_rx: watch::Receiver<bool>,
-        parents: Arc<Mutex<HashMap<String, String>>>,
+        mut stop_rx: watch::Receiver<bool>,
+        parents: Arc<ShardedParents>,
+        pages_fetched: Arc<AtomicUsize>,
+        finished: Arc<AtomicBool>,
         barrier: Arc<Barrier>,
     ) {
         loop {
             'this_round: loop {
                 if *stop_rx.borrow() {
-                    println!("[Worker {id}] stopping");
                     break 'this_round;
                 }
 
-                let Some(cur_title) = frontier.lock().unwrap().pop_front() else {
+                let Some(cur_id) = lock(&frontier).pop_front() else {
                     break 'this_round;
                 };
+                let cur_title = parents.title(cur_id);
 
-                let linked_titles = match self.get_linked_titles(&cur_title).await {
+                // Drop the fetch as soon as the target is found by another
+                // worker, rather than waiting on it
+                let fetched = tokio::select! {
+                    fetched = self.get_linked_titles(&cur_title) => fetched,
+                    _ = stop_rx.wait_for(|&stop| stop) => {
+                        break 'this_round;
+                    }
+                };
+                let linked_titles = match fetched {
                     Ok(linked_titles) => linked_titles,
                     Err(e) => {
-                        println!(
-                            "[Worker {id}] Failed to get linked titles for page '{cur_title}': {e}"
-                        );
+                        self.emit(CrawlEvent::FetchFailed {
+                            title: cur_title.to_string(),
+                            depth,
+                            error: e.to_string(),
+                        });
                         continue;
                     }
                 };
+                pages_fetched.fetch_add(1, Ordering::Relaxed);
 
-                println!(
-                    "[Worker {id}] Got linked titles for page '{cur_title}'; length: {}",
-                    linked_titles.len()
-                );
+                self.emit(CrawlEvent::PageFetched {
+                    title: cur_title.to_string(),
+                    depth,
+                    link_count: linked_titles.len(),
+                });
 
+                let mut found = Vec::new();
                 for linked_title in linked_titles {
-                    {
-                        let mut p = parents.lock().unwrap();
-                        if p.contains_key(&linked_title) {
-                            continue;
-                        }
-                        p.insert(linked_title.to_string(), cur_title.to_string());
-                    }
+                    let Some(linked_id) = parents.insert(&linked_title, cur_id) else {
+                        continue;
+                    };
 
-                    if linked_title == KEVIN_BACON {
-                        println!("[Worker {id}] Found target");
+                    if linked_title == self.target {
+                        self.emit(CrawlEvent::TargetFound { depth: depth + 1 });
                         let _ = stop_tx.send(true);
                         break 'this_round;
                     }
 
-                    next_frontier.lock().unwrap().push_back(linked_title);
+                    found.push(linked_id);
                 }
+                lock(&next_frontier).extend(found);
             }
 
             barrier.wait().await; // round finished
             barrier.wait().await; // wait for coordinator to swap queues
+            if finished.load(Ordering::Relaxed) {
+                break;
+            }
+            depth += 1;
+        }
+    }
+
+    /// Save the crawl's state between depths if checkpoints are enabled. The
+    /// crawl carries on if saving fails, as it can still finish without one.
+    fn save_checkpoint(
+        &self,
+        start: &str,
+        depth: usize,
+        frontier: &VecDeque<TitleId>,
+        parents: &ShardedParents,
+        pages_fetched: usize,
+    ) {
+        let Some(path) = &self.checkpoint else {
+            return;
+        };
+        let checkpoint = Checkpoint {
+            start: start.to_string(),
+            target: self.target.clone(),
+            link_mode: self.link_mode,
+            filter: self.filter.clone(),
+            depth,
+            frontier: frontier
+                .iter()
+                .map(|&id| parents.title(id).to_string())
+                .collect(),
+            next_frontier: Vec::new(),
+            parents: parents.to_titles(),
+            pages_fetched,
+        };
+        if let Err(e) = checkpoint.save(path) {
+            eprintln!("Failed to save checkpoint: {e:#}");
         }
     }
 
@@ -205,45 +426,54 @@ impl WikipediaCrawler {
     ///   - Any http request fails
     ///   - Any http response status is not within 200-299
     ///   - Returned JSON is invalid or otherwise not decodable
+    ///   - The crawler's link source fails to look up the article
     pub async fn get_linked_titles(&self, title: &str) -> anyhow::Result<Vec<String>> {
-        let url = "https://en.wikipedia.org/w/api.php";
-        let mut params = HashMap::from([
-            ("action".to_string(), "query".to_string()),
-            ("titles".to_string(), title.to_string()),
-            ("prop".to_string(), "links".to_string()),
-            ("pllimit".to_string(), "max".to_string()),
-            ("format".to_string(), "json".to_string()),
-        ]);
-
-        let mut linked_titles = Vec::new();
+        if let Some(source) = &self.source {
+            let mut linked_titles = source.linked_titles(title).await?;
+            linked_titles.retain(|linked_title| self.filter.allows_source_title(linked_title));
+            return Ok(linked_titles);
+        }
 
-        loop {
-            let resp = self
-                .client
-                .get(url)
-                .query(&params)
-                .send()
-                .await
-                .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
-
-            if !resp.status().is_success() {
-                return Err(anyhow!("HTTP error {} for page '{}'", resp.status(), title));
+        let mut linked_titles = self
+            .query_linked_titles(title, links_params(title, &self.filter))
+            .await?;
+
+        if self.link_mode == LinkMode::Body {
+            let template_titles: HashSet<String> = self
+                .query_linked_titles(title, template_links_params(title, &self.filter))
+                .await?
+                .into_iter()
+                .collect();
+            if linked_titles
+                .iter()
+                .any(|linked_title| template_titles.contains(linked_title))
+            {
+                let wikitext = self.query_wikitext(title).await?;
+                linked_titles = body_links(linked_titles, &template_titles, &wikitext);
             }
+        }
 
-            let body_text = resp
-                .text()
-                .await
-                .map_err(|e| anyhow!("Failed to read response body for '{}': {}", title, e))?;
+        Ok(linked_titles)
+    }
 
-            let wiki_resp: WikiResponse = serde_json::from_str(&body_text)
-                .map_err(|e| anyhow!("Failed to decode JSON for page '{}': {}", title, e))?;
+    /// Run a links query, following continuations, and collect the titles
+    /// that pass the filter.
+    async fn query_linked_titles(
+        &self,
+        title: &str,
+        mut params: HashMap<String, String>,
+    ) -> anyhow::Result<Vec<String>> {
+        let mut linked_titles = Vec::new();
+
+        loop {
+            let wiki_resp = self.query(title, &params).await?;
 
             for page in wiki_resp.query.pages.values() {
                 if let Some(links) = &page.links {
                     linked_titles.extend(
                         links
                             .iter()
-                            .filter(|link| link.ns == 0)
+                            .filter(|link| self.filter.allows(link))
                             .map(|link| link.title.clone()),
                     );
                 }
@@ -260,24 +490,66 @@ impl WikipediaCrawler {
         Ok(linked_titles)
     }
 
-    fn get_path(
-        start_title: &str,
-        parents: &HashMap<String, String>,
-    ) -> anyhow::Result<Vec<String>> {
-        let mut path = vec![KEVIN_BACON.to_string()];
+    /// Fetch the wikitext source of the article with the given title
+    async fn query_wikitext(&self, title: &str) -> anyhow::Result<String> {
+        let wiki_resp = self.query(title, &wikitext_params(title)).await?;
+        Ok(wiki_resp
+            .query
+            .pages
+            .into_values()
+            .filter_map(|page| page.revisions)
+            .flatten()
+            .map(|revision| revision.slots.main.content)
+            .collect())
+    }
 
-        while let Some(last) = path.last() {
-            if last == start_title {
-                break;
-            }
+    /// Send one query to the API and decode the response
+    #[allow(clippy::uninlined_format_args)]
+    async fn query(
+        &self,
+        title: &str,
+        params: &HashMap<String, String>,
+    ) -> anyhow::Result<WikiResponse> {
+        let client = self.client.as_ref().context("Crawler has no http client")?;
+        let resp = client
+            .get(API_URL)
+            .query(params)
+            .send()
+            .await
+            .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
 
-            let parent = parents
-                .get(last)
-                .context(format!("Parent of {last} should be present in parents map"))?;
-            path.push(parent.to_string());
+        if !resp.status().is_success() {
+            return Err(anyhow!("HTTP error {} for page '{}'", resp.status(), title));
         }
 
-        path.reverse();
-        Ok(path)
+        let body_text = resp
+            .text()
+            .await
+            .map_err(|e| anyhow!("Failed to read response body for '{}': {}", title, e))?;
+
+        serde_json::from_str(&body_text)
+            .map_err(|e| anyhow!("Failed to decode JSON for page '{}': {}", title, e))
+    }
+
+    fn emit(&self, event: CrawlEvent) {
+        if let Some(on_event) = &self.on_event {
+            on_event(event);
+        }
     }
+
+    fn result(&self, path: Vec<String>) -> CrawlResult {
+        CrawlResult {
+            path,
+            filter: self.filter.clone(),
+            link_mode: self.link_mode,
+            shortest: true,
+        }
+    }
+}
+
+/// Lock a queue shared by the workers. A worker that panicked while holding
+/// it can only have left it without a title it was adding or taking, so the
+/// crawl carries on.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
 }
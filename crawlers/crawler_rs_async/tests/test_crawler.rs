@@ -1,22 +1,30 @@
+use crawler_common::{Checkpoint, CrawlEvent, InMemoryLinks, Latency, Stalls};
 use crawler_rs_async::WikipediaCrawler;
-use std::sync::LazyLock;
+use std::{
+    sync::{Arc, LazyLock, Mutex},
+    time::{Duration, Instant},
+};
 use titles::{
     AMANDA_CLAYTON, CITY_ON_A_HILL, CLINT_EASTWOOD, CURTIS_HANSON, FOOTLOOSE, FRIDAY_THE_13TH,
     GINETTA_GT5_CHALLENGE, GRAN_TURISMO_5, GRAN_TURISMO_5_PROLOGUE, GT5, HERBERT_ROSS, KEVIN_BACON,
     THE_BET,
 };
+use tokio::time::timeout;
 
 static CRAWLER: LazyLock<WikipediaCrawler> = LazyLock::new(|| WikipediaCrawler::new(5).unwrap());
 
 #[tokio::test]
 async fn starting_at_kevin_bacon() {
-    assert_eq!(CRAWLER.crawl(KEVIN_BACON).await.unwrap(), vec![KEVIN_BACON]);
+    assert_eq!(
+        CRAWLER.crawl(KEVIN_BACON).await.unwrap().path,
+        vec![KEVIN_BACON]
+    );
 }
 
 #[tokio::test]
 async fn one_hop_1() {
     assert_eq!(
-        CRAWLER.crawl(FOOTLOOSE).await.unwrap(),
+        CRAWLER.crawl(FOOTLOOSE).await.unwrap().path,
         vec![FOOTLOOSE, KEVIN_BACON]
     );
 }
@@ -24,7 +32,7 @@ async fn one_hop_1() {
 #[tokio::test]
 async fn one_hop_2() {
     assert_eq!(
-        CRAWLER.crawl(FRIDAY_THE_13TH).await.unwrap(),
+        CRAWLER.crawl(FRIDAY_THE_13TH).await.unwrap().path,
         vec![FRIDAY_THE_13TH, KEVIN_BACON]
     );
 }
@@ -32,7 +40,7 @@ async fn one_hop_2() {
 #[tokio::test]
 async fn one_hop_3() {
     assert_eq!(
-        CRAWLER.crawl(CITY_ON_A_HILL).await.unwrap(),
+        CRAWLER.crawl(CITY_ON_A_HILL).await.unwrap().path,
         vec![CITY_ON_A_HILL, KEVIN_BACON]
     );
 }
@@ -47,7 +55,7 @@ async fn two_hops_1() {
     // Must correctly handle ordering of new titles added to the queue to
     // ensure we find the shortest path, which is length 3.
     assert_eq!(
-        CRAWLER.crawl(AMANDA_CLAYTON).await.unwrap(),
+        CRAWLER.crawl(AMANDA_CLAYTON).await.unwrap().path,
         vec![AMANDA_CLAYTON, CITY_ON_A_HILL, KEVIN_BACON]
     );
 }
@@ -58,7 +66,7 @@ async fn two_hops_2() {
     // 1 worker  - 8.5-9s
     // 5 workers - 2-2.5s
 
-    let result = CRAWLER.crawl(HERBERT_ROSS).await.unwrap();
+    let result = CRAWLER.crawl(HERBERT_ROSS).await.unwrap().path;
     // This implementation seems to be sometimes ending with Curtis Hanson as
     // the second name in the final path depending on the number of workers
     assert!(
@@ -74,7 +82,7 @@ async fn three_hops() {
     // 5 workers - 1.5-2s
 
     assert_eq!(
-        CRAWLER.crawl(THE_BET).await.unwrap(),
+        CRAWLER.crawl(THE_BET).await.unwrap().path,
         vec![THE_BET, AMANDA_CLAYTON, CITY_ON_A_HILL, KEVIN_BACON]
     );
 }
@@ -90,3 +98,154 @@ async fn get_linked_titles() {
         ]
     );
 }
+
+#[tokio::test]
+async fn crawl_events() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    let crawler = WikipediaCrawler::new(5)
+        .unwrap()
+        .with_event_handler(move |event| recorded.lock().unwrap().push(event));
+
+    crawler.crawl(FOOTLOOSE).await.unwrap();
+
+    let events = events.lock().unwrap();
+    assert!(events.iter().any(|event| matches!(
+        event,
+        CrawlEvent::PageFetched { title, depth: 0, .. } if title == FOOTLOOSE
+    )));
+    assert!(events.contains(&CrawlEvent::TargetFound { depth: 1 }));
+}
+
+#[tokio::test]
+async fn in_memory_shortest_path() {
+    // "Long" is expanded before "Short", but the path through "Short" is
+    // found first at depth 2, so it must win.
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["Long", "Short"]),
+        ("Long", vec!["Longer"]),
+        ("Longer", vec![KEVIN_BACON]),
+        ("Short", vec![KEVIN_BACON]),
+    ]);
+    let crawler = WikipediaCrawler::from_link_source(3, Arc::new(links));
+
+    assert_eq!(
+        crawler.crawl("Start").await.unwrap().path,
+        vec!["Start", "Short", KEVIN_BACON]
+    );
+}
+
+#[tokio::test]
+async fn zero_workers_still_crawl() {
+    let links =
+        InMemoryLinks::from_edges([("Start", vec!["Middle"]), ("Middle", vec![KEVIN_BACON])]);
+    let crawler = WikipediaCrawler::from_link_source(0, Arc::new(links));
+
+    let result = timeout(Duration::from_secs(5), crawler.crawl("Start")).await;
+    assert_eq!(
+        result.expect("the crawl should finish").unwrap().path,
+        vec!["Start", "Middle", KEVIN_BACON]
+    );
+}
+
+#[tokio::test]
+async fn in_memory_unreachable() {
+    let links = InMemoryLinks::from_edges([("Start", vec!["Dead end"])]);
+    let crawler = WikipediaCrawler::from_link_source(3, Arc::new(links));
+
+    assert!(crawler.crawl("Start").await.is_err());
+}
+
+#[tokio::test]
+async fn in_memory_other_target() {
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["Middle", KEVIN_BACON]),
+        ("Middle", vec!["Goal"]),
+    ]);
+    let crawler = WikipediaCrawler::from_link_source(3, Arc::new(links)).with_target("Goal");
+
+    assert_eq!(
+        crawler.crawl("Start").await.unwrap().path,
+        vec!["Start", "Middle", "Goal"]
+    );
+    assert_eq!(crawler.crawl("Goal").await.unwrap().path, vec!["Goal"]);
+}
+
+#[tokio::test]
+async fn resumes_from_checkpoint() {
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["A", "B"]),
+        ("A", vec!["C"]),
+        ("B", vec!["D"]),
+        ("D", vec![KEVIN_BACON]),
+    ]);
+    let dir = tempfile::tempdir().unwrap();
+    let checkpoint = dir.path().join("crawl.json");
+    let stalls = Stalls::new(links.clone(), "D");
+    let killed =
+        WikipediaCrawler::from_link_source(3, Arc::new(stalls)).with_checkpoint(&checkpoint);
+    let crawler =
+        WikipediaCrawler::from_link_source(3, Arc::new(links)).with_checkpoint(&checkpoint);
+
+    // Stop the crawl stuck at depth 2, as if the process were killed
+    assert!(timeout(Duration::from_millis(500), killed.crawl("Start"))
+        .await
+        .is_err());
+    let saved = Checkpoint::load(&checkpoint).unwrap();
+    assert_eq!(saved.depth, 2);
+
+    assert_eq!(
+        crawler.resume(saved).await.unwrap().path,
+        crawler.crawl("Start").await.unwrap().path
+    );
+    assert!(!checkpoint.exists());
+}
+
+#[tokio::test]
+async fn abandons_fetches_once_target_found() {
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["Near", "Stuck"]),
+        ("Near", vec![KEVIN_BACON]),
+    ]);
+    // "Stuck" is being fetched when "Near" is found to link to the target
+    let latency = Latency::of_titles(
+        links,
+        [
+            ("Near", Duration::from_millis(50)),
+            ("Stuck", Duration::from_mins(1)),
+        ],
+    );
+    let crawler = WikipediaCrawler::from_link_source(3, Arc::new(latency));
+
+    let result = timeout(Duration::from_secs(2), crawler.crawl("Start"))
+        .await
+        .expect("crawl should not wait on the stuck fetch");
+
+    assert_eq!(result.unwrap().path, vec!["Start", "Near", KEVIN_BACON]);
+}
+
+#[tokio::test]
+async fn shortest_path_with_slow_pages() {
+    // The path through "Slow" is shortest, though "Fast" and its links are
+    // looked up long before "Slow" is.
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["Fast", "Slow", "Stuck"]),
+        ("Fast", vec!["Deep"]),
+        ("Deep", vec![KEVIN_BACON]),
+        ("Slow", vec![KEVIN_BACON]),
+    ]);
+    let latency = Latency::of_titles(
+        links,
+        [
+            ("Slow", Duration::from_millis(200)),
+            ("Stuck", Duration::from_mins(1)),
+        ],
+    );
+    let crawler = WikipediaCrawler::from_link_source(3, Arc::new(latency));
+
+    let started = Instant::now();
+    let path = crawler.crawl("Start").await.unwrap().path;
+
+    assert_eq!(path, vec!["Start", "Slow", KEVIN_BACON]);
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
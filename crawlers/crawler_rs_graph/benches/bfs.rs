@@ -0,0 +1,134 @@
+//! Time to search a synthetic graph of a few million titles whose links
+//! follow a power law, as Wikipedia's do: most titles have a handful, a few
+//! have thousands, and links point mostly at the titles with the most.
+//! Compares the direction-optimizing parallel search with a plain queue.
+//!
+//! Run with `cargo bench --bench bfs`. Rayon uses a thread per core; set
+//! `RAYON_NUM_THREADS` to compare thread counts.
+
+use crawler_common::TitleId;
+use crawler_rs_graph::{shortest_path, CompactGraph};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::collections::VecDeque;
+
+/// Titles in the synthetic graph, besides one no title links to
+const TITLES: u64 = 2_000_000;
+/// Links from a title, at least
+const MIN_LINKS: u64 = 2;
+/// Link counts double this many times at most, each time with half the
+/// chance, so a title has up to `MIN_LINKS << MAX_DOUBLINGS` links
+const MAX_DOUBLINGS: u32 = 9;
+
+/// Random links, the same on every run. Link counts follow a power law, and
+/// each linked title is drawn as the product of three uniform fractions of
+/// the titles, so titles with low ids are linked to far more often.
+fn graph() -> CompactGraph {
+    let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move || {
+        // xorshift64
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed
+    };
+
+    let mut edges = Vec::new();
+    for from in 0..TITLES {
+        let links = MIN_LINKS << next().trailing_zeros().min(MAX_DOUBLINGS);
+        for _ in 0..links {
+            let to = (0..3).fold(TITLES, |to, _| to * (next() % TITLES) / TITLES);
+            edges.push((id(from), id(to)));
+        }
+    }
+    CompactGraph::from_edges(
+        (0..=TITLES).map(|i| format!("Synthetic article number {i}")),
+        &edges,
+    )
+}
+
+fn id(i: u64) -> TitleId {
+    TitleId::try_from(i).unwrap()
+}
+
+/// The last title a breadth first search from `start` reaches
+fn farthest(graph: &CompactGraph, start: TitleId) -> TitleId {
+    let mut found = vec![false; graph.len()];
+    found[start as usize] = true;
+    let mut queue = VecDeque::from([start]);
+    let mut last = start;
+    while let Some(parent) = queue.pop_front() {
+        last = parent;
+        for &id in graph.linked(parent) {
+            if !found[id as usize] {
+                found[id as usize] = true;
+                queue.push_back(id);
+            }
+        }
+    }
+    last
+}
+
+/// A breadth first search following the links out of one title at a time
+fn queue_path(graph: &CompactGraph, start: TitleId, target: TitleId) -> Option<Vec<TitleId>> {
+    let mut parents = vec![TitleId::MAX; graph.len()];
+    parents[start as usize] = start;
+    let mut queue = VecDeque::from([start]);
+    while let Some(parent) = queue.pop_front() {
+        for &id in graph.linked(parent) {
+            if parents[id as usize] != TitleId::MAX {
+                continue;
+            }
+            parents[id as usize] = parent;
+            if id == target {
+                let mut path = vec![id];
+                while *path.last().unwrap() != start {
+                    path.push(parents[*path.last().unwrap() as usize]);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            queue.push_back(id);
+        }
+    }
+    None
+}
+
+fn bfs(c: &mut Criterion) {
+    let graph = graph();
+    let allowed = vec![true; graph.len()];
+    eprintln!("{} titles, {} links", graph.len(), graph.link_count());
+
+    // The title reached last, and one no title links to, for which the
+    // whole graph is searched
+    let start = id(TITLES - 1);
+    let targets = [
+        ("farthest", farthest(&graph, start)),
+        ("unreachable", id(TITLES)),
+    ];
+    let mut group = c.benchmark_group("bfs");
+    group.sample_size(10);
+
+    for (name, target) in targets {
+        let parallel = shortest_path(&graph, start, target, &allowed, &|_| {});
+        let queue = queue_path(&graph, start, target);
+        assert_eq!(
+            parallel.map(|path| path.len()),
+            queue.map(|path| path.len())
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("direction_optimizing", name),
+            &target,
+            |b, &target| {
+                b.iter(|| shortest_path(&graph, start, target, &allowed, &|_| {}));
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("queue", name), &target, |b, &target| {
+            b.iter(|| queue_path(&graph, start, target));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bfs);
+criterion_main!(benches);
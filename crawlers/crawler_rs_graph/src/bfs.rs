@@ -0,0 +1,159 @@
+use crate::CompactGraph;
+use crawler_common::{CrawlEvent, TitleId};
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Parent of a title not yet found
+const UNVISITED: TitleId = TitleId::MAX;
+/// Switch to bottom-up steps once the links out of the frontier are more than
+/// this fraction of the links out of titles not yet found
+const ALPHA: usize = 14;
+/// Switch back to top-down steps once the frontier holds fewer than this
+/// fraction of all titles
+const BETA: usize = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    /// Follow the links out of every frontier title
+    TopDown,
+    /// Look for a frontier title among the titles linking to every title not
+    /// yet found
+    BottomUp,
+}
+
+/// Find a shortest path from `start` to `target` with a parallel breadth
+/// first search, returning the ids along it.
+///
+/// Each depth is expanded in whichever direction checks fewer links, as in
+/// Beamer's direction-optimizing search: top-down while the frontier is
+/// small, and bottom-up while it holds a large share of the graph, when most
+/// titles not yet found link to it and stop looking at their first link from
+/// it. Titles whose entry in `allowed` is false are never entered.
+///
+/// `on_event` is sent `DepthCompleted` as each depth is expanded, counting
+/// titles expanded as pages fetched, and `TargetFound` once the target is.
+pub fn shortest_path(
+    graph: &CompactGraph,
+    start: TitleId,
+    target: TitleId,
+    allowed: &[bool],
+    on_event: &dyn Fn(CrawlEvent),
+) -> Option<Vec<TitleId>> {
+    if start == target {
+        return Some(vec![start]);
+    }
+
+    let parents: Vec<AtomicU32> = (0..graph.len())
+        .into_par_iter()
+        .map(|_| AtomicU32::new(UNVISITED))
+        .collect();
+    parents[start as usize].store(start, Ordering::Relaxed);
+
+    let mut frontier = vec![start];
+    let mut direction = Direction::TopDown;
+    // Links out of titles not yet found, which a bottom-up step may check
+    let mut unexplored_links = graph.link_count();
+    let mut expanded = 0;
+    let mut depth = 0;
+
+    while !frontier.is_empty() {
+        let frontier_links: usize = frontier.par_iter().map(|&id| graph.linked(id).len()).sum();
+        direction = match direction {
+            Direction::TopDown if frontier_links > unexplored_links / ALPHA => Direction::BottomUp,
+            Direction::BottomUp if frontier.len() < graph.len() / BETA => Direction::TopDown,
+            direction => direction,
+        };
+
+        let next_frontier = match direction {
+            Direction::TopDown => top_down_step(graph, &frontier, allowed, &parents),
+            Direction::BottomUp => bottom_up_step(graph, &frontier, allowed, &parents),
+        };
+        unexplored_links = unexplored_links.saturating_sub(frontier_links);
+        expanded += frontier.len();
+        on_event(CrawlEvent::DepthCompleted {
+            depth,
+            pages_fetched: expanded,
+            frontier_size: next_frontier.len(),
+        });
+        depth += 1;
+
+        if parents[target as usize].load(Ordering::Relaxed) != UNVISITED {
+            on_event(CrawlEvent::TargetFound { depth });
+            return Some(path(&parents, target));
+        }
+        frontier = next_frontier;
+    }
+    None
+}
+
+/// Claim the unfound titles linked from the frontier
+fn top_down_step(
+    graph: &CompactGraph,
+    frontier: &[TitleId],
+    allowed: &[bool],
+    parents: &[AtomicU32],
+) -> Vec<TitleId> {
+    frontier
+        .par_iter()
+        .flat_map_iter(|&parent| {
+            graph.linked(parent).iter().copied().filter(move |&id| {
+                let slot = &parents[id as usize];
+                allowed[id as usize]
+                    && slot.load(Ordering::Relaxed) == UNVISITED
+                    && slot
+                        .compare_exchange(UNVISITED, parent, Ordering::Relaxed, Ordering::Relaxed)
+                        .is_ok()
+            })
+        })
+        .collect()
+}
+
+/// Find a frontier title linking to each unfound title. Every title is
+/// claimed by the one task looking at it, so no compare-and-swap is needed.
+fn bottom_up_step(
+    graph: &CompactGraph,
+    frontier: &[TitleId],
+    allowed: &[bool],
+    parents: &[AtomicU32],
+) -> Vec<TitleId> {
+    let mut in_frontier = vec![false; graph.len()];
+    for &id in frontier {
+        in_frontier[id as usize] = true;
+    }
+
+    graph
+        .ids()
+        .into_par_iter()
+        .filter(|&id| {
+            let slot = &parents[id as usize];
+            if !allowed[id as usize] || slot.load(Ordering::Relaxed) != UNVISITED {
+                return false;
+            }
+            let Some(&parent) = graph
+                .linking(id)
+                .iter()
+                .find(|&&parent| in_frontier[parent as usize])
+            else {
+                return false;
+            };
+            slot.store(parent, Ordering::Relaxed);
+            true
+        })
+        .collect()
+}
+
+/// Follow parents back from `target` to the title that is its own parent
+fn path(parents: &[AtomicU32], target: TitleId) -> Vec<TitleId> {
+    let mut path = vec![target];
+    let mut id = target;
+    loop {
+        let parent = parents[id as usize].load(Ordering::Relaxed);
+        if parent == id {
+            break;
+        }
+        path.push(parent);
+        id = parent;
+    }
+    path.reverse();
+    path
+}
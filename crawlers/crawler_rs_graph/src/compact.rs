@@ -0,0 +1,140 @@
+use crawler_common::{InMemoryLinks, TitleId, TitleInterner};
+use std::{ops::Range, sync::Arc};
+
+/// A link graph held as arrays of title ids, in compressed sparse row form:
+/// the links of a title are one slice of a single array. Links are kept both
+/// ways so a search can look from the titles it has found or towards them.
+#[derive(Debug, Default)]
+pub struct CompactGraph {
+    titles: TitleInterner,
+    /// Ids run from 0 to one less than this
+    end_id: TitleId,
+    /// Links from title `i` are `linked[linked_offsets[i]..linked_offsets[i + 1]]`
+    linked_offsets: Vec<usize>,
+    linked: Vec<TitleId>,
+    /// Links to title `i` are `linking[linking_offsets[i]..linking_offsets[i + 1]]`
+    linking_offsets: Vec<usize>,
+    linking: Vec<TitleId>,
+}
+
+impl CompactGraph {
+    /// Convert a graph of titles
+    #[must_use]
+    pub fn from_links(graph: &InMemoryLinks) -> Self {
+        let mut titles = TitleInterner::new();
+        let mut edges = Vec::new();
+        for (title, linked_titles) in graph.links() {
+            let id = titles.intern(title);
+            for linked_title in linked_titles {
+                edges.push((id, titles.intern(linked_title)));
+            }
+        }
+        Self::new(titles, &edges)
+    }
+
+    /// A graph of titles numbered from 0 in `titles`, linked by `(from, to)`
+    /// pairs of those numbers
+    ///
+    /// # Panics
+    ///
+    /// Panics if an edge refers to a title past the end of `titles`.
+    #[must_use]
+    pub fn from_edges(
+        titles: impl IntoIterator<Item = String>,
+        edges: &[(TitleId, TitleId)],
+    ) -> Self {
+        let mut interner = TitleInterner::new();
+        for title in titles {
+            interner.intern(&title);
+        }
+        Self::new(interner, edges)
+    }
+
+    fn new(titles: TitleInterner, edges: &[(TitleId, TitleId)]) -> Self {
+        let (linked_offsets, linked) = sparse_rows(titles.len(), edges.iter().copied());
+        let (linking_offsets, linking) =
+            sparse_rows(titles.len(), edges.iter().map(|&(from, to)| (to, from)));
+        Self {
+            end_id: TitleId::try_from(titles.len()).expect("title ids should fit in u32"),
+            titles,
+            linked_offsets,
+            linked,
+            linking_offsets,
+            linking,
+        }
+    }
+
+    /// Titles in the graph, whether or not they have links
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.titles.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.titles.is_empty()
+    }
+
+    #[must_use]
+    pub fn link_count(&self) -> usize {
+        self.linked.len()
+    }
+
+    /// Ids of every title in the graph
+    #[must_use]
+    pub fn ids(&self) -> Range<TitleId> {
+        0..self.end_id
+    }
+
+    /// The id of `title`, if it is in the graph
+    #[must_use]
+    pub fn id(&self, title: &str) -> Option<TitleId> {
+        self.titles.get(title)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `id` isn't in the graph.
+    #[must_use]
+    pub fn title(&self, id: TitleId) -> &Arc<str> {
+        self.titles.title(id)
+    }
+
+    /// Titles linked from `id`
+    #[must_use]
+    pub fn linked(&self, id: TitleId) -> &[TitleId] {
+        let id = id as usize;
+        &self.linked[self.linked_offsets[id]..self.linked_offsets[id + 1]]
+    }
+
+    /// Titles linking to `id`
+    #[must_use]
+    pub fn linking(&self, id: TitleId) -> &[TitleId] {
+        let id = id as usize;
+        &self.linking[self.linking_offsets[id]..self.linking_offsets[id + 1]]
+    }
+}
+
+/// Group `(row, value)` pairs by row, returning where each row starts and the
+/// values of all rows in order
+fn sparse_rows(
+    rows: usize,
+    pairs: impl Iterator<Item = (TitleId, TitleId)> + Clone,
+) -> (Vec<usize>, Vec<TitleId>) {
+    let mut offsets = vec![0; rows + 1];
+    for (row, _) in pairs.clone() {
+        offsets[row as usize + 1] += 1;
+    }
+    for row in 0..rows {
+        offsets[row + 1] += offsets[row];
+    }
+
+    let mut next = offsets.clone();
+    let mut values = vec![0; offsets[rows]];
+    for (row, value) in pairs {
+        let slot = &mut next[row as usize];
+        values[*slot] = value;
+        *slot += 1;
+    }
+    (offsets, values)
+}
@@ -0,0 +1,169 @@
+mod bfs;
+mod compact;
+
+pub use bfs::shortest_path;
+pub use compact::CompactGraph;
+
+use anyhow::anyhow;
+use crawler_common::{CrawlEvent, CrawlResult, EventHandler, InMemoryLinks, LinkFilter, LinkMode};
+use rayon::prelude::*;
+use std::sync::Arc;
+use titles::KEVIN_BACON;
+
+/// A crawler that searches a link graph held in memory as title ids, using
+/// every core, instead of fetching pages.
+///
+/// The graph holds whichever links it was built with, so the link mode is
+/// only reported in results.
+#[derive(Clone)]
+pub struct WikipediaCrawler {
+    graph: Arc<CompactGraph>,
+    filter: LinkFilter,
+    /// Whether each title passes `filter`, by id
+    allowed: Arc<Vec<bool>>,
+    link_mode: LinkMode,
+    target: String,
+    on_event: Option<EventHandler>,
+}
+
+impl WikipediaCrawler {
+    /// Create a crawler searching a copy of `links`
+    #[must_use]
+    pub fn from_links(links: &InMemoryLinks) -> Self {
+        Self::from_graph(Arc::new(CompactGraph::from_links(links)))
+    }
+
+    /// Create a crawler searching `graph`
+    #[must_use]
+    pub fn from_graph(graph: Arc<CompactGraph>) -> Self {
+        let filter = LinkFilter::default();
+        Self {
+            allowed: Arc::new(allowed(&graph, &filter)),
+            graph,
+            filter,
+            link_mode: LinkMode::default(),
+            target: KEVIN_BACON.to_string(),
+            on_event: None,
+        }
+    }
+
+    /// Restrict which linked titles the crawl follows
+    #[must_use]
+    pub fn with_filter(mut self, filter: LinkFilter) -> Self {
+        self.allowed = Arc::new(allowed(&self.graph, &filter));
+        self.filter = filter;
+        self
+    }
+
+    /// The filter applied to linked titles
+    #[must_use]
+    pub fn filter(&self) -> &LinkFilter {
+        &self.filter
+    }
+
+    /// Set the link mode reported in results
+    #[must_use]
+    pub fn with_link_mode(mut self, link_mode: LinkMode) -> Self {
+        self.link_mode = link_mode;
+        self
+    }
+
+    /// Which links of each article are followed
+    #[must_use]
+    pub fn link_mode(&self) -> LinkMode {
+        self.link_mode
+    }
+
+    /// Search for a path to `target` instead of Kevin Bacon
+    #[must_use]
+    pub fn with_target(mut self, target: &str) -> Self {
+        self.target = target.to_string();
+        self
+    }
+
+    /// The article searched for
+    #[must_use]
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Report progress of every crawl to the given callback
+    #[must_use]
+    pub fn with_event_handler(
+        mut self,
+        on_event: impl Fn(CrawlEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_event = Some(Arc::new(on_event));
+        self
+    }
+
+    /// Execute the main crawl process. It runs on rayon's thread pool and
+    /// blocks until it finishes.
+    ///
+    /// # Errors
+    ///
+    /// This function errors if it fails to find a successful path after
+    /// exhausting all found links.
+    pub fn crawl(&self, start_title: &str) -> anyhow::Result<CrawlResult> {
+        if start_title == self.target {
+            return Ok(self.result(vec![self.target.clone()]));
+        }
+
+        let not_found = || anyhow!("Could not find path to {}", self.target);
+        let (Some(start), Some(target)) = (self.graph.id(start_title), self.graph.id(&self.target))
+        else {
+            return Err(not_found());
+        };
+
+        let on_event = |event| {
+            if let Some(on_event) = &self.on_event {
+                on_event(event);
+            }
+        };
+        let path = shortest_path(&self.graph, start, target, &self.allowed, &on_event)
+            .ok_or_else(not_found)?;
+        Ok(self.result(
+            path.into_iter()
+                .map(|id| self.graph.title(id).to_string())
+                .collect(),
+        ))
+    }
+
+    /// Collect all titles the article with the given title links to in the
+    /// graph that pass the filter. Titles not in the graph have no links.
+    ///
+    /// # Errors
+    ///
+    /// Never; returns a result to match the other crawlers.
+    #[allow(clippy::unnecessary_wraps)]
+    pub fn get_linked_titles(&self, title: &str) -> anyhow::Result<Vec<String>> {
+        let Some(id) = self.graph.id(title) else {
+            return Ok(Vec::new());
+        };
+        Ok(self
+            .graph
+            .linked(id)
+            .iter()
+            .filter(|&&linked_id| self.allowed[linked_id as usize])
+            .map(|&linked_id| self.graph.title(linked_id).to_string())
+            .collect())
+    }
+
+    fn result(&self, path: Vec<String>) -> CrawlResult {
+        CrawlResult {
+            path,
+            filter: self.filter.clone(),
+            link_mode: self.link_mode,
+            shortest: true,
+        }
+    }
+}
+
+/// Whether each title of `graph` passes `filter`, by id
+fn allowed(graph: &CompactGraph, filter: &LinkFilter) -> Vec<bool> {
+    graph
+        .ids()
+        .into_par_iter()
+        .map(|id| filter.allows_source_title(graph.title(id)))
+        .collect()
+}
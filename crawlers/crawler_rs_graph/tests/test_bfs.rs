@@ -0,0 +1,93 @@
+use crawler_common::TitleId;
+use crawler_rs_graph::{shortest_path, CompactGraph};
+use std::collections::VecDeque;
+
+/// Titles in the random graph
+const TITLES: u32 = 3000;
+
+/// Random links, the same on every run, enough that searches switch to
+/// bottom-up steps
+fn random_graph() -> CompactGraph {
+    let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move || {
+        // xorshift64
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        TitleId::try_from(seed % u64::from(TITLES)).unwrap()
+    };
+
+    let edges: Vec<_> = (0..TITLES * 4).map(|_| (next(), next())).collect();
+    CompactGraph::from_edges((0..TITLES).map(|i| format!("Title {i}")), &edges)
+}
+
+/// Hops from `start` to every title, by a plain queue
+fn distances(graph: &CompactGraph, start: TitleId) -> Vec<Option<usize>> {
+    let mut distances = vec![None; graph.len()];
+    distances[start as usize] = Some(0);
+    let mut queue = VecDeque::from([start]);
+    while let Some(id) = queue.pop_front() {
+        let distance = distances[id as usize].unwrap();
+        for &linked in graph.linked(id) {
+            if distances[linked as usize].is_none() {
+                distances[linked as usize] = Some(distance + 1);
+                queue.push_back(linked);
+            }
+        }
+    }
+    distances
+}
+
+#[test]
+fn compact_graph_links_both_ways() {
+    let graph = CompactGraph::from_edges(
+        ["A", "B", "C"].map(str::to_string),
+        &[(0, 1), (0, 2), (2, 1)],
+    );
+
+    assert_eq!(graph.len(), 3);
+    assert_eq!(graph.link_count(), 3);
+    assert_eq!(graph.id("C"), Some(2));
+    assert_eq!(&**graph.title(1), "B");
+    assert_eq!(graph.linked(0), [1, 2]);
+    assert_eq!(graph.linked(1), [] as [TitleId; 0]);
+    assert_eq!(graph.linking(1), [0, 2]);
+}
+
+#[test]
+fn matches_queue_search() {
+    let graph = random_graph();
+    let allowed = vec![true; graph.len()];
+    let expected = distances(&graph, 0);
+
+    for target in graph.ids().step_by(37) {
+        let path = shortest_path(&graph, 0, target, &allowed, &|_| {});
+        assert_eq!(
+            path.as_ref().map(|path| path.len() - 1),
+            expected[target as usize]
+        );
+        if let Some(path) = path {
+            assert_eq!(path[0], 0);
+            assert_eq!(*path.last().unwrap(), target);
+            for hop in path.windows(2) {
+                assert!(graph.linked(hop[0]).contains(&hop[1]));
+            }
+        }
+    }
+}
+
+#[test]
+fn avoids_titles_not_allowed() {
+    let graph = random_graph();
+    let mut allowed = vec![true; graph.len()];
+    for id in graph.ids().filter(|id| id % 2 == 1) {
+        allowed[id as usize] = false;
+    }
+
+    for target in graph.ids().step_by(2).take(200) {
+        if let Some(path) = shortest_path(&graph, 0, target, &allowed, &|_| {}) {
+            assert!(path.iter().all(|&id| allowed[id as usize]));
+        }
+    }
+    assert_eq!(shortest_path(&graph, 0, 1, &allowed, &|_| {}), None);
+}
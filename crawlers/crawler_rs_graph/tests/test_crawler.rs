@@ -0,0 +1,117 @@
+use crawler_common::{CrawlEvent, InMemoryLinks, LinkFilter};
+use crawler_rs_graph::WikipediaCrawler;
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, Mutex},
+};
+use titles::KEVIN_BACON;
+
+#[test]
+fn shortest_path() {
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["Long", "Short"]),
+        ("Long", vec!["Longer"]),
+        ("Longer", vec![KEVIN_BACON]),
+        ("Short", vec![KEVIN_BACON]),
+    ]);
+    let crawler = WikipediaCrawler::from_links(&links);
+
+    assert_eq!(
+        crawler.crawl("Start").unwrap().path,
+        vec!["Start", "Short", KEVIN_BACON]
+    );
+}
+
+#[test]
+fn other_target() {
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["Middle", KEVIN_BACON]),
+        ("Middle", vec!["Goal"]),
+    ]);
+    let crawler = WikipediaCrawler::from_links(&links).with_target("Goal");
+
+    assert_eq!(
+        crawler.crawl("Start").unwrap().path,
+        vec!["Start", "Middle", "Goal"]
+    );
+    assert_eq!(crawler.crawl("Goal").unwrap().path, vec!["Goal"]);
+}
+
+#[test]
+fn no_path() {
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["Dead end"]),
+        ("Elsewhere", vec![KEVIN_BACON]),
+    ]);
+    let crawler = WikipediaCrawler::from_links(&links);
+
+    let error = crawler.crawl("Start").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        format!("Could not find path to {KEVIN_BACON}")
+    );
+    assert!(crawler.crawl("Not in the graph").is_err());
+}
+
+#[test]
+fn follows_filter() {
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["List of films", "Footloose"]),
+        ("List of films", vec![KEVIN_BACON]),
+        ("Footloose", vec!["Cast", KEVIN_BACON]),
+    ]);
+    let filter = LinkFilter {
+        exclude_lists: true,
+        deny_list: BTreeSet::from(["Cast".to_string()]),
+        ..LinkFilter::default()
+    };
+    let crawler = WikipediaCrawler::from_links(&links).with_filter(filter.clone());
+
+    let result = crawler.crawl("Start").unwrap();
+    assert_eq!(result.path, vec!["Start", "Footloose", KEVIN_BACON]);
+    assert_eq!(result.filter, filter);
+    assert_eq!(
+        crawler.get_linked_titles("Start").unwrap(),
+        vec!["Footloose"]
+    );
+    assert_eq!(
+        crawler.get_linked_titles("Footloose").unwrap(),
+        vec![KEVIN_BACON]
+    );
+    assert!(crawler
+        .get_linked_titles("Not in the graph")
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn crawl_events() {
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["A", "B"]),
+        ("A", vec!["C"]),
+        ("B", vec![KEVIN_BACON]),
+    ]);
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    let crawler = WikipediaCrawler::from_links(&links)
+        .with_event_handler(move |event| recorded.lock().unwrap().push(event));
+
+    crawler.crawl("Start").unwrap();
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![
+            CrawlEvent::DepthCompleted {
+                depth: 0,
+                pages_fetched: 1,
+                frontier_size: 2,
+            },
+            CrawlEvent::DepthCompleted {
+                depth: 1,
+                pages_fetched: 3,
+                frontier_size: 2,
+            },
+            CrawlEvent::TargetFound { depth: 2 },
+        ]
+    );
+}
@@ -0,0 +1,137 @@
+//! Time to find a path with each async crawler, over a synthetic link graph
+//! whose lookups take a few milliseconds and now and then far longer, as
+//! Wikipedia's do.
+//!
+//! Also compares the pipelined crawler's search orders, guided by the titles
+//! known to link to Kevin Bacon.
+//!
+//! Run with `cargo bench --bench crawlers`.
+
+use crawler_common::{InMemoryLinks, Latency, LinkSource, SearchOrder, TargetInbound};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::Arc,
+    time::Duration,
+};
+use titles::KEVIN_BACON;
+use tokio::runtime::Runtime;
+
+/// Articles in the synthetic graph
+const ARTICLES: usize = 3000;
+/// Links from each article
+const LINKS: usize = 8;
+/// One article in this many links to Kevin Bacon
+const BACON_EVERY: usize = 400;
+/// One lookup in this many is slow
+const SLOW_EVERY: u64 = 40;
+
+/// How long looking up a title's links takes, depending only on the title
+fn delay(title: &str) -> Duration {
+    let mut hasher = DefaultHasher::new();
+    title.hash(&mut hasher);
+    let hash = hasher.finish();
+    let millis = if hash.is_multiple_of(SLOW_EVERY) {
+        50
+    } else {
+        1 + hash % 4
+    };
+    Duration::from_millis(millis)
+}
+
+fn title(i: usize) -> String {
+    format!("Synthetic article number {i}")
+}
+
+/// Random links, the same on every run
+fn graph() -> InMemoryLinks {
+    let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move || {
+        // xorshift64
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        usize::try_from(seed % ARTICLES as u64).unwrap()
+    };
+
+    let links: HashMap<String, Vec<String>> = (0..ARTICLES)
+        .map(|i| {
+            let mut linked: Vec<String> = (0..LINKS).map(|_| title(next())).collect();
+            if i % BACON_EVERY == BACON_EVERY - 1 {
+                linked.push(KEVIN_BACON.to_string());
+            }
+            (title(i), linked)
+        })
+        .collect();
+    InMemoryLinks::new(links)
+}
+
+fn crawlers(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let source: Arc<dyn LinkSource> = Arc::new(Latency::new(graph(), delay));
+    let start = title(0);
+    let mut group = c.benchmark_group("crawlers");
+    group.sample_size(10);
+
+    for concurrency in [4, 16] {
+        let crawler =
+            crawler_rs_async::WikipediaCrawler::from_link_source(concurrency, source.clone());
+        group.bench_with_input(
+            BenchmarkId::new("async", concurrency),
+            &start,
+            |b, start| {
+                b.iter(|| runtime.block_on(crawler.crawl(start)).unwrap());
+            },
+        );
+
+        let crawler = crawler_rs_async_channels::WikipediaCrawler::from_link_source(
+            concurrency,
+            source.clone(),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("channels", concurrency),
+            &start,
+            |b, start| {
+                b.iter(|| runtime.block_on(crawler.crawl(start)).unwrap());
+            },
+        );
+
+        let crawler =
+            crawler_rs_pipelined::WikipediaCrawler::from_link_source(concurrency, source.clone());
+        group.bench_with_input(
+            BenchmarkId::new("pipelined", concurrency),
+            &start,
+            |b, start| {
+                b.iter(|| runtime.block_on(crawler.crawl(start)).unwrap());
+            },
+        );
+    }
+    group.finish();
+}
+
+fn orders(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let links = graph();
+    let heuristic = Arc::new(TargetInbound::from_links(&links, KEVIN_BACON));
+    let source: Arc<dyn LinkSource> = Arc::new(Latency::new(links, delay));
+    let start = title(0);
+    let mut group = c.benchmark_group("orders");
+    group.sample_size(10);
+
+    for (name, order) in [
+        ("breadth", SearchOrder::Breadth),
+        ("prioritized", SearchOrder::Prioritized(heuristic.clone())),
+        ("best_first", SearchOrder::BestFirst(heuristic)),
+    ] {
+        let crawler = crawler_rs_pipelined::WikipediaCrawler::from_link_source(4, source.clone())
+            .with_search_order(order);
+        group.bench_with_input(BenchmarkId::new(name, 4), &start, |b, start| {
+            b.iter(|| runtime.block_on(crawler.crawl(start)).unwrap());
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, crawlers, orders);
+criterion_main!(benches);
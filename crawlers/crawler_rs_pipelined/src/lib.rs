@@ -0,0 +1,694 @@
+use anyhow::{anyhow, Context};
+use crawler_common::{
+    body_links, links_params, template_links_params, wikitext_params, Checkpoint, CrawlEvent,
+    CrawlResult, EventHandler, Heuristic, LinkFilter, LinkMode, LinkSource, Parents, SearchOrder,
+    TitleId, API_URL,
+};
+use dotenvy::dotenv;
+use futures::{stream::FuturesUnordered, StreamExt};
+use reqwest::Client;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    env, fs,
+    path::PathBuf,
+    sync::Arc,
+};
+use titles::KEVIN_BACON;
+use tokio::time::Duration;
+use wiki_response::WikiResponse;
+
+/// Pages of the next depth fetched while the current depth finishes, at most.
+/// Their links are held until it has, so this bounds the memory they take.
+const MAX_AHEAD: usize = 1000;
+
+/// A breadth first crawler that keeps up to `concurrency` fetches running at
+/// all times, rather than waiting for every page of a depth to be fetched
+/// before fetching any of the next.
+///
+/// Pages of the next depth are fetched while the last pages of the current
+/// one are, but the titles they link to are only taken once the current
+/// depth is fully expanded, so paths found are still shortest.
+///
+/// A heuristic can order the pages of each depth, with
+/// `SearchOrder::Prioritized`, or replace depths altogether with
+/// `SearchOrder::BestFirst`, which finds paths sooner that may not be
+/// shortest.
+#[derive(Clone)]
+pub struct WikipediaCrawler {
+    /// Absent when links come from a `LinkSource`
+    client: Option<Client>,
+    concurrency: u8,
+    filter: LinkFilter,
+    link_mode: LinkMode,
+    target: String,
+    on_event: Option<EventHandler>,
+    source: Option<Arc<dyn LinkSource>>,
+    checkpoint: Option<PathBuf>,
+    order: SearchOrder,
+}
+
+/// Pages waiting to be fetched, highest score first, then in the order they
+/// were found
+#[derive(Default)]
+struct Queue {
+    pages: BinaryHeap<(u64, Reverse<usize>, TitleId, usize)>,
+    found: usize,
+}
+
+impl Queue {
+    fn push(&mut self, id: TitleId, depth: usize, score: u64) {
+        self.pages.push((score, Reverse(self.found), id, depth));
+        self.found += 1;
+    }
+
+    fn pop(&mut self) -> Option<(TitleId, usize)> {
+        self.pages.pop().map(|(_, _, id, depth)| (id, depth))
+    }
+
+    fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+}
+
+/// The state of a crawl between fetches
+struct Search {
+    parents: Parents,
+    target: TitleId,
+    /// Orders the pages of each depth, when searching in prioritized order
+    heuristic: Option<Arc<dyn Heuristic>>,
+    /// Depth of the pages being expanded
+    depth: usize,
+    /// Pages at `depth` waiting to be fetched
+    queue: Queue,
+    /// Pages at `depth + 1` waiting to be fetched
+    next_queue: Queue,
+    /// Pages at `depth` not yet expanded, whether queued, being fetched or
+    /// fetched
+    remaining: usize,
+    /// Pages at `depth + 1` being fetched or held
+    ahead: usize,
+    /// Links of pages at `depth + 1` fetched before `depth` was finished
+    held: Vec<(TitleId, Vec<String>)>,
+    /// Titles found at `depth + 1`
+    next_level: Vec<TitleId>,
+}
+
+impl Search {
+    fn new(checkpoint: &Checkpoint, heuristic: Option<Arc<dyn Heuristic>>) -> Self {
+        let mut parents = Parents::from_titles(&checkpoint.parents);
+        parents.intern(&checkpoint.start);
+        let target = parents.intern(&checkpoint.target);
+        let score = |title: &str| heuristic.as_ref().map_or(0, |h| h.score(title));
+        let mut queue = Queue::default();
+        for title in &checkpoint.frontier {
+            queue.push(parents.intern(title), checkpoint.depth, score(title));
+        }
+        let mut next_queue = Queue::default();
+        let mut next_level = Vec::new();
+        for title in &checkpoint.next_frontier {
+            let id = parents.intern(title);
+            next_queue.push(id, checkpoint.depth + 1, score(title));
+            next_level.push(id);
+        }
+
+        Self {
+            parents,
+            target,
+            heuristic,
+            depth: checkpoint.depth,
+            queue,
+            next_queue,
+            remaining: checkpoint.frontier.len(),
+            ahead: 0,
+            held: Vec::new(),
+            next_level,
+        }
+    }
+
+    /// The next page to fetch, unless it is of the next depth and enough of
+    /// those are fetched already
+    fn next_page(&mut self) -> Option<(TitleId, usize)> {
+        if !self.queue.is_empty() {
+            return self.queue.pop();
+        }
+        if self.ahead >= MAX_AHEAD {
+            return None;
+        }
+        let page = self.next_queue.pop()?;
+        self.ahead += 1;
+        Some(page)
+    }
+
+    /// Count a page that couldn't be fetched as done with
+    fn skip(&mut self, depth: usize) {
+        if depth > self.depth {
+            self.ahead -= 1;
+        } else {
+            self.remaining -= 1;
+        }
+    }
+
+    /// Expand a fetched page, or hold it if it is of the next depth. Returns
+    /// whether the target was found.
+    fn take(&mut self, cur_id: TitleId, depth: usize, linked_titles: Vec<String>) -> bool {
+        if depth > self.depth {
+            self.held.push((cur_id, linked_titles));
+            return false;
+        }
+        self.remaining -= 1;
+        self.expand(cur_id, linked_titles)
+    }
+
+    /// Whether no pages are left to fetch at any depth
+    fn is_exhausted(&self) -> bool {
+        self.ahead == 0 && self.queue.is_empty() && self.next_queue.is_empty()
+    }
+
+    /// Move on to the next depth once the current one is finished, expanding
+    /// the pages held for it. Returns whether the target was found.
+    fn next_depth(&mut self) -> bool {
+        self.depth += 1;
+        self.next_level.clear();
+        self.queue = std::mem::take(&mut self.next_queue);
+        self.remaining = self.ahead + self.queue.len();
+        self.ahead = 0;
+        for (cur_id, linked_titles) in std::mem::take(&mut self.held) {
+            self.remaining -= 1;
+            if self.expand(cur_id, linked_titles) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Take the titles linked from a page, returning whether the target is
+    /// among them
+    fn expand(&mut self, cur_id: TitleId, linked_titles: Vec<String>) -> bool {
+        for linked_title in linked_titles {
+            let linked_id = self.parents.intern(&linked_title);
+            if !self.parents.insert(linked_id, cur_id) {
+                continue;
+            }
+
+            if linked_id == self.target {
+                return true;
+            }
+
+            let score = self
+                .heuristic
+                .as_ref()
+                .map_or(0, |heuristic| heuristic.score(&linked_title));
+            self.next_queue.push(linked_id, self.depth + 1, score);
+            self.next_level.push(linked_id);
+        }
+        false
+    }
+}
+
+impl WikipediaCrawler {
+    /// Create a new instance of an object to search pages, fetching up to
+    /// `concurrency` pages at once
+    ///
+    /// # Errors
+    ///
+    /// `new` errors if:
+    ///   - The environment variable `CONTACT` cannot be found (used to create
+    ///     user agent for http requests)
+    ///   - There is an error encountered while creating the http client
+    pub fn new(concurrency: u8) -> anyhow::Result<Self> {
+        dotenv().ok();
+        let contact = env::var("CONTACT")?;
+        let user_agent = format!("MyWikiCrawler ({contact})");
+
+        let client = Client::builder()
+            .user_agent(user_agent)
+            .timeout(Duration::from_secs(5))
+            .build()
+            .context("Error creating http client")?;
+
+        Ok(Self {
+            client: Some(client),
+            concurrency,
+            filter: LinkFilter::default(),
+            link_mode: LinkMode::default(),
+            target: KEVIN_BACON.to_string(),
+            on_event: None,
+            source: None,
+            checkpoint: None,
+            order: SearchOrder::Breadth,
+        })
+    }
+
+    /// Create a crawler that looks up links in the given source instead of
+    /// the Wikipedia API
+    #[must_use]
+    pub fn from_link_source(concurrency: u8, source: Arc<dyn LinkSource>) -> Self {
+        Self {
+            client: None,
+            concurrency,
+            filter: LinkFilter::default(),
+            link_mode: LinkMode::default(),
+            target: KEVIN_BACON.to_string(),
+            on_event: None,
+            source: Some(source),
+            checkpoint: None,
+            order: SearchOrder::Breadth,
+        }
+    }
+
+    /// Restrict which linked titles the crawl follows
+    #[must_use]
+    pub fn with_filter(mut self, filter: LinkFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// The filter applied to linked titles
+    #[must_use]
+    pub fn filter(&self) -> &LinkFilter {
+        &self.filter
+    }
+
+    /// Choose whether links from transcluded templates are followed
+    #[must_use]
+    pub fn with_link_mode(mut self, link_mode: LinkMode) -> Self {
+        self.link_mode = link_mode;
+        self
+    }
+
+    /// Which links of each article are followed
+    #[must_use]
+    pub fn link_mode(&self) -> LinkMode {
+        self.link_mode
+    }
+
+    /// Search for a path to `target` instead of Kevin Bacon
+    #[must_use]
+    pub fn with_target(mut self, target: &str) -> Self {
+        self.target = target.to_string();
+        self
+    }
+
+    /// The article searched for
+    #[must_use]
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Save the state of every crawl to `path` as each depth completes, so it
+    /// can be resumed with `resume` if stopped. The file is removed once the
+    /// crawl finishes.
+    #[must_use]
+    pub fn with_checkpoint(mut self, path: impl Into<PathBuf>) -> Self {
+        self.checkpoint = Some(path.into());
+        self
+    }
+
+    /// Fetch pages in the given order instead of breadth first. Best-first
+    /// crawls aren't checkpointed, as they have no depths to save between.
+    #[must_use]
+    pub fn with_search_order(mut self, order: SearchOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// The order pages are fetched in
+    #[must_use]
+    pub fn search_order(&self) -> &SearchOrder {
+        &self.order
+    }
+
+    /// Report progress of every crawl to the given callback
+    #[must_use]
+    pub fn with_event_handler(
+        mut self,
+        on_event: impl Fn(CrawlEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_event = Some(Arc::new(on_event));
+        self
+    }
+
+    /// Execute the main crawl process. Dropping the future cancels the
+    /// fetches it is waiting on.
+    ///
+    /// # Errors
+    ///
+    /// This function errors if it fails to find a successful path after
+    /// exhausting all found links.
+    pub async fn crawl(&self, start_title: &str) -> anyhow::Result<CrawlResult> {
+        if start_title == self.target {
+            return Ok(self.result(vec![self.target.clone()]));
+        }
+
+        if let SearchOrder::BestFirst(heuristic) = &self.order {
+            return self.search_best_first(start_title, &**heuristic).await;
+        }
+        self.search(Checkpoint::new(
+            start_title,
+            &self.target,
+            self.link_mode,
+            &self.filter,
+        ))
+        .await
+    }
+
+    /// Continue a crawl from a checkpoint saved by an earlier crawl with the
+    /// same target and link mode.
+    ///
+    /// # Errors
+    ///
+    /// This function errors if the checkpoint is of a different crawl, if
+    /// the crawler searches best first, or if it fails to find a successful
+    /// path after exhausting all found links.
+    pub async fn resume(&self, checkpoint: Checkpoint) -> anyhow::Result<CrawlResult> {
+        checkpoint.ensure_matches(&self.target, self.link_mode, &self.filter)?;
+        if let SearchOrder::BestFirst(_) = self.order {
+            return Err(anyhow!("Best-first crawls can't be resumed"));
+        }
+        self.search(checkpoint).await
+    }
+
+    async fn search(&self, checkpoint: Checkpoint) -> anyhow::Result<CrawlResult> {
+        let heuristic = match &self.order {
+            SearchOrder::Prioritized(heuristic) => Some(heuristic.clone()),
+            SearchOrder::Breadth | SearchOrder::BestFirst(_) => None,
+        };
+        let mut search = Search::new(&checkpoint, heuristic);
+        let Checkpoint {
+            start,
+            mut pages_fetched,
+            ..
+        } = checkpoint;
+        let concurrency = usize::from(self.concurrency.max(1));
+        let mut in_flight = FuturesUnordered::new();
+
+        'search: loop {
+            while search.remaining == 0 {
+                self.emit(CrawlEvent::DepthCompleted {
+                    depth: search.depth,
+                    pages_fetched,
+                    frontier_size: search.next_level.len(),
+                });
+                if search.is_exhausted() {
+                    break 'search;
+                }
+
+                self.save_checkpoint(
+                    &start,
+                    search.depth + 1,
+                    &search.next_level,
+                    &search.parents,
+                    pages_fetched - search.held.len(),
+                );
+                if search.next_depth() {
+                    self.found_target(search.depth);
+                    break 'search;
+                }
+            }
+
+            while in_flight.len() < concurrency {
+                let Some((cur_id, cur_depth)) = search.next_page() else {
+                    break;
+                };
+                let cur_title = search.parents.title(cur_id).clone();
+                in_flight.push(async move {
+                    let linked_titles = self.get_linked_titles(&cur_title).await;
+                    (cur_id, cur_title, cur_depth, linked_titles)
+                });
+            }
+
+            let Some((cur_id, cur_title, cur_depth, linked_titles)) = in_flight.next().await else {
+                break;
+            };
+            let Some(linked_titles) = self.fetched(&cur_title, cur_depth, linked_titles) else {
+                search.skip(cur_depth);
+                continue;
+            };
+            pages_fetched += 1;
+
+            if search.take(cur_id, cur_depth, linked_titles) {
+                self.found_target(search.depth);
+                break;
+            }
+        }
+
+        if let Some(checkpoint) = &self.checkpoint {
+            let _ = fs::remove_file(checkpoint);
+        }
+
+        let path = search.parents.path(&start, &self.target);
+        path.map(|path| self.result(path))
+            .ok_or_else(|| anyhow!("Could not find path to {}", self.target))
+    }
+
+    /// Fetch the most promising page found so far, whatever its depth, and
+    /// take the titles it links to as soon as it is fetched, until the target
+    /// is found
+    async fn search_best_first(
+        &self,
+        start_title: &str,
+        heuristic: &dyn Heuristic,
+    ) -> anyhow::Result<CrawlResult> {
+        let mut parents = Parents::new();
+        let start = parents.intern(start_title);
+        let target = parents.intern(&self.target);
+        let mut queue = Queue::default();
+        queue.push(start, 0, heuristic.score(start_title));
+        let concurrency = usize::from(self.concurrency.max(1));
+        let mut in_flight = FuturesUnordered::new();
+
+        'search: loop {
+            while in_flight.len() < concurrency {
+                let Some((cur_id, cur_depth)) = queue.pop() else {
+                    break;
+                };
+                let cur_title = parents.title(cur_id).clone();
+                in_flight.push(async move {
+                    let linked_titles = self.get_linked_titles(&cur_title).await;
+                    (cur_id, cur_title, cur_depth, linked_titles)
+                });
+            }
+
+            let Some((cur_id, cur_title, cur_depth, linked_titles)) = in_flight.next().await else {
+                break;
+            };
+            let Some(linked_titles) = self.fetched(&cur_title, cur_depth, linked_titles) else {
+                continue;
+            };
+
+            for linked_title in linked_titles {
+                let linked_id = parents.intern(&linked_title);
+                if !parents.insert(linked_id, cur_id) {
+                    continue;
+                }
+
+                if linked_id == target {
+                    self.found_target(cur_depth);
+                    break 'search;
+                }
+
+                queue.push(linked_id, cur_depth + 1, heuristic.score(&linked_title));
+            }
+        }
+
+        let path = parents.path(start_title, &self.target);
+        path.map(|path| self.result(path))
+            .ok_or_else(|| anyhow!("Could not find path to {}", self.target))
+    }
+
+    /// Report a finished fetch, returning the linked titles if it succeeded
+    fn fetched(
+        &self,
+        title: &str,
+        depth: usize,
+        linked_titles: anyhow::Result<Vec<String>>,
+    ) -> Option<Vec<String>> {
+        match linked_titles {
+            Ok(linked_titles) => {
+                self.emit(CrawlEvent::PageFetched {
+                    title: title.to_string(),
+                    depth,
+                    link_count: linked_titles.len(),
+                });
+                Some(linked_titles)
+            }
+            Err(e) => {
+                self.emit(CrawlEvent::FetchFailed {
+                    title: title.to_string(),
+                    depth,
+                    error: e.to_string(),
+                });
+                None
+            }
+        }
+    }
+
+    fn found_target(&self, depth: usize) {
+        self.emit(CrawlEvent::TargetFound { depth: depth + 1 });
+    }
+
+    /// Save the crawl's state between depths if checkpoints are enabled. The
+    /// crawl carries on if saving fails, as it can still finish without one.
+    fn save_checkpoint(
+        &self,
+        start: &str,
+        depth: usize,
+        frontier: &[TitleId],
+        parents: &Parents,
+        pages_fetched: usize,
+    ) {
+        let Some(path) = &self.checkpoint else {
+            return;
+        };
+        let checkpoint = Checkpoint {
+            start: start.to_string(),
+            target: self.target.clone(),
+            link_mode: self.link_mode,
+            filter: self.filter.clone(),
+            depth,
+            frontier: frontier
+                .iter()
+                .map(|&id| parents.title(id).to_string())
+                .collect(),
+            next_frontier: Vec::new(),
+            parents: parents.to_titles(),
+            pages_fetched,
+        };
+        if let Err(e) = checkpoint.save(path) {
+            eprintln!("Failed to save checkpoint: {e:#}");
+        }
+    }
+
+    /// Collect all titles linked to in the article with the given title.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    ///   - Any http request fails
+    ///   - Any http response status is not within 200-299
+    ///   - Returned JSON is invalid or otherwise not decodable
+    ///   - The crawler's link source fails to look up the article
+    pub async fn get_linked_titles(&self, title: &str) -> anyhow::Result<Vec<String>> {
+        if let Some(source) = &self.source {
+            let mut linked_titles = source.linked_titles(title).await?;
+            linked_titles.retain(|linked_title| self.filter.allows_source_title(linked_title));
+            return Ok(linked_titles);
+        }
+
+        let mut linked_titles = self
+            .query_linked_titles(title, links_params(title, &self.filter))
+            .await?;
+
+        if self.link_mode == LinkMode::Body {
+            let template_titles: HashSet<String> = self
+                .query_linked_titles(title, template_links_params(title, &self.filter))
+                .await?
+                .into_iter()
+                .collect();
+            if linked_titles
+                .iter()
+                .any(|linked_title| template_titles.contains(linked_title))
+            {
+                let wikitext = self.query_wikitext(title).await?;
+                linked_titles = body_links(linked_titles, &template_titles, &wikitext);
+            }
+        }
+
+        Ok(linked_titles)
+    }
+
+    /// Run a links query, following continuations, and collect the titles
+    /// that pass the filter.
+    async fn query_linked_titles(
+        &self,
+        title: &str,
+        mut params: HashMap<String, String>,
+    ) -> anyhow::Result<Vec<String>> {
+        let mut linked_titles = Vec::new();
+
+        loop {
+            let wiki_resp = self.query(title, &params).await?;
+
+            for page in wiki_resp.query.pages.values() {
+                if let Some(links) = &page.links {
+                    linked_titles.extend(
+                        links
+                            .iter()
+                            .filter(|link| self.filter.allows(link))
+                            .map(|link| link.title.clone()),
+                    );
+                }
+            }
+
+            // Handle continuation
+            if let Some(cont) = wiki_resp.continuation {
+                params.extend(cont);
+            } else {
+                break;
+            }
+        }
+
+        Ok(linked_titles)
+    }
+
+    /// Fetch the wikitext source of the article with the given title
+    async fn query_wikitext(&self, title: &str) -> anyhow::Result<String> {
+        let wiki_resp = self.query(title, &wikitext_params(title)).await?;
+        Ok(wiki_resp
+            .query
+            .pages
+            .into_values()
+            .filter_map(|page| page.revisions)
+            .flatten()
+            .map(|revision| revision.slots.main.content)
+            .collect())
+    }
+
+    /// Send one query to the API and decode the response
+    #[allow(clippy::uninlined_format_args)]
+    async fn query(
+        &self,
+        title: &str,
+        params: &HashMap<String, String>,
+    ) -> anyhow::Result<WikiResponse> {
+        let client = self.client.as_ref().context("Crawler has no http client")?;
+        let resp = client
+            .get(API_URL)
+            .query(params)
+            .send()
+            .await
+            .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
+
+        if !resp.status().is_success() {
+            return Err(anyhow!("HTTP error {} for page '{}'", resp.status(), title));
+        }
+
+        let body_text = resp
+            .text()
+            .await
+            .map_err(|e| anyhow!("Failed to read response body for '{}': {}", title, e))?;
+
+        serde_json::from_str(&body_text)
+            .map_err(|e| anyhow!("Failed to decode JSON for page '{}': {}", title, e))
+    }
+
+    fn emit(&self, event: CrawlEvent) {
+        if let Some(on_event) = &self.on_event {
+            on_event(event);
+        }
+    }
+
+    fn result(&self, path: Vec<String>) -> CrawlResult {
+        CrawlResult {
+            path,
+            filter: self.filter.clone(),
+            link_mode: self.link_mode,
+            shortest: self.order.finds_shortest(),
+        }
+    }
+}
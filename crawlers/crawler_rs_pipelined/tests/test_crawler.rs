@@ -0,0 +1,319 @@
+use crawler_common::{
+    Checkpoint, CrawlEvent, InMemoryLinks, Latency, LinkFilter, LinkMode, SearchOrder, Stalls,
+};
+use crawler_rs_pipelined::WikipediaCrawler;
+use std::{
+    sync::{Arc, LazyLock, Mutex},
+    time::Duration,
+};
+use titles::{
+    AMANDA_CLAYTON, CITY_ON_A_HILL, CLINT_EASTWOOD, CURTIS_HANSON, FOOTLOOSE, FRIDAY_THE_13TH,
+    GINETTA_GT5_CHALLENGE, GRAN_TURISMO_5, GRAN_TURISMO_5_PROLOGUE, GT5, HERBERT_ROSS, KEVIN_BACON,
+    THE_BET,
+};
+use tokio::time::timeout;
+
+static CRAWLER: LazyLock<WikipediaCrawler> = LazyLock::new(|| WikipediaCrawler::new(5).unwrap());
+
+#[tokio::test]
+async fn starting_at_kevin_bacon() {
+    assert_eq!(
+        CRAWLER.crawl(KEVIN_BACON).await.unwrap().path,
+        vec![KEVIN_BACON]
+    );
+}
+
+#[tokio::test]
+async fn one_hop_1() {
+    assert_eq!(
+        CRAWLER.crawl(FOOTLOOSE).await.unwrap().path,
+        vec![FOOTLOOSE, KEVIN_BACON]
+    );
+}
+
+#[tokio::test]
+async fn one_hop_2() {
+    assert_eq!(
+        CRAWLER.crawl(FRIDAY_THE_13TH).await.unwrap().path,
+        vec![FRIDAY_THE_13TH, KEVIN_BACON]
+    );
+}
+
+#[tokio::test]
+async fn one_hop_3() {
+    assert_eq!(
+        CRAWLER.crawl(CITY_ON_A_HILL).await.unwrap().path,
+        vec![CITY_ON_A_HILL, KEVIN_BACON]
+    );
+}
+
+#[tokio::test]
+async fn two_hops_1() {
+    // There are at least a couple paths of length 4.
+    // Two found while testing:
+    // - ["Amanda Clayton", "If Loving You Is Wrong", "The Rosie Show", "Kevin Bacon"]
+    // - ["Amanda Clayton", "Katey Sagal", "Kyra Sedgwick", "Kevin Bacon"]
+    //
+    // Must correctly handle ordering of new titles added to the queue to
+    // ensure we find the shortest path, which is length 3.
+    assert_eq!(
+        CRAWLER.crawl(AMANDA_CLAYTON).await.unwrap().path,
+        vec![AMANDA_CLAYTON, CITY_ON_A_HILL, KEVIN_BACON]
+    );
+}
+
+#[ignore = "long execution time"]
+#[tokio::test]
+async fn two_hops_2() {
+    let result = CRAWLER.crawl(HERBERT_ROSS).await.unwrap().path;
+    // Either is shortest; which is found depends on which page is fetched
+    // first
+    assert!(
+        result == vec![HERBERT_ROSS, CURTIS_HANSON, KEVIN_BACON]
+            || result == vec![HERBERT_ROSS, CLINT_EASTWOOD, KEVIN_BACON]
+    );
+}
+
+#[ignore = "long execution time"]
+#[tokio::test]
+async fn three_hops() {
+    assert_eq!(
+        CRAWLER.crawl(THE_BET).await.unwrap().path,
+        vec![THE_BET, AMANDA_CLAYTON, CITY_ON_A_HILL, KEVIN_BACON]
+    );
+}
+
+#[tokio::test]
+async fn get_linked_titles() {
+    assert_eq!(
+        CRAWLER.get_linked_titles(GT5).await.unwrap(),
+        [
+            GINETTA_GT5_CHALLENGE,
+            GRAN_TURISMO_5,
+            GRAN_TURISMO_5_PROLOGUE
+        ]
+    );
+}
+
+#[tokio::test]
+async fn crawl_events() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    let crawler = WikipediaCrawler::new(5)
+        .unwrap()
+        .with_event_handler(move |event| recorded.lock().unwrap().push(event));
+
+    crawler.crawl(FOOTLOOSE).await.unwrap();
+
+    let events = events.lock().unwrap();
+    assert!(events.iter().any(|event| matches!(
+        event,
+        CrawlEvent::PageFetched { title, depth: 0, .. } if title == FOOTLOOSE
+    )));
+    assert!(events.contains(&CrawlEvent::TargetFound { depth: 1 }));
+}
+
+#[tokio::test]
+async fn in_memory_shortest_path() {
+    // "Long" is expanded before "Short", but the path through "Short" is
+    // found first at depth 2, so it must win.
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["Long", "Short"]),
+        ("Long", vec!["Longer"]),
+        ("Longer", vec![KEVIN_BACON]),
+        ("Short", vec![KEVIN_BACON]),
+    ]);
+    let crawler = WikipediaCrawler::from_link_source(3, Arc::new(links));
+
+    assert_eq!(
+        crawler.crawl("Start").await.unwrap().path,
+        vec!["Start", "Short", KEVIN_BACON]
+    );
+}
+
+#[tokio::test]
+async fn in_memory_unreachable() {
+    let links = InMemoryLinks::from_edges([("Start", vec!["Dead end"])]);
+    let crawler = WikipediaCrawler::from_link_source(3, Arc::new(links));
+
+    assert!(crawler.crawl("Start").await.is_err());
+}
+
+#[tokio::test]
+async fn in_memory_other_target() {
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["Middle", KEVIN_BACON]),
+        ("Middle", vec!["Goal"]),
+    ]);
+    let crawler = WikipediaCrawler::from_link_source(3, Arc::new(links)).with_target("Goal");
+
+    assert_eq!(
+        crawler.crawl("Start").await.unwrap().path,
+        vec!["Start", "Middle", "Goal"]
+    );
+    assert_eq!(crawler.crawl("Goal").await.unwrap().path, vec!["Goal"]);
+}
+
+#[tokio::test]
+async fn resumes_from_checkpoint() {
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["A", "B"]),
+        ("A", vec!["C"]),
+        ("B", vec!["D"]),
+        ("D", vec![KEVIN_BACON]),
+    ]);
+    let dir = tempfile::tempdir().unwrap();
+    let checkpoint = dir.path().join("crawl.json");
+    let stalls = Stalls::new(links.clone(), "D");
+    let killed =
+        WikipediaCrawler::from_link_source(3, Arc::new(stalls)).with_checkpoint(&checkpoint);
+    let crawler =
+        WikipediaCrawler::from_link_source(3, Arc::new(links)).with_checkpoint(&checkpoint);
+
+    // Stop the crawl stuck at depth 2, as if the process were killed
+    assert!(timeout(Duration::from_millis(500), killed.crawl("Start"))
+        .await
+        .is_err());
+    let saved = Checkpoint::load(&checkpoint).unwrap();
+    assert_eq!(saved.depth, 2);
+
+    assert_eq!(
+        crawler.resume(saved).await.unwrap().path,
+        crawler.crawl("Start").await.unwrap().path
+    );
+    assert!(!checkpoint.exists());
+}
+
+#[tokio::test]
+async fn waits_for_slow_page_before_next_depth() {
+    // "Deep" is fetched while "Slow" is, and links to Kevin Bacon first, but
+    // it is a hop further from the start so its links must wait.
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["Fast", "Slow"]),
+        ("Fast", vec!["Deep"]),
+        ("Deep", vec![KEVIN_BACON]),
+        ("Slow", vec![KEVIN_BACON]),
+    ]);
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    let slow = Latency::of_titles(links, [("Slow", Duration::from_millis(200))]);
+    let crawler = WikipediaCrawler::from_link_source(2, Arc::new(slow))
+        .with_event_handler(move |event| recorded.lock().unwrap().push(event));
+
+    assert_eq!(
+        crawler.crawl("Start").await.unwrap().path,
+        vec!["Start", "Slow", KEVIN_BACON]
+    );
+    // "Deep" was fetched without waiting for "Slow"
+    let events = events.lock().unwrap();
+    let fetched = |page: &str| {
+        events.iter().position(
+            |event| matches!(event, CrawlEvent::PageFetched { title, .. } if title == page),
+        )
+    };
+    assert!(fetched("Deep").unwrap() < fetched("Slow").unwrap());
+}
+
+#[tokio::test]
+async fn abandons_fetches_once_target_found() {
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["Near", "Stuck"]),
+        ("Near", vec![KEVIN_BACON]),
+    ]);
+    // "Stuck" is being fetched when "Near" is found to link to the target
+    let latency = Latency::of_titles(
+        links,
+        [
+            ("Near", Duration::from_millis(50)),
+            ("Stuck", Duration::from_mins(1)),
+        ],
+    );
+    let crawler = WikipediaCrawler::from_link_source(3, Arc::new(latency));
+
+    let result = timeout(Duration::from_secs(2), crawler.crawl("Start"))
+        .await
+        .expect("crawl should not wait on the stuck fetch");
+
+    assert_eq!(result.unwrap().path, vec!["Start", "Near", KEVIN_BACON]);
+}
+
+/// Links where the titles starting "Lure" look promising but lead the long
+/// way round
+fn lures() -> InMemoryLinks {
+    InMemoryLinks::from_edges([
+        ("Start", vec!["Lure", "Plain"]),
+        ("Lure", vec!["Lure 2"]),
+        ("Lure 2", vec![KEVIN_BACON]),
+        ("Plain", vec![KEVIN_BACON]),
+    ])
+}
+
+fn lure_score(title: &str) -> u64 {
+    u64::from(title.starts_with("Lure"))
+}
+
+#[tokio::test]
+async fn prioritized_fetches_promising_pages_first() {
+    let fillers: Vec<String> = (0..10).map(|i| format!("Filler {i}")).collect();
+    let links = InMemoryLinks::from_edges([
+        ("Start", fillers.iter().map(String::as_str).collect()),
+        ("Filler 9", vec![KEVIN_BACON]),
+    ]);
+    let fetched = Arc::new(Mutex::new(Vec::new()));
+    let recorded = fetched.clone();
+    let crawler = WikipediaCrawler::from_link_source(1, Arc::new(links))
+        .with_search_order(SearchOrder::Prioritized(Arc::new(|title: &str| {
+            u64::from(title == "Filler 9")
+        })))
+        .with_event_handler(move |event| {
+            if let CrawlEvent::PageFetched { title, .. } = event {
+                recorded.lock().unwrap().push(title);
+            }
+        });
+
+    let result = crawler.crawl("Start").await.unwrap();
+
+    assert_eq!(result.path, vec!["Start", "Filler 9", KEVIN_BACON]);
+    assert!(result.shortest);
+    assert_eq!(*fetched.lock().unwrap(), vec!["Start", "Filler 9"]);
+}
+
+#[tokio::test]
+async fn prioritized_finds_shortest_path() {
+    let crawler = WikipediaCrawler::from_link_source(1, Arc::new(lures()))
+        .with_search_order(SearchOrder::Prioritized(Arc::new(lure_score)));
+
+    let result = crawler.crawl("Start").await.unwrap();
+
+    assert_eq!(result.path, vec!["Start", "Plain", KEVIN_BACON]);
+    assert!(result.shortest);
+}
+
+#[tokio::test]
+async fn best_first_follows_heuristic() {
+    let crawler = WikipediaCrawler::from_link_source(1, Arc::new(lures()))
+        .with_search_order(SearchOrder::BestFirst(Arc::new(lure_score)));
+
+    let result = crawler.crawl("Start").await.unwrap();
+
+    assert_eq!(result.path, vec!["Start", "Lure", "Lure 2", KEVIN_BACON]);
+    assert!(!result.shortest);
+    assert!(crawler
+        .resume(Checkpoint::new(
+            "Start",
+            KEVIN_BACON,
+            LinkMode::All,
+            &LinkFilter::default(),
+        ))
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn best_first_unreachable() {
+    let links = InMemoryLinks::from_edges([("Start", vec!["Dead end"])]);
+    let crawler = WikipediaCrawler::from_link_source(2, Arc::new(links))
+        .with_search_order(SearchOrder::BestFirst(Arc::new(lure_score)));
+
+    assert!(crawler.crawl("Start").await.is_err());
+}
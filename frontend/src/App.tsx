@@ -2,7 +2,7 @@ import { useState, useEffect } from "react";
 import axios from "axios";
 import "./App.css";
 import UserGuessForm from "./UserGuessForm";
-import SearchResults from "./SearchResults";
+import SearchResults, { PlayerStats, Score } from "./SearchResults";
 
 // const TIMEOUT_DURATION_MS = 8000;
 
@@ -36,9 +36,21 @@ const STARTING_PAGES = [
   // "The_Exorcist",
 ];
 
+// Anonymous id the server keeps this browser's statistics under
+function playerId() {
+  let id = localStorage.getItem("playerId");
+  if (!id) {
+    id = crypto.randomUUID();
+    localStorage.setItem("playerId", id);
+  }
+  return id;
+}
+
 function App() {
   const BASE_URL = "https://en.wikipedia.org/wiki/";
   const KEVIN_BACON_TITLE = "Kevin_Bacon";
+  const BACKEND_HOST =
+    import.meta.env.VITE_BACKEND_URL + ":" + import.meta.env.VITE_BACKEND_PORT;
   // const GET_RANDOM_TITLE_URL =
   //   "https://en.wikipedia.org/api/rest_v1/page/random/title";
 
@@ -47,12 +59,26 @@ function App() {
   const [startingURL, setStartingURL] = useState("");
   const [userGuess, setUserGuess] = useState(undefined);
   const [timedOut, setTimedOut] = useState(false);
+  const [error, setError] = useState("");
+  const [score, setScore] = useState<Score | undefined>(undefined);
+  const [stats, setStats] = useState<PlayerStats | undefined>(undefined);
 
   useEffect(() => {
     // setRandomWikipediaTitle();
-    setTitleFromFilmList();
+    setTitleFromServer();
   }, []);
 
+  // The server only offers articles whose answer it has already found
+  function setTitleFromServer() {
+    axios(`${BACKEND_HOST}/random?difficulty=medium`)
+      .then((response) => {
+        const title: string = response.data.title;
+        setStartingTitle(title);
+        setStartingURL(BASE_URL + title);
+      })
+      .catch((_e) => setTitleFromFilmList());
+  }
+
   function setTitleFromFilmList() {
     const idx = Math.floor(Math.random() * STARTING_PAGES.length);
     const title = STARTING_PAGES[idx];
@@ -72,18 +98,17 @@ function App() {
   // }
 
   function findPath() {
-    const BACKEND_HOST =
-      import.meta.env.VITE_BACKEND_URL +
-      ":" +
-      import.meta.env.VITE_BACKEND_PORT;
-
     axios(`${BACKEND_HOST}/${startingTitle}`)
       // , {
       //   timeout: TIMEOUT_DURATION_MS,
       // })
       .then((response) => {
         console.log({ response });
-        setPath(response.data.result);
+        if (response.data.status === "found") {
+          setPath(response.data.path);
+        } else {
+          setError(response.data.error.message);
+        }
       })
       .catch((_e) => {
         console.log("failed");
@@ -91,10 +116,27 @@ function App() {
       });
   }
 
+  // The server scores the guess and records it in the player's statistics,
+  // falling back to a plain search when it can't be scored
+  function submitGuess(guess: number) {
+    axios
+      .post(`${BACKEND_HOST}/guesses`, {
+        player: playerId(),
+        start: startingTitle,
+        guess,
+      })
+      .then((response) => {
+        setPath(response.data.path);
+        setScore(response.data.score);
+        setStats(response.data.stats);
+      })
+      .catch((_e) => findPath());
+  }
+
   function handleSubmitGuess(e: React.FormEvent<HTMLFormElement>) {
     e.preventDefault();
     setUserGuess(e.target.userGuess.value);
-    findPath();
+    submitGuess(Number(e.target.userGuess.value));
   }
 
   return (
@@ -111,6 +153,9 @@ function App() {
             userGuess={userGuess}
             path={path}
             timedOut={timedOut}
+            error={error}
+            score={score}
+            stats={stats}
           />
         </div>
         <div className="col">
@@ -1,18 +1,44 @@
+export type Score = {
+  outcome: "exact" | "off_by_one" | "miss";
+  points: number;
+};
+
+export type PlayerStats = {
+  games: number;
+  accuracy: number;
+  average_error: number;
+  current_streak: number;
+  best_streak: number;
+};
+
+const OUTCOMES = {
+  exact: "Exactly right!",
+  off_by_one: "Off by one.",
+  miss: "Not quite.",
+};
+
 function SearchResults({
   userGuess,
   path,
   timedOut,
+  error,
+  score,
+  stats,
 }: {
   userGuess: number | undefined;
   path: string[];
   timedOut: boolean;
+  error: string;
+  score: Score | undefined;
+  stats: PlayerStats | undefined;
 }) {
   return (
     <div className="py-3 px-1">
       {userGuess && <p>You guessed: {userGuess}</p>}
 
-      {userGuess && path.length === 0 && <p>Searching...</p>}
+      {userGuess && path.length === 0 && !error && <p>Searching...</p>}
       {timedOut && <p>Timed out before finishing search.</p>}
+      {error && <p>Could not reach Kevin Bacon: {error}</p>}
 
       {path.length > 0 && (
         <div>
@@ -25,7 +51,22 @@ function SearchResults({
         </div>
       )}
 
-      {(timedOut || path.length > 0) && (
+      {score && (
+        <p>
+          {OUTCOMES[score.outcome]} You scored {score.points} points.
+        </p>
+      )}
+      {stats && (
+        <p>
+          {`Games: ${stats.games}, accuracy: ${Math.round(
+            stats.accuracy * 100
+          )}%, average error: ${stats.average_error.toFixed(
+            1
+          )}, streak: ${stats.current_streak} (best ${stats.best_streak})`}
+        </p>
+      )}
+
+      {(timedOut || error || path.length > 0) && (
         <button
           onClick={() => window.location.reload()}
           className="btn btn-primary m-3"
@@ -0,0 +1,170 @@
+use crate::{distances_to, shortest_paths, Difficulty};
+use anyhow::{anyhow, Context};
+use crawler_common::InMemoryLinks;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::Path};
+use time::{Date, Duration};
+use titles::KEVIN_BACON;
+
+/// Shortest paths kept per puzzle
+pub const MAX_PATHS: usize = 10;
+
+time::serde::format_description!(iso_date, Date, "[year]-[month]-[day]");
+
+/// The puzzle of one day
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct Puzzle {
+    #[serde(with = "iso_date")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = Date))]
+    pub date: Date,
+    pub seed: u64,
+    pub start: String,
+    pub target: String,
+    pub hops: usize,
+    pub difficulty: Difficulty,
+    /// Up to `MAX_PATHS` shortest paths from `start` to `target`
+    pub paths: Vec<Vec<String>>,
+}
+
+/// A day's puzzle as shown to players, with its answer left out until it can
+/// no longer spoil the game
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct PuzzleView {
+    #[serde(with = "iso_date")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = Date))]
+    pub date: Date,
+    pub start: String,
+    pub target: String,
+    /// Present once the answer is revealed
+    pub hops: Option<usize>,
+    /// Present once the answer is revealed
+    pub difficulty: Option<Difficulty>,
+    /// Present once the answer is revealed
+    pub paths: Option<Vec<Vec<String>>>,
+}
+
+impl Puzzle {
+    /// The puzzle as shown to players, with its answer only if `reveal`
+    #[must_use]
+    pub fn view(self, reveal: bool) -> PuzzleView {
+        let (hops, difficulty, paths) = if reveal {
+            (Some(self.hops), Some(self.difficulty), Some(self.paths))
+        } else {
+            (None, None, None)
+        };
+        PuzzleView {
+            date: self.date,
+            start: self.start,
+            target: self.target,
+            hops,
+            difficulty,
+            paths,
+        }
+    }
+}
+
+/// Puzzles drawn from an offline graph, so a day's puzzle never changes
+/// however the live wiki is edited. The same graph and seed always give the
+/// same puzzle for a date.
+pub struct DailyPuzzles {
+    graph: InMemoryLinks,
+    distances: HashMap<String, usize>,
+    /// Every article that can reach the target, sorted so the choice doesn't
+    /// depend on hash map order
+    candidates: Vec<String>,
+    seed: u64,
+}
+
+impl DailyPuzzles {
+    /// # Errors
+    ///
+    /// Errors if no article of the graph can reach Kevin Bacon.
+    pub fn new(graph: InMemoryLinks, seed: u64) -> anyhow::Result<Self> {
+        let distances = distances_to(&graph, KEVIN_BACON);
+        let mut candidates: Vec<String> = distances
+            .iter()
+            .filter(|&(_, &distance)| distance > 0)
+            .map(|(title, _)| title.clone())
+            .collect();
+        candidates.sort();
+
+        if candidates.is_empty() {
+            return Err(anyhow!("No article of the graph reaches {KEVIN_BACON}"));
+        }
+
+        Ok(Self {
+            graph,
+            distances,
+            candidates,
+            seed,
+        })
+    }
+
+    /// Load an offline graph saved as JSON; see `InMemoryLinks`
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file can't be read or decoded, or if no article of the
+    /// graph can reach Kevin Bacon.
+    pub fn from_graph_file(path: &Path, seed: u64) -> anyhow::Result<Self> {
+        let bytes =
+            fs::read(path).with_context(|| format!("Failed to read graph {}", path.display()))?;
+        let graph = serde_json::from_slice(&bytes)
+            .with_context(|| format!("Failed to decode graph {}", path.display()))?;
+        Self::new(graph, seed)
+    }
+
+    #[must_use]
+    pub fn puzzle(&self, date: Date) -> Puzzle {
+        let day = u64::from(date.to_julian_day().unsigned_abs());
+        let pick = splitmix64(self.seed ^ splitmix64(day));
+        // Below the candidate count, so always fits
+        let index = usize::try_from(pick % self.candidates.len() as u64).unwrap_or_default();
+
+        let start = self.candidates[index].clone();
+        let hops = self.distances[&start];
+
+        Puzzle {
+            date,
+            seed: self.seed,
+            paths: shortest_paths(&self.graph, &self.distances, &start, MAX_PATHS),
+            start,
+            target: KEVIN_BACON.to_string(),
+            hops,
+            difficulty: Difficulty::of_hops(hops),
+        }
+    }
+
+    /// Puzzles of `days` consecutive days from `first`
+    #[must_use]
+    pub fn calendar(&self, first: Date, days: u32) -> Vec<Puzzle> {
+        (0..days)
+            .map_while(|day| first.checked_add(Duration::days(i64::from(day))))
+            .map(|date| self.puzzle(date))
+            .collect()
+    }
+}
+
+/// Parse a date written as `YYYY-MM-DD`
+///
+/// # Errors
+///
+/// Errors if `date` is not a valid date in that form.
+pub fn parse_date(date: &str) -> anyhow::Result<Date> {
+    Date::parse(
+        date,
+        &time::macros::format_description!("[year]-[month]-[day]"),
+    )
+    .with_context(|| format!("Invalid date '{date}', expected YYYY-MM-DD"))
+}
+
+/// A well-mixed hash that, unlike the standard library's hashers, is
+/// guaranteed never to change between releases
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// How far from the target a starting article is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    /// At most 2 hops
+    Easy,
+    /// Exactly 3 hops
+    Medium,
+    /// 4 hops or more
+    Hard,
+}
+
+impl Difficulty {
+    /// Link hops an article of this difficulty is from the target
+    #[must_use]
+    pub fn hops(self) -> RangeInclusive<usize> {
+        match self {
+            Self::Easy => 0..=2,
+            Self::Medium => 3..=3,
+            Self::Hard => 4..=usize::MAX,
+        }
+    }
+
+    /// The difficulty of an article `hops` link hops from the target
+    #[must_use]
+    pub fn of_hops(hops: usize) -> Self {
+        match hops {
+            0..=2 => Self::Easy,
+            3 => Self::Medium,
+            _ => Self::Hard,
+        }
+    }
+}
@@ -0,0 +1,7 @@
+mod daily;
+mod difficulty;
+mod paths;
+
+pub use daily::{parse_date, DailyPuzzles, Puzzle, PuzzleView, MAX_PATHS};
+pub use difficulty::Difficulty;
+pub use paths::{distances_to, shortest_paths};
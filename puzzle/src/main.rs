@@ -0,0 +1,51 @@
+use clap::{Parser, Subcommand};
+use puzzle::{parse_date, DailyPuzzles};
+use std::{fs, path::PathBuf};
+use time::Date;
+
+#[derive(Parser)]
+#[command(about = "Daily Kevin puzzles")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Pre-generate the puzzles of consecutive days into a JSON file
+    Calendar {
+        /// Offline link graph, a JSON object mapping titles to linked titles
+        #[arg(long)]
+        graph: PathBuf,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// First day, as YYYY-MM-DD
+        #[arg(long, value_parser = parse_date)]
+        from: Date,
+        #[arg(long, default_value_t = 30)]
+        days: u32,
+        /// Where to write the calendar; printed when absent
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+}
+
+fn main() -> anyhow::Result<()> {
+    match Cli::parse().command {
+        Command::Calendar {
+            graph,
+            seed,
+            from,
+            days,
+            out,
+        } => {
+            let puzzles = DailyPuzzles::from_graph_file(&graph, seed)?;
+            let calendar = serde_json::to_string_pretty(&puzzles.calendar(from, days))?;
+            match out {
+                Some(out) => fs::write(out, calendar + "\n")?,
+                None => println!("{calendar}"),
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use crawler_common::InMemoryLinks;
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::BuildHasher,
+};
+
+/// Link hops from every article of a graph that can reach `target`, found
+/// with a breadth-first search over the reversed links
+#[must_use]
+pub fn distances_to(graph: &InMemoryLinks, target: &str) -> HashMap<String, usize> {
+    let mut linked_from: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (title, linked_titles) in graph.links() {
+        for linked_title in linked_titles {
+            linked_from
+                .entry(linked_title.as_str())
+                .or_default()
+                .push(title.as_str());
+        }
+    }
+
+    let mut distances = HashMap::from([(target.to_string(), 0)]);
+    let mut queue = VecDeque::from([(target, 0)]);
+    while let Some((title, distance)) = queue.pop_front() {
+        for &source in linked_from.get(title).into_iter().flatten() {
+            if !distances.contains_key(source) {
+                distances.insert(source.to_string(), distance + 1);
+                queue.push_back((source, distance + 1));
+            }
+        }
+    }
+    distances
+}
+
+/// Up to `limit` shortest paths from `start` to the article `distances` was
+/// computed for, in lexicographic order
+#[must_use]
+pub fn shortest_paths<S: BuildHasher>(
+    graph: &InMemoryLinks,
+    distances: &HashMap<String, usize, S>,
+    start: &str,
+    limit: usize,
+) -> Vec<Vec<String>> {
+    let mut paths = Vec::new();
+    if distances.contains_key(start) {
+        let mut path = vec![start.to_string()];
+        extend_paths(graph, distances, &mut path, limit, &mut paths);
+    }
+    paths
+}
+
+fn extend_paths<S: BuildHasher>(
+    graph: &InMemoryLinks,
+    distances: &HashMap<String, usize, S>,
+    path: &mut Vec<String>,
+    limit: usize,
+    paths: &mut Vec<Vec<String>>,
+) {
+    let title = path.last().expect("paths are never empty");
+    let distance = distances[title];
+    if distance == 0 {
+        paths.push(path.clone());
+        return;
+    }
+
+    let mut next: Vec<&String> = graph
+        .links()
+        .get(title)
+        .into_iter()
+        .flatten()
+        .filter(|linked_title| distances.get(*linked_title) == Some(&(distance - 1)))
+        .collect();
+    next.sort();
+    next.dedup();
+
+    for linked_title in next {
+        if paths.len() >= limit {
+            return;
+        }
+        path.push(linked_title.clone());
+        extend_paths(graph, distances, path, limit, paths);
+        path.pop();
+    }
+}
@@ -0,0 +1,118 @@
+use crawler_common::InMemoryLinks;
+use puzzle::{parse_date, DailyPuzzles, Difficulty};
+use titles::KEVIN_BACON;
+
+fn graph() -> InMemoryLinks {
+    InMemoryLinks::from_edges([
+        ("A", vec![KEVIN_BACON]),
+        ("B", vec!["A", "C"]),
+        ("C", vec![KEVIN_BACON]),
+        ("D", vec!["B"]),
+        ("E", vec!["D"]),
+        ("Island", vec![]),
+    ])
+}
+
+#[test]
+fn same_day_same_puzzle() {
+    let date = parse_date("2026-10-19").unwrap();
+
+    let first = DailyPuzzles::new(graph(), 7).unwrap().puzzle(date);
+    let second = DailyPuzzles::new(graph(), 7).unwrap().puzzle(date);
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn puzzle_is_consistent() {
+    let puzzles = DailyPuzzles::new(graph(), 7).unwrap();
+
+    for puzzle in puzzles.calendar(parse_date("2026-01-01").unwrap(), 60) {
+        assert_ne!(puzzle.start, "Island");
+        assert_eq!(puzzle.target, KEVIN_BACON);
+        assert_eq!(puzzle.difficulty, Difficulty::of_hops(puzzle.hops));
+        assert!(!puzzle.paths.is_empty());
+        for path in &puzzle.paths {
+            assert_eq!(path.len(), puzzle.hops + 1);
+            assert_eq!(path[0], puzzle.start);
+        }
+    }
+}
+
+#[test]
+fn puzzles_vary_by_day() {
+    let puzzles = DailyPuzzles::new(graph(), 7).unwrap();
+
+    let mut starts: Vec<String> = puzzles
+        .calendar(parse_date("2026-01-01").unwrap(), 30)
+        .into_iter()
+        .map(|puzzle| puzzle.start)
+        .collect();
+    starts.sort();
+    starts.dedup();
+
+    assert!(starts.len() > 1);
+}
+
+#[test]
+fn calendar_covers_consecutive_days() {
+    let puzzles = DailyPuzzles::new(graph(), 7).unwrap();
+
+    let calendar = puzzles.calendar(parse_date("2026-12-30").unwrap(), 3);
+
+    let dates: Vec<String> = calendar
+        .iter()
+        .map(|puzzle| serde_json::to_value(puzzle).unwrap()["date"].to_string())
+        .collect();
+    assert_eq!(
+        dates,
+        [r#""2026-12-30""#, r#""2026-12-31""#, r#""2027-01-01""#]
+    );
+}
+
+#[test]
+fn graph_must_reach_target() {
+    let graph = InMemoryLinks::from_edges([("Island", vec![])]);
+
+    assert!(DailyPuzzles::new(graph, 0).is_err());
+}
+
+#[test]
+fn invalid_date() {
+    assert!(parse_date("2026-02-30").is_err());
+    assert!(parse_date("yesterday").is_err());
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn calendar_command() {
+    let dir = tempfile::tempdir().unwrap();
+    let graph_path = dir.path().join("graph.json");
+    let out = dir.path().join("calendar.json");
+    std::fs::write(&graph_path, serde_json::to_vec(&graph()).unwrap()).unwrap();
+
+    let status = std::process::Command::new(env!("CARGO_BIN_EXE_puzzle"))
+        .args([
+            "calendar",
+            "--seed",
+            "7",
+            "--from",
+            "2026-10-19",
+            "--days",
+            "5",
+        ])
+        .arg("--graph")
+        .arg(&graph_path)
+        .arg("--out")
+        .arg(&out)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let calendar: Vec<puzzle::Puzzle> =
+        serde_json::from_slice(&std::fs::read(out).unwrap()).unwrap();
+    let expected = DailyPuzzles::new(graph(), 7)
+        .unwrap()
+        .calendar(parse_date("2026-10-19").unwrap(), 5);
+    assert_eq!(calendar, expected);
+}
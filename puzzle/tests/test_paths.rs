@@ -0,0 +1,42 @@
+use crawler_common::InMemoryLinks;
+use puzzle::{distances_to, shortest_paths, Difficulty};
+
+fn graph() -> InMemoryLinks {
+    InMemoryLinks::from_edges([
+        ("A", vec!["B", "C"]),
+        ("B", vec!["T"]),
+        ("C", vec!["T", "B"]),
+        ("D", vec!["A"]),
+        ("Island", vec!["D"]),
+    ])
+}
+
+#[test]
+fn distances() {
+    let distances = distances_to(&graph(), "T");
+
+    assert_eq!(distances["T"], 0);
+    assert_eq!(distances["B"], 1);
+    assert_eq!(distances["A"], 2);
+    assert_eq!(distances["Island"], 4);
+}
+
+#[test]
+fn all_shortest_paths() {
+    let graph = graph();
+    let distances = distances_to(&graph, "T");
+
+    assert_eq!(
+        shortest_paths(&graph, &distances, "D", 10),
+        [["D", "A", "B", "T"], ["D", "A", "C", "T"]]
+    );
+    assert_eq!(shortest_paths(&graph, &distances, "D", 1).len(), 1);
+    assert!(shortest_paths(&graph, &distances, "Nowhere", 10).is_empty());
+}
+
+#[test]
+fn difficulty_of_hops() {
+    for hops in 0..8 {
+        assert!(Difficulty::of_hops(hops).hops().contains(&hops));
+    }
+}
@@ -0,0 +1,255 @@
+//! Completed searches are cached so popular starting pages aren't re-crawled
+//! on every page load. Only found paths are cached; a failed search may have
+//! failed for a transient reason.
+
+use crate::{Crawler, SearchResponse, SearchStatus};
+use axum::http::{header, HeaderMap, HeaderName, HeaderValue};
+use crawler_common::{canonical_title, LinkFilter, LinkMode, SearchOrderKind};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+    time::{Duration, SystemTime},
+};
+use tokio::sync::mpsc;
+
+/// Header reporting whether a response came from the cache
+pub const X_CACHE: HeaderName = HeaderName::from_static("x-cache");
+
+/// Limits of the result cache
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    /// How long a result is served from the cache, and how long browsers may
+    /// reuse it
+    pub ttl: Duration,
+    /// Results kept at most; the oldest is dropped to make room. Zero
+    /// disables the cache.
+    pub capacity: usize,
+    /// File the cache is saved to after it changes and loaded from on start,
+    /// if any
+    pub path: Option<PathBuf>,
+    /// How long to wait after a change before saving, so a burst of changes
+    /// is saved once
+    pub save_delay: Duration,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_hours(1),
+            capacity: 1000,
+            path: None,
+            save_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Everything that decides the result of a search
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CacheKey {
+    pub start: String,
+    pub target: String,
+    pub link_mode: LinkMode,
+    pub filter: LinkFilter,
+    pub order: SearchOrderKind,
+}
+
+impl CacheKey {
+    /// Key of a search by `crawler` from `starting_page`, following links
+    /// as `link_mode` says
+    #[must_use]
+    pub fn new(starting_page: &str, link_mode: LinkMode, crawler: &Crawler) -> Self {
+        Self {
+            start: canonical_title(starting_page),
+            target: crawler.target().to_string(),
+            link_mode,
+            filter: crawler.filter().clone(),
+            order: crawler.search_order(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    Hit,
+    Miss,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Entry {
+    key: CacheKey,
+    response: SearchResponse,
+    cached_at: SystemTime,
+}
+
+type Entries = Mutex<HashMap<CacheKey, Entry>>;
+
+pub struct ResultCache {
+    config: CacheConfig,
+    entries: Arc<Entries>,
+    /// Asks the task saving the cache to save it, once the first change
+    /// starts that task
+    saver: OnceLock<mpsc::Sender<()>>,
+}
+
+impl ResultCache {
+    /// Create a cache, loading any results saved at `config.path`. A missing
+    /// or unreadable file starts the cache empty.
+    #[must_use]
+    pub fn new(config: CacheConfig) -> Self {
+        let entries = config
+            .path
+            .as_ref()
+            .and_then(|path| match fs::read(path) {
+                Ok(bytes) => serde_json::from_slice::<Vec<Entry>>(&bytes)
+                    .inspect_err(|e| eprintln!("Ignoring cache file {}: {e}", path.display()))
+                    .ok(),
+                Err(_) => None,
+            })
+            .unwrap_or_default()
+            .into_iter()
+            .map(|entry| (entry.key.clone(), entry))
+            .collect();
+
+        let cache = Self {
+            config,
+            entries: Arc::new(Mutex::new(entries)),
+            saver: OnceLock::new(),
+        };
+        cache
+            .lock()
+            .retain(|_, entry| cache.remaining(entry).is_some());
+        cache
+    }
+
+    /// A cached response and how much longer it stays fresh
+    pub fn get(&self, key: &CacheKey) -> Option<(SearchResponse, Duration)> {
+        let mut entries = self.lock();
+        let entry = entries.get(key)?;
+        if let Some(remaining) = self.remaining(entry) {
+            return Some((entry.response.clone(), remaining));
+        }
+        entries.remove(key);
+        None
+    }
+
+    /// Cache a response if it found a path
+    pub fn insert(&self, key: CacheKey, response: &SearchResponse) {
+        if self.config.capacity == 0 || response.status != SearchStatus::Found {
+            return;
+        }
+
+        {
+            let mut entries = self.lock();
+            entries.retain(|_, entry| self.remaining(entry).is_some());
+            if !entries.contains_key(&key) && entries.len() >= self.config.capacity {
+                let oldest = entries
+                    .values()
+                    .min_by_key(|entry| entry.cached_at)
+                    .map(|entry| entry.key.clone());
+                if let Some(oldest) = oldest {
+                    entries.remove(&oldest);
+                }
+            }
+
+            let entry = Entry {
+                key: key.clone(),
+                response: response.clone(),
+                cached_at: SystemTime::now(),
+            };
+            entries.insert(key, entry);
+        }
+
+        self.request_save();
+    }
+
+    #[must_use]
+    pub fn ttl(&self) -> Duration {
+        self.config.ttl
+    }
+
+    fn remaining(&self, entry: &Entry) -> Option<Duration> {
+        let age = entry.cached_at.elapsed().unwrap_or_default();
+        self.config
+            .ttl
+            .checked_sub(age)
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    /// Have the cache saved soon. Saves are made one at a time by a single
+    /// task, each of the cache as it is when the save starts, so the newest
+    /// entries are always the last written.
+    fn request_save(&self) {
+        let Some(path) = &self.config.path else {
+            return;
+        };
+        let saver = self.saver.get_or_init(|| {
+            // One pending request is enough; later ones are covered by it
+            let (tx, mut rx) = mpsc::channel(1);
+            let (path, delay, entries) = (
+                path.clone(),
+                self.config.save_delay,
+                Arc::clone(&self.entries),
+            );
+            tokio::spawn(async move {
+                while rx.recv().await.is_some() {
+                    tokio::time::sleep(delay).await;
+                    let snapshot: Vec<Entry> = lock(&entries).values().cloned().collect();
+                    let path = path.clone();
+                    let _ = tokio::task::spawn_blocking(move || save(&path, &snapshot)).await;
+                }
+            });
+            tx
+        });
+        let _ = saver.try_send(());
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<CacheKey, Entry>> {
+        lock(&self.entries)
+    }
+}
+
+fn lock(entries: &Entries) -> std::sync::MutexGuard<'_, HashMap<CacheKey, Entry>> {
+    // Entries are replaced whole, so a poisoned map is still usable
+    entries.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn save(path: &Path, entries: &[Entry]) {
+    // Write a sibling file first so a crash never leaves a partial cache
+    let tmp = path.with_extension("tmp");
+    let written = serde_json::to_vec(entries)
+        .map_err(std::io::Error::from)
+        .and_then(|bytes| fs::write(&tmp, bytes))
+        .and_then(|()| fs::rename(&tmp, path));
+    if let Err(e) = written {
+        eprintln!("Could not save cache to {}: {e}", path.display());
+    }
+}
+
+/// Headers telling the client where a response came from and how long it
+/// may reuse it
+#[must_use]
+pub fn cache_headers(
+    response: &SearchResponse,
+    status: CacheStatus,
+    remaining: Duration,
+) -> HeaderMap {
+    let x_cache = match status {
+        CacheStatus::Hit => "HIT",
+        CacheStatus::Miss => "MISS",
+    };
+    let cache_control = if response.status == SearchStatus::Found {
+        format!("public, max-age={}", remaining.as_secs())
+    } else {
+        "no-store".to_string()
+    };
+
+    let mut headers = HeaderMap::new();
+    headers.insert(X_CACHE, HeaderValue::from_static(x_cache));
+    if let Ok(cache_control) = HeaderValue::try_from(cache_control) {
+        headers.insert(header::CACHE_CONTROL, cache_control);
+    }
+    headers
+}
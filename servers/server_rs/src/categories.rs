@@ -0,0 +1,107 @@
+//! Categories of articles, shown as hints about where a path leads.
+
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use crawler_common::{categories_params, API_URL};
+use dotenvy::dotenv;
+use std::{collections::HashMap, env, time::Duration};
+use wiki_response::WikiResponse;
+
+const CATEGORY_PREFIX: &str = "Category:";
+
+/// Somewhere the categories of an article can be looked up
+#[async_trait]
+pub trait CategorySource: Send + Sync {
+    /// Names of the article's categories, without the `Category:` prefix
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the categories cannot be retrieved.
+    async fn categories(&self, title: &str) -> anyhow::Result<Vec<String>>;
+}
+
+/// Looks up visible categories with `prop=categories`
+pub struct WikipediaCategories {
+    client: reqwest::Client,
+}
+
+impl WikipediaCategories {
+    /// # Errors
+    ///
+    /// `new` errors if:
+    ///   - The environment variable `CONTACT` cannot be found (used to create
+    ///     user agent for http requests)
+    ///   - There is an error encountered while creating the http client
+    pub fn new() -> anyhow::Result<Self> {
+        dotenv().ok();
+        let contact = env::var("CONTACT")?;
+        let user_agent = format!("MyWikiCrawler ({contact})");
+
+        let client = reqwest::Client::builder()
+            .user_agent(user_agent)
+            .timeout(Duration::from_secs(5))
+            .build()
+            .context("Error creating http client")?;
+
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl CategorySource for WikipediaCategories {
+    async fn categories(&self, title: &str) -> anyhow::Result<Vec<String>> {
+        let resp = self
+            .client
+            .get(API_URL)
+            .query(&categories_params(title))
+            .send()
+            .await
+            .map_err(|e| anyhow!("HTTP request failed: {e}"))?;
+
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "HTTP error {} for categories of {title}",
+                resp.status()
+            ));
+        }
+
+        let data: WikiResponse = resp
+            .json()
+            .await
+            .map_err(|e| anyhow!("Failed to decode JSON for categories of {title}: {e}"))?;
+
+        Ok(data
+            .query
+            .pages
+            .into_values()
+            .flat_map(|page| page.categories.unwrap_or_default())
+            .map(|category| {
+                category
+                    .title
+                    .strip_prefix(CATEGORY_PREFIX)
+                    .map_or(category.title.clone(), str::to_string)
+            })
+            .collect())
+    }
+}
+
+/// Categories from a fixed map. Titles without an entry have no categories,
+/// so an empty map leaves category hints out.
+#[derive(Default)]
+pub struct FixedCategories {
+    categories: HashMap<String, Vec<String>>,
+}
+
+impl FixedCategories {
+    #[must_use]
+    pub fn new(categories: HashMap<String, Vec<String>>) -> Self {
+        Self { categories }
+    }
+}
+
+#[async_trait]
+impl CategorySource for FixedCategories {
+    async fn categories(&self, title: &str) -> anyhow::Result<Vec<String>> {
+        Ok(self.categories.get(title).cloned().unwrap_or_default())
+    }
+}
@@ -0,0 +1,230 @@
+use crawler_common::{
+    Backend, CrawlEvent, CrawlResult, InMemoryLinks, LinkFilter, LinkMode, LinkSource, SearchOrder,
+    SearchOrderKind,
+};
+use std::sync::Arc;
+
+/// A crawler of any backend, cheap to clone and shared across requests
+#[derive(Clone)]
+pub enum Crawler {
+    Blocking(crawler_rs::WikipediaCrawler),
+    Async(crawler_rs_async::WikipediaCrawler),
+    Channels(crawler_rs_async_channels::WikipediaCrawler),
+    Pipelined(crawler_rs_pipelined::WikipediaCrawler),
+    Graph(crawler_rs_graph::WikipediaCrawler),
+}
+
+impl Crawler {
+    /// Create a crawler of the given backend that searches Wikipedia
+    ///
+    /// # Errors
+    ///
+    /// Errors if the underlying crawler cannot be created; see each crawler's
+    /// `new`. The graph backend has no graph to search, so always errors;
+    /// create it with `from_graph`.
+    pub async fn new(backend: Backend, worker_count: u8) -> anyhow::Result<Self> {
+        Ok(match backend {
+            // The blocking http client must be built off the async runtime
+            Backend::Blocking => Self::Blocking(
+                tokio::task::spawn_blocking(crawler_rs::WikipediaCrawler::new).await??,
+            ),
+            Backend::Async => Self::Async(crawler_rs_async::WikipediaCrawler::new(worker_count)?),
+            Backend::Channels => Self::Channels(crawler_rs_async_channels::WikipediaCrawler::new(
+                worker_count,
+            )?),
+            Backend::Pipelined => {
+                Self::Pipelined(crawler_rs_pipelined::WikipediaCrawler::new(worker_count)?)
+            }
+            Backend::Graph => anyhow::bail!("The graph backend needs an offline graph to search"),
+        })
+    }
+
+    /// Create a crawler of the given backend that looks up links in `source`
+    ///
+    /// # Panics
+    ///
+    /// Panics for the graph backend, which can't search a source it can only
+    /// look links up in; create it with `from_graph`.
+    #[must_use]
+    pub fn from_link_source(
+        backend: Backend,
+        worker_count: u8,
+        source: Arc<dyn LinkSource>,
+    ) -> Self {
+        match backend {
+            Backend::Blocking => {
+                Self::Blocking(crawler_rs::WikipediaCrawler::from_link_source(source))
+            }
+            Backend::Async => Self::Async(crawler_rs_async::WikipediaCrawler::from_link_source(
+                worker_count,
+                source,
+            )),
+            Backend::Channels => Self::Channels(
+                crawler_rs_async_channels::WikipediaCrawler::from_link_source(worker_count, source),
+            ),
+            Backend::Pipelined => Self::Pipelined(
+                crawler_rs_pipelined::WikipediaCrawler::from_link_source(worker_count, source),
+            ),
+            Backend::Graph => panic!("The graph backend needs a whole graph; use `from_graph`"),
+        }
+    }
+
+    /// Create a crawler of the given backend that searches an offline graph
+    #[must_use]
+    pub fn from_graph(backend: Backend, worker_count: u8, graph: InMemoryLinks) -> Self {
+        match backend {
+            Backend::Graph => Self::Graph(crawler_rs_graph::WikipediaCrawler::from_links(&graph)),
+            backend => Self::from_link_source(backend, worker_count, Arc::new(graph)),
+        }
+    }
+
+    #[must_use]
+    pub fn backend(&self) -> Backend {
+        match self {
+            Self::Blocking(_) => Backend::Blocking,
+            Self::Async(_) => Backend::Async,
+            Self::Channels(_) => Backend::Channels,
+            Self::Pipelined(_) => Backend::Pipelined,
+            Self::Graph(_) => Backend::Graph,
+        }
+    }
+
+    /// Whether dropping a `crawl` future stops the crawl. Blocking and graph
+    /// crawls run on a blocking thread that finishes regardless.
+    #[must_use]
+    pub fn stops_when_dropped(&self) -> bool {
+        !matches!(self, Self::Blocking(_) | Self::Graph(_))
+    }
+
+    /// Rules deciding which links the crawler follows
+    #[must_use]
+    pub fn filter(&self) -> &LinkFilter {
+        match self {
+            Self::Blocking(c) => c.filter(),
+            Self::Async(c) => c.filter(),
+            Self::Channels(c) => c.filter(),
+            Self::Pipelined(c) => c.filter(),
+            Self::Graph(c) => c.filter(),
+        }
+    }
+
+    /// The order pages are fetched in; only the pipelined crawler searches
+    /// in any order but breadth first
+    #[must_use]
+    pub fn search_order(&self) -> SearchOrderKind {
+        match self {
+            Self::Pipelined(c) => c.search_order().kind(),
+            _ => SearchOrderKind::Breadth,
+        }
+    }
+
+    /// Fetch pages in the given order instead of breadth first
+    ///
+    /// # Errors
+    ///
+    /// Errors for an order other than breadth first on any backend but the
+    /// pipelined one.
+    pub fn with_search_order(self, order: SearchOrder) -> anyhow::Result<Self> {
+        match self {
+            Self::Pipelined(c) => Ok(Self::Pipelined(c.with_search_order(order))),
+            crawler if matches!(order, SearchOrder::Breadth) => Ok(crawler),
+            crawler => anyhow::bail!(
+                "The {} crawler only searches breadth first, not in {} order",
+                crawler.backend(),
+                order.kind()
+            ),
+        }
+    }
+
+    #[must_use]
+    pub fn with_link_mode(self, link_mode: LinkMode) -> Self {
+        match self {
+            Self::Blocking(c) => Self::Blocking(c.with_link_mode(link_mode)),
+            Self::Async(c) => Self::Async(c.with_link_mode(link_mode)),
+            Self::Channels(c) => Self::Channels(c.with_link_mode(link_mode)),
+            Self::Pipelined(c) => Self::Pipelined(c.with_link_mode(link_mode)),
+            Self::Graph(c) => Self::Graph(c.with_link_mode(link_mode)),
+        }
+    }
+
+    /// The article searched for
+    #[must_use]
+    pub fn target(&self) -> &str {
+        match self {
+            Self::Blocking(c) => c.target(),
+            Self::Async(c) => c.target(),
+            Self::Channels(c) => c.target(),
+            Self::Pipelined(c) => c.target(),
+            Self::Graph(c) => c.target(),
+        }
+    }
+
+    /// Search for a path to `target` instead of Kevin Bacon
+    #[must_use]
+    pub fn with_target(self, target: &str) -> Self {
+        match self {
+            Self::Blocking(c) => Self::Blocking(c.with_target(target)),
+            Self::Async(c) => Self::Async(c.with_target(target)),
+            Self::Channels(c) => Self::Channels(c.with_target(target)),
+            Self::Pipelined(c) => Self::Pipelined(c.with_target(target)),
+            Self::Graph(c) => Self::Graph(c.with_target(target)),
+        }
+    }
+
+    #[must_use]
+    pub fn with_event_handler(self, on_event: impl Fn(CrawlEvent) + Send + Sync + 'static) -> Self {
+        match self {
+            Self::Blocking(c) => Self::Blocking(c.with_event_handler(on_event)),
+            Self::Async(c) => Self::Async(c.with_event_handler(on_event)),
+            Self::Channels(c) => Self::Channels(c.with_event_handler(on_event)),
+            Self::Pipelined(c) => Self::Pipelined(c.with_event_handler(on_event)),
+            Self::Graph(c) => Self::Graph(c.with_event_handler(on_event)),
+        }
+    }
+
+    /// Search for a path from `start_title` to the target, Kevin Bacon unless
+    /// set otherwise.
+    ///
+    /// Dropping the future stops the async backends; a blocking or graph
+    /// crawl runs to completion regardless.
+    ///
+    /// # Errors
+    ///
+    /// Errors if no path is found, or if a blocking or graph crawl panics.
+    pub async fn crawl(&self, start_title: &str) -> anyhow::Result<CrawlResult> {
+        match self {
+            Self::Blocking(c) => {
+                let crawler = c.clone();
+                let start_title = start_title.to_string();
+                tokio::task::spawn_blocking(move || crawler.crawl(&start_title)).await?
+            }
+            Self::Async(c) => c.crawl(start_title).await,
+            Self::Channels(c) => c.crawl(start_title).await,
+            Self::Pipelined(c) => c.crawl(start_title).await,
+            Self::Graph(c) => {
+                let crawler = c.clone();
+                let start_title = start_title.to_string();
+                tokio::task::spawn_blocking(move || crawler.crawl(&start_title)).await?
+            }
+        }
+    }
+
+    /// Titles linked from an article, filtered as a crawl would filter them
+    ///
+    /// # Errors
+    ///
+    /// Errors if the links can't be retrieved, or if a blocking lookup panics.
+    pub async fn linked_titles(&self, title: &str) -> anyhow::Result<Vec<String>> {
+        match self {
+            Self::Blocking(c) => {
+                let crawler = c.clone();
+                let title = title.to_string();
+                tokio::task::spawn_blocking(move || crawler.get_linked_titles(&title)).await?
+            }
+            Self::Async(c) => c.get_linked_titles(title).await,
+            Self::Channels(c) => c.get_linked_titles(title).await,
+            Self::Pipelined(c) => c.get_linked_titles(title).await,
+            Self::Graph(c) => c.get_linked_titles(title),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{AppState, PuzzleView};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use serde::Deserialize;
+use time::OffsetDateTime;
+use utoipa::IntoParams;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DailyParams {
+    /// Anonymous id of the player asking, so the answer is included once
+    /// they have submitted a guess
+    player: Option<String>,
+}
+
+/// The puzzle of a day, drawn from the offline graph. Its answer is left out
+/// until the day is over or the player has submitted a guess.
+///
+/// Only this route and the leaderboard hide it: searching, guessing or
+/// asking for hints from the day's start answers as for any other article.
+#[utoipa::path(
+    get,
+    path = "/daily/{date}",
+    params(("date" = String, Path, description = "Day of the puzzle, as YYYY-MM-DD"), DailyParams),
+    responses(
+        (status = 200, description = "The day's puzzle", body = PuzzleView),
+        (status = 400, description = "The date is invalid"),
+        (status = 404, description = "Daily puzzles are not configured"),
+    ),
+)]
+pub async fn get_daily(
+    State(state): State<AppState>,
+    Path(date): Path<String>,
+    Query(params): Query<DailyParams>,
+) -> Response {
+    let Some(daily) = &state.daily else {
+        return (StatusCode::NOT_FOUND, "Daily puzzles are not configured").into_response();
+    };
+    let date = match puzzle::parse_date(&date) {
+        Ok(date) => date,
+        Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    };
+
+    let submitted = match (&params.player, &state.leaderboard) {
+        (Some(player), Some(leaderboard)) => match leaderboard.has_submitted(player, date) {
+            Ok(submitted) => submitted,
+            Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        },
+        _ => false,
+    };
+    let past = date < OffsetDateTime::now_utc().date();
+
+    Json(daily.puzzle(date).view(past || submitted)).into_response()
+}
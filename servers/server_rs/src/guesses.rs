@@ -0,0 +1,88 @@
+use crate::{response, score, AppState, GuessRecord, GuessRequest, GuessResult, PlayerStats};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+
+/// Whether `id` can name a player: 1 to 64 ASCII letters, digits, `-` or `_`
+#[must_use]
+pub fn valid_player_id(id: &str) -> bool {
+    (1..=64).contains(&id.len())
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Score a guess and record it in the player's statistics, less any hints
+/// used on the start
+#[utoipa::path(
+    post,
+    path = "/guesses",
+    request_body = GuessRequest,
+    responses(
+        (status = 200, description = "The scored guess", body = GuessResult),
+        (status = 400, description = "The player id is invalid"),
+        (status = 422, description = "No path from the start was found, so the guess can't be scored"),
+    ),
+)]
+pub async fn create_guess(
+    State(state): State<AppState>,
+    Json(request): Json<GuessRequest>,
+) -> Response {
+    if !valid_player_id(&request.player) {
+        return (StatusCode::BAD_REQUEST, "Invalid player id").into_response();
+    }
+
+    let (search, ..) = response::cached_search(&state, &request.start, request.links, |_| {}).await;
+    let (Some(path), Some(hops)) = (search.path, search.hops) else {
+        return (StatusCode::UNPROCESSABLE_ENTITY, "No path was found").into_response();
+    };
+
+    let hints = state.hints.take(&request.player, &search.canonical_start);
+    let record = GuessRecord {
+        start: search.canonical_start,
+        guess: request.guess,
+        hops,
+        hints,
+    };
+    let guesses = state
+        .stats
+        .record(&request.player, &record)
+        .and_then(|()| state.stats.guesses(&request.player));
+    let player_stats = match guesses {
+        Ok(guesses) => PlayerStats::from_guesses(&guesses),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+
+    Json(GuessResult {
+        start: record.start,
+        guess: record.guess,
+        hops,
+        path,
+        score: score(record.guess, hops).with_hints(hints),
+        stats: player_stats,
+    })
+    .into_response()
+}
+
+/// Statistics of a player; all zero for a player who hasn't guessed yet
+#[utoipa::path(
+    get,
+    path = "/players/{id}/stats",
+    params(("id" = String, Path, description = "The player's anonymous id")),
+    responses(
+        (status = 200, description = "The player's statistics", body = PlayerStats),
+        (status = 400, description = "The player id is invalid"),
+    ),
+)]
+pub async fn get_player_stats(State(state): State<AppState>, Path(id): Path<String>) -> Response {
+    if !valid_player_id(&id) {
+        return (StatusCode::BAD_REQUEST, "Invalid player id").into_response();
+    }
+
+    match state.stats.guesses(&id) {
+        Ok(guesses) => Json(PlayerStats::from_guesses(&guesses)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
@@ -0,0 +1,252 @@
+//! Hints for players stuck on a guess. Each request reveals one more hint
+//! about the shortest path from a start: first a bound on its hops, then the
+//! categories of the first article on the way, then the articles along it one
+//! by one. Every hint used is taken off the player's next score for that
+//! start.
+//!
+//! Articles are picked from every known shortest path, preferring the one the
+//! most paths go through. All shortest paths are known for today's daily
+//! puzzle; other starts have the single path the crawler found.
+
+use crate::{
+    hint_penalty, response, valid_player_id, AppState, Difficulty, Hint, HintRequest, HintResponse,
+};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use crawler_common::{canonical_title, LinkMode};
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+use time::OffsetDateTime;
+
+/// The articles between the start and the target along the route the most
+/// shortest paths share, each with how many paths go through it
+#[must_use]
+pub fn likely_route(paths: &[Vec<String>]) -> Vec<(String, usize)> {
+    let mut candidates: Vec<&Vec<String>> = paths.iter().collect();
+    let hops = paths.first().map_or(0, |path| path.len().saturating_sub(1));
+    let mut route = Vec::new();
+
+    for hop in 1..hops {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for path in &candidates {
+            if let Some(title) = path.get(hop) {
+                *counts.entry(title).or_default() += 1;
+            }
+        }
+        // Ties go to the first title alphabetically, so hints are stable
+        let Some((title, count)) = counts
+            .into_iter()
+            .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then(b.cmp(a)))
+        else {
+            break;
+        };
+        let title = title.to_string();
+        candidates.retain(|path| path.get(hop) == Some(&title));
+        route.push((title, count));
+    }
+    route
+}
+
+/// Every hint about `paths`, in the order they are revealed. `categories`
+/// are those of the first article after the start; the category hint is left
+/// out when there are none.
+#[must_use]
+pub fn plan_hints(paths: &[Vec<String>], categories: Vec<String>) -> Vec<Hint> {
+    let Some(hops) = paths.first().map(|path| path.len().saturating_sub(1)) else {
+        return Vec::new();
+    };
+
+    let difficulty = Difficulty::of_hops(hops);
+    let bound = difficulty.hops();
+    let mut hints = vec![Hint::HopBound {
+        difficulty,
+        at_least: *bound.start(),
+        at_most: (*bound.end() != usize::MAX).then_some(*bound.end()),
+    }];
+
+    let route = likely_route(paths);
+    if !route.is_empty() && !categories.is_empty() {
+        hints.push(Hint::Category { categories });
+    }
+    hints.extend(
+        route
+            .into_iter()
+            .enumerate()
+            .map(|(i, (title, paths_through))| Hint::Article {
+                hop: i + 1,
+                title,
+                paths_through,
+            }),
+    );
+    hints
+}
+
+/// Limits of the hint ledger
+#[derive(Debug, Clone, Copy)]
+pub struct HintConfig {
+    /// How long hints used on a start are remembered after the last one
+    pub ttl: Duration,
+    /// Players' starts remembered at most; the one whose last hint is oldest
+    /// is forgotten to make room
+    pub capacity: usize,
+}
+
+impl Default for HintConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_hours(24),
+            capacity: 100_000,
+        }
+    }
+}
+
+struct Used {
+    count: usize,
+    revealed_at: Instant,
+}
+
+type Key = (String, String);
+
+/// How many hints each player has used on each start. Counts are forgotten
+/// once they expire or the ledger is full, so it can't grow without bound.
+pub struct HintLedger {
+    config: HintConfig,
+    used: Mutex<HashMap<Key, Used>>,
+}
+
+impl HintLedger {
+    #[must_use]
+    pub fn new(config: HintConfig) -> Self {
+        Self {
+            config,
+            used: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Reveal one more of `available` hints, returning how many are revealed
+    /// now, or `None` if all of them already were
+    pub fn reveal(&self, player: &str, start: &str, available: usize) -> Option<usize> {
+        let now = Instant::now();
+        let key = (player.to_string(), start.to_string());
+        let mut used = self.lock();
+        used.retain(|_, used| self.is_fresh(used, now));
+        if !used.contains_key(&key) && used.len() >= self.config.capacity {
+            let oldest = used
+                .iter()
+                .min_by_key(|(_, used)| used.revealed_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                used.remove(&oldest);
+            }
+        }
+
+        let used = used.entry(key).or_insert(Used {
+            count: 0,
+            revealed_at: now,
+        });
+        if used.count >= available {
+            return None;
+        }
+        used.count += 1;
+        used.revealed_at = now;
+        Some(used.count)
+    }
+
+    /// How many hints the player has revealed on `start`
+    pub fn used(&self, player: &str, start: &str) -> usize {
+        self.lock()
+            .get(&(player.to_string(), start.to_string()))
+            .filter(|used| self.is_fresh(used, Instant::now()))
+            .map_or(0, |used| used.count)
+    }
+
+    /// Forget the player's hints on `start` once they have guessed,
+    /// returning how many were used
+    pub fn take(&self, player: &str, start: &str) -> usize {
+        self.lock()
+            .remove(&(player.to_string(), start.to_string()))
+            .filter(|used| self.is_fresh(used, Instant::now()))
+            .map_or(0, |used| used.count)
+    }
+
+    fn is_fresh(&self, used: &Used, now: Instant) -> bool {
+        now.duration_since(used.revealed_at) < self.config.ttl
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<Key, Used>> {
+        // Counts are updated in one step, so a poisoned map is still usable
+        self.used.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Every shortest path known from `start`: all of them for today's daily
+/// puzzle, else the one the crawler finds
+async fn known_paths(state: &AppState, start: &str, link_mode: LinkMode) -> Vec<Vec<String>> {
+    if let Some(daily) = &state.daily {
+        let puzzle = daily.puzzle(OffsetDateTime::now_utc().date());
+        if puzzle.start == start && !puzzle.paths.is_empty() {
+            return puzzle.paths;
+        }
+    }
+
+    let (search, ..) = response::cached_search(state, start, link_mode, |_| {}).await;
+    search.path.into_iter().collect()
+}
+
+/// Reveal the next hint about the shortest path from a start
+#[utoipa::path(
+    post,
+    path = "/hints",
+    request_body = HintRequest,
+    responses(
+        (status = 200, description = "The hints revealed so far", body = HintResponse),
+        (status = 400, description = "The player id is invalid"),
+        (status = 409, description = "Every hint was already revealed"),
+        (status = 422, description = "No path from the start was found, so there is nothing to hint at"),
+        (status = 502, description = "The categories of the next article could not be retrieved"),
+    ),
+)]
+pub async fn create_hint(
+    State(state): State<AppState>,
+    Json(request): Json<HintRequest>,
+) -> Response {
+    if !valid_player_id(&request.player) {
+        return (StatusCode::BAD_REQUEST, "Invalid player id").into_response();
+    }
+
+    let start = canonical_title(&request.start);
+    let paths = known_paths(&state, &start, request.links).await;
+    if paths.is_empty() {
+        return (StatusCode::UNPROCESSABLE_ENTITY, "No path was found").into_response();
+    }
+
+    // Failing rather than leaving the categories out, which would change the
+    // hints planned for the start between requests
+    let categories = match likely_route(&paths).first() {
+        Some((title, _)) => match state.categories.categories(title).await {
+            Ok(categories) => categories,
+            Err(e) => return (StatusCode::BAD_GATEWAY, e.to_string()).into_response(),
+        },
+        None => Vec::new(),
+    };
+    let mut hints = plan_hints(&paths, categories);
+    let Some(revealed) = state.hints.reveal(&request.player, &start, hints.len()) else {
+        return (StatusCode::CONFLICT, "Every hint was already revealed").into_response();
+    };
+
+    let remaining = hints.len() - revealed;
+    hints.truncate(revealed);
+    Json(HintResponse {
+        start,
+        hints,
+        remaining,
+        penalty: hint_penalty(revealed),
+    })
+    .into_response()
+}
@@ -0,0 +1,323 @@
+//! Searches run in the background and polled by id, so no HTTP request has
+//! to stay open for a whole crawl.
+
+use crate::{
+    response, AppState, CacheKey, Crawler, CreatedSearch, JobStatus, JobView, ResultCache,
+    SearchProgress, SearchRequest, SearchStatus,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    time::{Duration, Instant},
+};
+use tokio::{
+    sync::{OwnedSemaphorePermit, Semaphore},
+    task::AbortHandle,
+};
+
+/// Limits of the background search pool
+#[derive(Debug, Clone, Copy)]
+pub struct JobConfig {
+    /// Searches allowed to run at once; the rest wait queued
+    pub concurrency: usize,
+    /// How long a finished search is kept for polling before it is dropped
+    pub retention: Duration,
+}
+
+impl Default for JobConfig {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            retention: Duration::from_mins(10),
+        }
+    }
+}
+
+struct Job {
+    view: JobView,
+    finished_at: Option<Instant>,
+    abort: Option<AbortHandle>,
+}
+
+/// Every known search job and the pool they run on
+pub struct Jobs {
+    config: JobConfig,
+    next_id: AtomicU64,
+    permits: Arc<Semaphore>,
+    entries: Mutex<HashMap<u64, Job>>,
+}
+
+impl Jobs {
+    #[must_use]
+    pub fn new(config: JobConfig) -> Self {
+        Self {
+            config,
+            next_id: AtomicU64::new(1),
+            permits: Arc::new(Semaphore::new(config.concurrency)),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Queue a search of `key` by `crawler`, returning its id. A path it
+    /// finds is put in `cache`, like that of any other search.
+    pub fn submit(
+        self: &Arc<Self>,
+        crawler: Crawler,
+        key: CacheKey,
+        cache: Arc<ResultCache>,
+    ) -> u64 {
+        self.purge_expired();
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        // A blocking or graph crawl keeps running once started even if its
+        // task is aborted, so such a task is only aborted while queued and
+        // otherwise holds its permit until the crawl has really finished
+        let stoppable = crawler.stops_when_dropped();
+        let start = key.start.clone();
+        let view = JobView {
+            id,
+            start: start.clone(),
+            status: JobStatus::Queued,
+            progress: SearchProgress::default(),
+            path: None,
+            error: None,
+        };
+
+        // Held until the job is inserted, so the task can't start it first
+        let mut entries = self.lock();
+        let jobs = Arc::clone(self);
+        let handle = tokio::spawn(async move {
+            let Ok(_permit) = jobs.permits.clone().acquire_owned().await else {
+                return;
+            };
+            {
+                let mut entries = jobs.lock();
+                // Cancelled while queued
+                let Some(job) = entries.get_mut(&id) else {
+                    return;
+                };
+                job.view.status = JobStatus::Running;
+                if !stoppable {
+                    job.abort = None;
+                }
+            }
+
+            let progress_jobs = Arc::clone(&jobs);
+            let response = response::search(crawler, &start, key.link_mode, move |event| {
+                progress_jobs.update(id, |job| {
+                    job.view.progress.apply(&event);
+                });
+            })
+            .await;
+            cache.insert(key, &response);
+
+            jobs.update(id, |job| {
+                match response.status {
+                    SearchStatus::Found => {
+                        job.view.status = JobStatus::Done;
+                        job.view.path = response.path;
+                    }
+                    SearchStatus::Failed => {
+                        job.view.status = JobStatus::Failed;
+                        job.view.error = response.error.map(|error| error.message);
+                    }
+                }
+                job.finished_at = Some(Instant::now());
+            });
+        });
+
+        let job = Job {
+            view,
+            finished_at: None,
+            abort: Some(handle.abort_handle()),
+        };
+        entries.insert(id, job);
+        id
+    }
+
+    /// Record a search already answered, such as from the result cache,
+    /// returning its id
+    pub fn finished(&self, start: String, path: Vec<String>) -> u64 {
+        self.purge_expired();
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let job = Job {
+            view: JobView {
+                id,
+                start,
+                status: JobStatus::Done,
+                progress: SearchProgress::default(),
+                path: Some(path),
+                error: None,
+            },
+            finished_at: Some(Instant::now()),
+            abort: None,
+        };
+        self.lock().insert(id, job);
+        id
+    }
+
+    /// Run a search outside a job once there is a place in the pool for it.
+    /// Dropping the future stops an async crawl and gives its place back at
+    /// once; a blocking or graph crawl can't be stopped, so it keeps its
+    /// place until it has really finished.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the search panics.
+    pub async fn run<T: Send + 'static>(
+        &self,
+        crawler: &Crawler,
+        search: impl Future<Output = T> + Send + 'static,
+    ) -> T {
+        let permit = self.permit().await;
+        if crawler.stops_when_dropped() {
+            let result = search.await;
+            drop(permit);
+            return result;
+        }
+
+        // The spawned task isn't aborted when its handle is dropped
+        let search = tokio::spawn(async move {
+            let result = search.await;
+            drop(permit);
+            result
+        });
+        search
+            .await
+            .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))
+    }
+
+    /// Wait for a place in the pool, for searches run outside a job. The
+    /// place is given back when the permit is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pool's semaphore is closed, which it never is.
+    pub async fn permit(&self) -> OwnedSemaphorePermit {
+        self.permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("the pool should never be closed")
+    }
+
+    /// Current state of a job, if it exists
+    pub fn get(&self, id: u64) -> Option<JobView> {
+        self.purge_expired();
+        self.lock().get(&id).map(|job| job.view.clone())
+    }
+
+    /// Stop a job and forget it, returning whether it existed. A running
+    /// blocking or graph crawl can't be stopped, so it is only forgotten and
+    /// keeps its place in the pool until it finishes.
+    pub fn cancel(&self, id: u64) -> bool {
+        let Some(job) = self.lock().remove(&id) else {
+            return false;
+        };
+        if let Some(abort) = job.abort {
+            abort.abort();
+        }
+        true
+    }
+
+    fn update(&self, id: u64, f: impl FnOnce(&mut Job)) {
+        if let Some(job) = self.lock().get_mut(&id) {
+            f(job);
+        }
+    }
+
+    fn purge_expired(&self) {
+        let retention = self.config.retention;
+        self.lock().retain(|_, job| {
+            job.finished_at
+                .is_none_or(|finished_at| finished_at.elapsed() < retention)
+        });
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<u64, Job>> {
+        // Every update leaves a job in a consistent state, so a poisoned map
+        // is still usable
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Queue a search to run in the background
+#[utoipa::path(
+    post,
+    path = "/searches",
+    request_body = SearchRequest,
+    responses((
+        status = 202,
+        description = "The search was queued, or answered at once from the result cache",
+        body = CreatedSearch,
+    )),
+)]
+pub async fn create_search(
+    State(state): State<AppState>,
+    Json(request): Json<SearchRequest>,
+) -> Response {
+    let key = CacheKey::new(&request.start, request.links, &state.crawler);
+    let cached = state
+        .cache
+        .get(&key)
+        .and_then(|(response, _)| response.path);
+    let created = if let Some(path) = cached {
+        CreatedSearch {
+            id: state.jobs.finished(key.start, path),
+            status: JobStatus::Done,
+        }
+    } else {
+        CreatedSearch {
+            id: state
+                .jobs
+                .submit(state.crawler.clone(), key, state.cache.clone()),
+            status: JobStatus::Queued,
+        }
+    };
+    (StatusCode::ACCEPTED, Json(created)).into_response()
+}
+
+/// Poll a background search
+#[utoipa::path(
+    get,
+    path = "/searches/{id}",
+    params(("id" = u64, Path, description = "Id returned when the search was created")),
+    responses(
+        (status = 200, description = "The search's current state", body = JobView),
+        (status = 404, description = "No such search, or it expired"),
+    ),
+)]
+pub async fn get_search(State(state): State<AppState>, Path(id): Path<u64>) -> Response {
+    match state.jobs.get(id) {
+        Some(job) => Json(job).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Cancel a background search and forget it
+#[utoipa::path(
+    delete,
+    path = "/searches/{id}",
+    params(("id" = u64, Path, description = "Id returned when the search was created")),
+    responses(
+        (status = 204, description = "The search was cancelled"),
+        (status = 404, description = "No such search, or it expired"),
+    ),
+)]
+pub async fn delete_search(State(state): State<AppState>, Path(id): Path<u64>) -> StatusCode {
+    if state.jobs.cancel(id) {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
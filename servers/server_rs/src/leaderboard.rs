@@ -0,0 +1,392 @@
+//! Daily and all-time leaderboards of the daily puzzle. Players submit only
+//! their guess; it is scored against the path the server found for the day's
+//! puzzle, and each player gets one submission per day. The all-time board
+//! totals every daily submission. Ties rank the earlier submission first.
+
+use crate::{
+    score, stats::record_guess, valid_player_id, AppState, GuessRecord, LeaderboardEntry,
+    LeaderboardPage, LeaderboardResult, LeaderboardSubmission,
+};
+use anyhow::Context;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Deserialize;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use time::{Date, OffsetDateTime};
+use utoipa::IntoParams;
+
+/// Most entries a page can hold
+pub const MAX_PER_PAGE: u32 = 100;
+
+/// A scored guess at a day's puzzle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    pub player: String,
+    pub name: String,
+    pub date: Date,
+    pub guess: usize,
+    pub hops: usize,
+    pub points: u32,
+    /// Milliseconds since the Unix epoch
+    pub submitted_at: u64,
+}
+
+/// Which page of a leaderboard to return
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct Page {
+    /// Page number, starting from 1
+    #[serde(default = "first_page")]
+    pub page: u32,
+    /// Entries per page, up to 100
+    #[serde(default = "default_per_page")]
+    pub per_page: u32,
+}
+
+fn first_page() -> u32 {
+    1
+}
+
+fn default_per_page() -> u32 {
+    20
+}
+
+impl Default for Page {
+    fn default() -> Self {
+        Self {
+            page: first_page(),
+            per_page: default_per_page(),
+        }
+    }
+}
+
+impl Page {
+    fn is_valid(self) -> bool {
+        self.page >= 1 && (1..=MAX_PER_PAGE).contains(&self.per_page)
+    }
+
+    fn offset(self) -> u64 {
+        u64::from(self.page.saturating_sub(1)) * u64::from(self.per_page)
+    }
+}
+
+/// Leaderboards kept in an embedded `SQLite` database
+pub struct Leaderboard {
+    connection: Mutex<Connection>,
+}
+
+impl Leaderboard {
+    /// Open the database at `path`, creating it if needed
+    ///
+    /// # Errors
+    ///
+    /// Errors if the database can't be opened or its table created.
+    pub fn open(path: &std::path::Path) -> anyhow::Result<Self> {
+        let connection = Connection::open(path)
+            .with_context(|| format!("Failed to open leaderboard database {}", path.display()))?;
+        Self::new(connection)
+    }
+
+    /// A database that lives only as long as this leaderboard
+    ///
+    /// # Errors
+    ///
+    /// Errors if the database can't be created.
+    pub fn open_in_memory() -> anyhow::Result<Self> {
+        Self::new(Connection::open_in_memory()?)
+    }
+
+    fn new(connection: Connection) -> anyhow::Result<Self> {
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS submissions (
+                id INTEGER PRIMARY KEY,
+                player TEXT NOT NULL,
+                name TEXT NOT NULL,
+                date TEXT NOT NULL,
+                guess INTEGER NOT NULL,
+                hops INTEGER NOT NULL,
+                points INTEGER NOT NULL,
+                submitted_at INTEGER NOT NULL,
+                UNIQUE (player, date)
+            );
+            CREATE INDEX IF NOT EXISTS submissions_by_date ON submissions (date);",
+        )?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Connection> {
+        // Every statement is atomic, so a poisoned connection is still usable
+        self.connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Add a submission, returning its rank on the day's leaderboard, or
+    /// `None` if the player already submitted that day
+    ///
+    /// # Errors
+    ///
+    /// Errors if the submission could not be stored.
+    pub fn submit(&self, submission: &Submission) -> anyhow::Result<Option<u64>> {
+        let connection = self.lock();
+        let date = submission.date.to_string();
+        let inserted = connection.execute(
+            "INSERT OR IGNORE INTO submissions
+                (player, name, date, guess, hops, points, submitted_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                submission.player,
+                submission.name,
+                date,
+                submission.guess,
+                submission.hops,
+                submission.points,
+                submission.submitted_at,
+            ],
+        )?;
+        if inserted == 0 {
+            return Ok(None);
+        }
+
+        let rank = connection
+            .query_row(
+                "SELECT rank FROM (
+                    SELECT player, ROW_NUMBER() OVER (ORDER BY points DESC, submitted_at, id) AS rank
+                    FROM submissions WHERE date = ?1
+                ) WHERE player = ?2",
+                params![date, submission.player],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(rank)
+    }
+
+    /// Whether the player has submitted a guess at the puzzle of `date`
+    ///
+    /// # Errors
+    ///
+    /// Errors if the leaderboard could not be read.
+    pub fn has_submitted(&self, player: &str, date: Date) -> anyhow::Result<bool> {
+        let submitted = self.lock().query_row(
+            "SELECT EXISTS (SELECT 1 FROM submissions WHERE player = ?1 AND date = ?2)",
+            params![player, date.to_string()],
+            |row| row.get(0),
+        )?;
+        Ok(submitted)
+    }
+
+    /// A page of the day's leaderboard
+    ///
+    /// # Errors
+    ///
+    /// Errors if the leaderboard could not be read.
+    pub fn daily(&self, date: Date, page: Page) -> anyhow::Result<LeaderboardPage> {
+        let connection = self.lock();
+        let date = date.to_string();
+        let total = connection.query_row(
+            "SELECT COUNT(*) FROM submissions WHERE date = ?1",
+            [&date],
+            |row| row.get(0),
+        )?;
+        let mut statement = connection.prepare(
+            "SELECT ROW_NUMBER() OVER (ORDER BY points DESC, submitted_at, id) AS rank,
+                name, points, 1, submitted_at
+            FROM submissions WHERE date = ?1
+            ORDER BY rank LIMIT ?2 OFFSET ?3",
+        )?;
+        let entries = statement
+            .query_map(params![date, page.per_page, page.offset()], entry)?
+            .collect::<Result<_, _>>()?;
+
+        Ok(LeaderboardPage {
+            entries,
+            page: page.page,
+            per_page: page.per_page,
+            total,
+        })
+    }
+
+    /// A page of the leaderboard of points totalled over every day
+    ///
+    /// # Errors
+    ///
+    /// Errors if the leaderboard could not be read.
+    pub fn all_time(&self, page: Page) -> anyhow::Result<LeaderboardPage> {
+        let connection = self.lock();
+        let total = connection.query_row(
+            "SELECT COUNT(DISTINCT player) FROM submissions",
+            [],
+            |row| row.get(0),
+        )?;
+        let mut statement = connection.prepare(
+            "SELECT ROW_NUMBER() OVER (
+                    ORDER BY SUM(points) DESC, MAX(submitted_at), MAX(id)
+                ) AS rank,
+                (SELECT name FROM submissions AS latest
+                    WHERE latest.player = submissions.player ORDER BY id DESC LIMIT 1),
+                SUM(points), COUNT(*), MAX(submitted_at)
+            FROM submissions GROUP BY player
+            ORDER BY rank LIMIT ?1 OFFSET ?2",
+        )?;
+        let entries = statement
+            .query_map(params![page.per_page, page.offset()], entry)?
+            .collect::<Result<_, _>>()?;
+
+        Ok(LeaderboardPage {
+            entries,
+            page: page.page,
+            per_page: page.per_page,
+            total,
+        })
+    }
+}
+
+fn entry(row: &Row) -> rusqlite::Result<LeaderboardEntry> {
+    Ok(LeaderboardEntry {
+        rank: row.get(0)?,
+        name: row.get(1)?,
+        points: row.get(2)?,
+        games: row.get(3)?,
+        submitted_at: row.get(4)?,
+    })
+}
+
+/// Whether `name` can be shown on a leaderboard: 1 to 32 characters, none of
+/// them control characters, and not only whitespace
+#[must_use]
+pub fn valid_display_name(name: &str) -> bool {
+    (1..=32).contains(&name.chars().count())
+        && !name.trim().is_empty()
+        && !name.chars().any(char::is_control)
+}
+
+/// Submit a guess at today's puzzle, less any hints used on it
+#[utoipa::path(
+    post,
+    path = "/leaderboard/daily",
+    request_body = LeaderboardSubmission,
+    responses(
+        (status = 200, description = "The scored submission", body = LeaderboardResult),
+        (status = 400, description = "The player id or name is invalid"),
+        (status = 404, description = "Daily puzzles are not configured"),
+        (status = 409, description = "The player already submitted today"),
+    ),
+)]
+pub async fn submit_daily(
+    State(state): State<AppState>,
+    Json(request): Json<LeaderboardSubmission>,
+) -> Response {
+    let (Some(daily), Some(leaderboard)) = (&state.daily, &state.leaderboard) else {
+        return (StatusCode::NOT_FOUND, "Daily puzzles are not configured").into_response();
+    };
+    if !valid_player_id(&request.player) {
+        return (StatusCode::BAD_REQUEST, "Invalid player id").into_response();
+    }
+    if !valid_display_name(&request.name) {
+        return (StatusCode::BAD_REQUEST, "Invalid name").into_response();
+    }
+
+    let now = OffsetDateTime::now_utc();
+    let puzzle = daily.puzzle(now.date());
+    // Only looked at for now, so a rejected submission doesn't use them up
+    let hints = state.hints.used(&request.player, &puzzle.start);
+    let score = score(request.guess, puzzle.hops).with_hints(hints);
+    let submission = Submission {
+        player: request.player,
+        name: request.name.trim().to_string(),
+        date: puzzle.date,
+        guess: request.guess,
+        hops: puzzle.hops,
+        points: score.points,
+        submitted_at: u64::try_from(now.unix_timestamp_nanos() / 1_000_000).unwrap_or_default(),
+    };
+
+    match leaderboard.submit(&submission) {
+        Ok(Some(rank)) => {
+            state.hints.take(&submission.player, &puzzle.start);
+            let record = GuessRecord {
+                start: puzzle.start.clone(),
+                guess: submission.guess,
+                hops: submission.hops,
+                hints,
+            };
+            record_guess(state.stats.as_ref(), &submission.player, &record);
+            // Submissions are only taken on the day, so the answer stays hidden
+            Json(LeaderboardResult {
+                puzzle: puzzle.view(false),
+                score,
+                rank,
+            })
+            .into_response()
+        }
+        Ok(None) => (StatusCode::CONFLICT, "Today's puzzle was already submitted").into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+/// The leaderboard of a day's puzzle
+#[utoipa::path(
+    get,
+    path = "/leaderboard/daily/{date}",
+    params(("date" = String, Path, description = "Day of the puzzle, as YYYY-MM-DD"), Page),
+    responses(
+        (status = 200, description = "A page of the day's leaderboard", body = LeaderboardPage),
+        (status = 400, description = "The date or page is invalid"),
+        (status = 404, description = "Daily puzzles are not configured"),
+    ),
+)]
+pub async fn get_daily_leaderboard(
+    State(state): State<AppState>,
+    Path(date): Path<String>,
+    Query(page): Query<Page>,
+) -> Response {
+    let Some(leaderboard) = &state.leaderboard else {
+        return (StatusCode::NOT_FOUND, "Daily puzzles are not configured").into_response();
+    };
+    let date = match puzzle::parse_date(&date) {
+        Ok(date) => date,
+        Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    };
+
+    page_response(page, |page| leaderboard.daily(date, page))
+}
+
+/// The leaderboard of points totalled over every daily puzzle
+#[utoipa::path(
+    get,
+    path = "/leaderboard",
+    params(Page),
+    responses(
+        (status = 200, description = "A page of the all-time leaderboard", body = LeaderboardPage),
+        (status = 400, description = "The page is invalid"),
+        (status = 404, description = "Daily puzzles are not configured"),
+    ),
+)]
+pub async fn get_leaderboard(State(state): State<AppState>, Query(page): Query<Page>) -> Response {
+    let Some(leaderboard) = &state.leaderboard else {
+        return (StatusCode::NOT_FOUND, "Daily puzzles are not configured").into_response();
+    };
+
+    page_response(page, |page| leaderboard.all_time(page))
+}
+
+fn page_response(
+    page: Page,
+    read: impl FnOnce(Page) -> anyhow::Result<LeaderboardPage>,
+) -> Response {
+    if !page.is_valid() {
+        let message = format!("Pages start from 1 and hold 1 to {MAX_PER_PAGE} entries");
+        return (StatusCode::BAD_REQUEST, message).into_response();
+    }
+
+    match read(page) {
+        Ok(page) => Json(page).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
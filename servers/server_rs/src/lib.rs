@@ -0,0 +1,252 @@
+mod cache;
+mod categories;
+mod crawler;
+mod daily;
+mod guesses;
+mod hints;
+mod jobs;
+mod leaderboard;
+mod navigate;
+mod openapi;
+mod random;
+mod response;
+mod sse;
+mod stats;
+mod ws;
+
+pub use cache::{CacheConfig, CacheKey, CacheStatus, ResultCache, X_CACHE};
+pub use categories::{CategorySource, FixedCategories, WikipediaCategories};
+pub use crawler::Crawler;
+pub use crawler_common::Backend;
+pub use guesses::valid_player_id;
+pub use hints::{likely_route, plan_hints, HintConfig, HintLedger};
+pub use jobs::{JobConfig, Jobs};
+pub use leaderboard::{valid_display_name, Leaderboard, Page, Submission, MAX_PER_PAGE};
+pub use navigate::{MoveError, NavigationConfig, Navigations};
+pub use openapi::ApiDoc;
+pub use puzzle::DailyPuzzles;
+pub use random::{ArticleSampler, FixedSampler, RandomConfig, WikipediaSampler};
+pub use server_types::{
+    hint_penalty, score, CreatedSearch, Difficulty, GuessRecord, GuessRequest, GuessResult, Hint,
+    HintRequest, HintResponse, JobStatus, JobView, LeaderboardEntry, LeaderboardPage,
+    LeaderboardResult, LeaderboardSubmission, NavigationMove, NavigationRequest, NavigationStatus,
+    NavigationView, Outcome, PlayerStats, Puzzle, PuzzleView, RandomArticle, Score, SearchError,
+    SearchProgress, SearchRequest, SearchResponse, SearchStatus, HINT_PENALTY,
+};
+pub use stats::{InMemoryStats, SqliteStats, StatsStore};
+pub use ws::{ClientMessage, ServerMessage, PROTOCOL_VERSION};
+
+use axum::extract::{Path, Query, State};
+use axum::{
+    response::Json,
+    routing::{get, post},
+    Router,
+};
+use crawler_common::LinkMode;
+use http::{HeaderMap, Method};
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::task::AbortHandle;
+use tower_http::cors::{Any, CorsLayer};
+use utoipa::IntoParams;
+
+/// Articles games start from when the client doesn't pick one. The target
+/// itself isn't one, as it would make a game of zero hops.
+pub const STARTING_PAGES: [&str; 2] = ["Footloose (1984 film)", "Herbert Ross"];
+/// State shared by every request
+#[derive(Clone)]
+pub struct AppState {
+    pub crawler: Crawler,
+    pub starting_pages: Arc<Vec<String>>,
+    pub jobs: Arc<Jobs>,
+    pub cache: Arc<ResultCache>,
+    pub sampler: Arc<dyn ArticleSampler>,
+    pub random: RandomConfig,
+    pub categories: Arc<dyn CategorySource>,
+    pub hints: Arc<HintLedger>,
+    /// Daily puzzles, when an offline graph is configured
+    pub daily: Option<Arc<DailyPuzzles>>,
+    /// Leaderboards of the daily puzzle, when daily puzzles are configured
+    pub leaderboard: Option<Arc<Leaderboard>>,
+    pub stats: Arc<dyn StatsStore>,
+    pub navigations: Arc<Navigations>,
+}
+
+impl AppState {
+    #[must_use]
+    pub fn new(crawler: Crawler) -> Self {
+        let starting_pages = STARTING_PAGES.map(str::to_string).to_vec();
+        Self {
+            crawler,
+            sampler: Arc::new(FixedSampler::new(starting_pages.clone())),
+            random: RandomConfig::default(),
+            categories: Arc::new(FixedCategories::default()),
+            hints: Arc::new(HintLedger::new(HintConfig::default())),
+            daily: None,
+            leaderboard: None,
+            stats: Arc::new(InMemoryStats::new()),
+            starting_pages: Arc::new(starting_pages),
+            jobs: Arc::new(Jobs::new(JobConfig::default())),
+            cache: Arc::new(ResultCache::new(CacheConfig::default())),
+            navigations: Arc::new(Navigations::new(NavigationConfig::default())),
+        }
+    }
+
+    /// Run background searches with the given limits
+    #[must_use]
+    pub fn with_job_config(mut self, config: JobConfig) -> Self {
+        self.jobs = Arc::new(Jobs::new(config));
+        self
+    }
+
+    /// Remember the hints players use with the given limits
+    #[must_use]
+    pub fn with_hint_config(mut self, config: HintConfig) -> Self {
+        self.hints = Arc::new(HintLedger::new(config));
+        self
+    }
+
+    /// Cache search results with the given limits
+    #[must_use]
+    pub fn with_cache_config(mut self, config: CacheConfig) -> Self {
+        self.cache = Arc::new(ResultCache::new(config));
+        self
+    }
+
+    /// Run navigation sessions with the given limits
+    #[must_use]
+    pub fn with_navigation_config(mut self, config: NavigationConfig) -> Self {
+        self.navigations = Arc::new(Navigations::new(config));
+        self
+    }
+
+    /// Look for `/random` articles with the given limits
+    #[must_use]
+    pub fn with_random_config(mut self, config: RandomConfig) -> Self {
+        self.random = config;
+        self
+    }
+
+    /// Draw `/random` candidates from `sampler` instead of the starting pages
+    #[must_use]
+    pub fn with_sampler(mut self, sampler: Arc<dyn ArticleSampler>) -> Self {
+        self.sampler = sampler;
+        self
+    }
+
+    /// Look up categories for hints in `categories`; without it, hints leave
+    /// categories out
+    #[must_use]
+    pub fn with_categories(mut self, categories: Arc<dyn CategorySource>) -> Self {
+        self.categories = categories;
+        self
+    }
+
+    /// Serve `/daily/:date` from the given puzzles
+    #[must_use]
+    pub fn with_daily_puzzles(mut self, daily: DailyPuzzles) -> Self {
+        self.daily = Some(Arc::new(daily));
+        self
+    }
+
+    /// Rank submissions to the daily puzzle on `leaderboard`
+    #[must_use]
+    pub fn with_leaderboard(mut self, leaderboard: Leaderboard) -> Self {
+        self.leaderboard = Some(Arc::new(leaderboard));
+        self
+    }
+
+    /// Keep player statistics in `stats` instead of in memory
+    #[must_use]
+    pub fn with_stats_store(mut self, stats: Arc<dyn StatsStore>) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    fn random_starting_page(&self) -> &str {
+        self.starting_pages
+            .choose(&mut rand::thread_rng())
+            .map_or(STARTING_PAGES[0], String::as_str)
+    }
+}
+
+/// Build the application with all routes
+pub fn router(state: AppState) -> Router {
+    let cors = CorsLayer::new()
+        .allow_methods([Method::GET, Method::POST, Method::DELETE])
+        .allow_headers(Any)
+        .allow_origin(Any);
+
+    Router::new()
+        .route("/ws", get(ws::game_session))
+        .route("/openapi.json", get(openapi::get_openapi))
+        .route("/random", get(random::get_random))
+        .route("/daily/:date", get(daily::get_daily))
+        .route("/leaderboard", get(leaderboard::get_leaderboard))
+        .route("/leaderboard/daily", post(leaderboard::submit_daily))
+        .route(
+            "/leaderboard/daily/:date",
+            get(leaderboard::get_daily_leaderboard),
+        )
+        .route("/hints", post(hints::create_hint))
+        .route("/guesses", post(guesses::create_guess))
+        .route("/players/:id/stats", get(guesses::get_player_stats))
+        .route("/navigations", post(navigate::create_navigation))
+        .route("/navigations/:id", get(navigate::get_navigation))
+        .route("/navigations/:id/moves", post(navigate::create_move))
+        .route("/:starting_page", get(get_path))
+        .route("/search/:starting_page/events", get(sse::get_path_events))
+        .route("/schemas/search-response.json", get(response::get_schema))
+        .route("/searches", post(jobs::create_search))
+        .route(
+            "/searches/:id",
+            get(jobs::get_search).delete(jobs::delete_search),
+        )
+        .layer(cors)
+        .with_state(state)
+}
+
+/// Aborts a spawned search when dropped, such as along with the stream or
+/// socket of a client that disconnected
+pub(crate) struct AbortOnDrop(pub(crate) AbortHandle);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct SearchParams {
+    /// Which of each article's links to follow
+    #[serde(default)]
+    links: LinkMode,
+}
+
+/// Search for the shortest path from an article to Kevin Bacon
+#[utoipa::path(
+    get,
+    path = "/{starting_page}",
+    params(("starting_page" = String, Path, description = "Article to start from"), SearchParams),
+    responses((
+        status = 200,
+        description = "The finished search",
+        body = SearchResponse,
+        headers(
+            ("x-cache" = String, description = "`HIT` when answered from the result cache, else `MISS`"),
+            ("cache-control" = String, description = "How long the answer may be reused; `no-store` when no path was found"),
+        ),
+    )),
+)]
+async fn get_path(
+    State(state): State<AppState>,
+    Path(starting_page): Path<String>,
+    Query(params): Query<SearchParams>,
+) -> (HeaderMap, Json<SearchResponse>) {
+    let (response, cache_status, remaining) =
+        response::cached_search(&state, &starting_page, params.links, |_| {}).await;
+    let headers = cache::cache_headers(&response, cache_status, remaining);
+    (headers, Json(response))
+}
@@ -1,37 +1,148 @@
-use axum::extract::Path;
-use axum::{response::Json, routing::get, Router};
-use crawler_rs::WikipediaCrawler;
+use crawler_common::{InMemoryLinks, Popularity, SearchOrderKind};
 use dotenvy::dotenv;
-use http::Method;
-use serde_json::{json, Value};
-use tower_http::cors::{Any, CorsLayer};
+use server_rs::{
+    router, AppState, ArticleSampler, Backend, CacheConfig, Crawler, DailyPuzzles, FixedSampler,
+    HintConfig, JobConfig, Leaderboard, NavigationConfig, RandomConfig, SqliteStats,
+    WikipediaCategories, WikipediaSampler,
+};
+use std::{path::PathBuf, sync::Arc, time::Duration};
 
 #[tokio::main]
 async fn main() {
     dotenv().expect(".env file not found");
 
-    let port: String = std::env::var("VITE_BACKEND_PORT")
-        .expect("`VITE_BACKEND_PORT` should be set in .env file")
-        .to_string();
+    let port: String =
+        std::env::var("VITE_BACKEND_PORT").expect("`VITE_BACKEND_PORT` should be set in .env file");
 
-    let cors = CorsLayer::new()
-        .allow_methods([Method::GET])
-        .allow_origin(Any);
+    let (crawler, sampler) = crawler_from_env().await;
+    let defaults = JobConfig::default();
+    // No search could ever start without a permit
+    let concurrency = std::env::var("SEARCH_CONCURRENCY")
+        .map_or(Ok(defaults.concurrency), |concurrency| concurrency.parse())
+        .ok()
+        .filter(|&concurrency| concurrency >= 1)
+        .expect("`SEARCH_CONCURRENCY` should be a whole number of at least 1");
+    let job_config = JobConfig {
+        concurrency,
+        retention: Duration::from_secs(env_or(
+            "SEARCH_RETENTION_SECS",
+            defaults.retention.as_secs(),
+        )),
+    };
+    let categories = WikipediaCategories::new().expect("category source should be created");
 
-    let app = Router::new()
-        .route("/:starting_page", get(get_path))
-        .layer(cors);
+    let cache_defaults = CacheConfig::default();
+    let cache_config = CacheConfig {
+        ttl: Duration::from_secs(env_or("CACHE_TTL_SECS", cache_defaults.ttl.as_secs())),
+        capacity: env_or("CACHE_CAPACITY", cache_defaults.capacity),
+        path: std::env::var_os("CACHE_PATH").map(PathBuf::from),
+        ..cache_defaults
+    };
+    let hint_defaults = HintConfig::default();
+    let hint_config = HintConfig {
+        ttl: Duration::from_secs(env_or("HINT_TTL_SECS", hint_defaults.ttl.as_secs())),
+        capacity: env_or("HINT_CAPACITY", hint_defaults.capacity),
+    };
+    let navigation_defaults = NavigationConfig::default();
+    let navigation_config = NavigationConfig {
+        time_limit: Duration::from_secs(env_or(
+            "NAVIGATION_TIME_LIMIT_SECS",
+            navigation_defaults.time_limit.as_secs(),
+        )),
+        ..navigation_defaults
+    };
+    let random_config = RandomConfig {
+        time_limit: Duration::from_secs(env_or(
+            "RANDOM_TIME_LIMIT_SECS",
+            RandomConfig::default().time_limit.as_secs(),
+        )),
+    };
+    let mut state = AppState::new(crawler.clone())
+        .with_job_config(job_config)
+        .with_navigation_config(navigation_config)
+        .with_random_config(random_config)
+        .with_cache_config(cache_config)
+        .with_hint_config(hint_config)
+        .with_sampler(sampler)
+        .with_categories(Arc::new(categories));
+    if let Some(graph) = std::env::var_os("DAILY_GRAPH") {
+        let daily = DailyPuzzles::from_graph_file(graph.as_ref(), env_or("DAILY_SEED", 0))
+            .expect("daily puzzle graph should load");
+        let leaderboard = match std::env::var_os("LEADERBOARD_DB") {
+            Some(path) => Leaderboard::open(path.as_ref()),
+            None => Leaderboard::open_in_memory(),
+        }
+        .expect("leaderboard database should open");
+        state = state
+            .with_daily_puzzles(daily)
+            .with_leaderboard(leaderboard);
+    }
+    if let Some(path) = std::env::var_os("STATS_DB") {
+        let store = SqliteStats::open(path.as_ref()).expect("stats database should open");
+        state = state.with_stats_store(Arc::new(store));
+    }
+    let app = router(state);
 
     let host = ["127.0.0.1", &port].join(":");
     let listener = tokio::net::TcpListener::bind(&host).await.unwrap();
 
-    println!("Server running on port: {port}");
+    println!(
+        "Server running on port: {port} with the {} crawler in {} order",
+        crawler.backend(),
+        crawler.search_order()
+    );
     axum::serve(listener, app).await.unwrap();
 }
 
-async fn get_path(Path(starting_page): Path<String>) -> Json<Value> {
-    let path = WikipediaCrawler::new(&starting_page).crawl().await;
-    let result = path.unwrap_or(vec!["Could not reach Kevin Bacon".to_string()]);
+/// The crawler configured by the `CRAWLER_` settings, and a sampler of random
+/// articles it can search from
+async fn crawler_from_env() -> (Crawler, Arc<dyn ArticleSampler>) {
+    let backend: Backend = std::env::var("CRAWLER_BACKEND")
+        .map_or(Ok(Backend::default()), |backend| backend.parse())
+        .expect("`CRAWLER_BACKEND` should be blocking, async, channels, pipelined or graph");
+    // A crawl without workers would wait for them forever
+    let workers = std::env::var("CRAWLER_WORKERS")
+        .map_or(Ok(5), |workers| workers.parse())
+        .ok()
+        .filter(|&workers: &u8| workers >= 1)
+        .expect("`CRAWLER_WORKERS` should be a whole number from 1 to 255");
+    let order: SearchOrderKind = std::env::var("CRAWLER_ORDER")
+        .map_or(Ok(SearchOrderKind::default()), |order| order.parse())
+        .expect("`CRAWLER_ORDER` should be breadth, prioritized or best-first");
+    let graph = std::env::var_os("CRAWLER_GRAPH");
+    assert!(
+        graph.is_some() || order == SearchOrderKind::Breadth,
+        "`CRAWLER_ORDER` needs `CRAWLER_GRAPH` to score titles with"
+    );
+    // Random articles are drawn from the graph too, so they can be reached
+    match graph {
+        Some(graph) => {
+            let bytes = std::fs::read(graph).expect("crawler graph should be readable");
+            let graph: InMemoryLinks =
+                serde_json::from_slice(&bytes).expect("crawler graph should decode");
+            let sampler = FixedSampler::new(graph.links().keys().cloned().collect());
+            // Titles many others link to are fetched first
+            let order = order.with_heuristic(Arc::new(Popularity::from_links(&graph)));
+            (
+                Crawler::from_graph(backend, workers, graph)
+                    .with_search_order(order)
+                    .expect("`CRAWLER_ORDER` should be breadth for this backend"),
+                Arc::new(sampler),
+            )
+        }
+        None => (
+            Crawler::new(backend, workers)
+                .await
+                .expect("crawler should be created"),
+            Arc::new(WikipediaSampler::new().expect("sampler should be created")),
+        ),
+    }
+}
 
-    Json(json!({"starting_page": starting_page, "result": result }))
+/// Read an optional numeric setting from the environment
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
 }
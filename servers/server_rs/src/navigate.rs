@@ -0,0 +1,396 @@
+//! "Navigate yourself" games, where the player clicks from the start article
+//! to Kevin Bacon instead of guessing the number of hops. Each move must
+//! follow one of the current article's links, as the crawler sees them. A
+//! session is kept by id so the player can resume it, until its time runs
+//! out.
+
+use crate::{
+    response, AppState, NavigationMove, NavigationRequest, NavigationStatus, NavigationView,
+    SearchStatus,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use crawler_common::{canonical_title, LinkMode};
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+use titles::KEVIN_BACON;
+use tokio::task::JoinHandle;
+
+/// Limits of navigation sessions
+#[derive(Debug, Clone, Copy)]
+pub struct NavigationConfig {
+    /// How long a player has to reach Kevin Bacon
+    pub time_limit: Duration,
+    /// How long an ended session can still be looked up
+    pub retention: Duration,
+}
+
+impl Default for NavigationConfig {
+    fn default() -> Self {
+        Self {
+            time_limit: Duration::from_mins(15),
+            retention: Duration::from_mins(10),
+        }
+    }
+}
+
+struct Navigation {
+    view: NavigationView,
+    deadline: Instant,
+    ended_at: Option<Instant>,
+    /// Shortest path from the start, kept from the player until they finish
+    optimum: Option<Vec<String>>,
+    /// The search for `optimum`, while it may still be running
+    search: Option<JoinHandle<()>>,
+}
+
+impl Drop for Navigation {
+    fn drop(&mut self) {
+        if let Some(search) = &self.search {
+            search.abort();
+        }
+    }
+}
+
+impl Navigation {
+    /// Bring the status, remaining time and optimum up to date
+    fn refresh(&mut self, now: Instant) {
+        if self.view.status == NavigationStatus::Playing && now >= self.deadline {
+            self.view.status = NavigationStatus::Expired;
+            self.view.links.clear();
+            self.ended_at = Some(self.deadline);
+            if let Some(search) = self.search.take() {
+                search.abort();
+            }
+        }
+        if self.view.status == NavigationStatus::Finished && self.view.optimum.is_none() {
+            if let Some(path) = &self.optimum {
+                self.view.extra_hops = Some(self.view.hops.saturating_sub(path.len() - 1));
+                self.view.optimum = Some(path.clone());
+            }
+        }
+        if self.view.status == NavigationStatus::Playing {
+            self.view.remaining_secs = self.deadline.saturating_duration_since(now).as_secs();
+        } else {
+            self.view.remaining_secs = 0;
+        }
+    }
+}
+
+/// Why a move was not made
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveError {
+    NotFound,
+    /// The session already finished or expired
+    Ended(NavigationStatus),
+    /// The title is not linked from the current article
+    NotALink,
+    /// Another move was made from the current article first
+    Conflict,
+}
+
+/// Every navigation session that is running or recently ended
+pub struct Navigations {
+    config: NavigationConfig,
+    entries: Mutex<HashMap<String, Navigation>>,
+}
+
+impl Navigations {
+    #[must_use]
+    pub fn new(config: NavigationConfig) -> Self {
+        Self {
+            config,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Start a session at `start`, whose links are `links`. Starting at Kevin
+    /// Bacon finishes the session straight away.
+    pub fn start(&self, start: String, links: Vec<String>, link_mode: LinkMode) -> NavigationView {
+        self.purge_expired();
+
+        let id = format!("{:016x}", rand::random::<u64>());
+        let now = Instant::now();
+        let start_is_target = start == KEVIN_BACON;
+        let mut navigation = Navigation {
+            view: NavigationView {
+                id: id.clone(),
+                status: NavigationStatus::Playing,
+                link_mode,
+                current: start.clone(),
+                route: vec![start],
+                hops: 0,
+                links,
+                remaining_secs: 0,
+                optimum: None,
+                extra_hops: None,
+            },
+            deadline: now + self.config.time_limit,
+            ended_at: None,
+            optimum: None,
+            search: None,
+        };
+        if start_is_target {
+            navigation.view.status = NavigationStatus::Finished;
+            navigation.view.links.clear();
+            navigation.view.optimum = Some(vec![KEVIN_BACON.to_string()]);
+            navigation.view.extra_hops = Some(0);
+            navigation.ended_at = Some(now);
+        }
+        navigation.refresh(now);
+        let view = navigation.view.clone();
+        self.lock().insert(id, navigation);
+        view
+    }
+
+    /// Current state of a session, if it exists
+    pub fn get(&self, id: &str) -> Option<NavigationView> {
+        self.purge_expired();
+        self.lock().get_mut(id).map(|navigation| {
+            navigation.refresh(Instant::now());
+            navigation.view.clone()
+        })
+    }
+
+    /// Check that `title` can be moved to from the session's current article,
+    /// returning the session with the title as the link names it
+    ///
+    /// # Errors
+    ///
+    /// Errors if the session doesn't exist or has ended, or if `title` is not
+    /// one of the current article's links.
+    pub fn check_move(&self, id: &str, title: &str) -> Result<(NavigationView, String), MoveError> {
+        let view = self.get(id).ok_or(MoveError::NotFound)?;
+        if view.status != NavigationStatus::Playing {
+            return Err(MoveError::Ended(view.status));
+        }
+
+        let title = canonical_title(title);
+        let link = view
+            .links
+            .iter()
+            .find(|link| canonical_title(link) == title)
+            .cloned()
+            .ok_or(MoveError::NotALink)?;
+        Ok((view, link))
+    }
+
+    /// Move from the article reached after `hops` hops to `title`, whose
+    /// links are `links`, finishing the session if `title` is Kevin Bacon
+    ///
+    /// # Errors
+    ///
+    /// Errors if the session doesn't exist, has ended, or has already moved
+    /// on from that article.
+    pub fn advance(
+        &self,
+        id: &str,
+        hops: usize,
+        title: String,
+        links: Vec<String>,
+    ) -> Result<NavigationView, MoveError> {
+        let now = Instant::now();
+        let mut entries = self.lock();
+        let navigation = entries.get_mut(id).ok_or(MoveError::NotFound)?;
+        navigation.refresh(now);
+        if navigation.view.status != NavigationStatus::Playing {
+            return Err(MoveError::Ended(navigation.view.status));
+        }
+        if navigation.view.hops != hops {
+            return Err(MoveError::Conflict);
+        }
+
+        let view = &mut navigation.view;
+        view.hops += 1;
+        view.current.clone_from(&title);
+        view.route.push(title);
+        if view.current == KEVIN_BACON {
+            view.status = NavigationStatus::Finished;
+            view.links.clear();
+            navigation.ended_at = Some(now);
+        } else {
+            view.links = links;
+        }
+        navigation.refresh(now);
+        Ok(navigation.view.clone())
+    }
+
+    /// Keep the search for the session's optimum, so it is stopped if the
+    /// session expires or is dropped first
+    pub fn set_search(&self, id: &str, search: JoinHandle<()>) {
+        if let Some(navigation) = self.lock().get_mut(id) {
+            navigation.search = Some(search);
+        }
+    }
+
+    /// Take the search for the session's optimum, to wait for it
+    pub fn take_search(&self, id: &str) -> Option<JoinHandle<()>> {
+        self.lock().get_mut(id)?.search.take()
+    }
+
+    /// Record the shortest path from the start of a session, shown once the
+    /// session has finished
+    pub fn set_optimum(&self, id: &str, path: Vec<String>) {
+        if let Some(navigation) = self.lock().get_mut(id) {
+            navigation.optimum = Some(path);
+            navigation.refresh(Instant::now());
+        }
+    }
+
+    fn purge_expired(&self) {
+        let now = Instant::now();
+        let retention = self.config.retention;
+        self.lock().retain(|_, navigation| {
+            navigation.refresh(now);
+            navigation
+                .ended_at
+                .is_none_or(|ended_at| now.saturating_duration_since(ended_at) < retention)
+        });
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Navigation>> {
+        // Every update leaves a session in a consistent state, so a poisoned
+        // map is still usable
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Start navigating from an article to Kevin Bacon
+#[utoipa::path(
+    post,
+    path = "/navigations",
+    request_body = NavigationRequest,
+    responses(
+        (status = 201, description = "The session was started", body = NavigationView),
+        (status = 503, description = "The start article's links could not be retrieved"),
+    ),
+)]
+pub async fn create_navigation(
+    State(state): State<AppState>,
+    Json(request): Json<NavigationRequest>,
+) -> Response {
+    let start = canonical_title(
+        &request
+            .start
+            .unwrap_or_else(|| state.random_starting_page().to_string()),
+    );
+    let crawler = state.crawler.clone().with_link_mode(request.links);
+    let links = match crawler.linked_titles(&start).await {
+        Ok(links) => links,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, e.to_string()).into_response(),
+    };
+
+    let view = state.navigations.start(start.clone(), links, request.links);
+    if view.status == NavigationStatus::Playing {
+        // Search while the player navigates, in the pool like any other
+        // search, so the optimum is known by the end. Stopping it early gives
+        // its place back only once a crawl that can't be stopped has ended.
+        let (search_state, id) = (state.clone(), view.id.clone());
+        let search = tokio::spawn(async move {
+            let cache_state = search_state.clone();
+            let search = async move {
+                response::cached_search(&cache_state, &start, request.links, |_| {}).await
+            };
+            let (search, ..) = search_state.jobs.run(&search_state.crawler, search).await;
+            if let (SearchStatus::Found, Some(path)) = (search.status, search.path) {
+                search_state.navigations.set_optimum(&id, path);
+            }
+        });
+        state.navigations.set_search(&view.id, search);
+    }
+    (StatusCode::CREATED, Json(view)).into_response()
+}
+
+/// Resume a navigation session
+#[utoipa::path(
+    get,
+    path = "/navigations/{id}",
+    params(("id" = String, Path, description = "Id returned when the session was started")),
+    responses(
+        (status = 200, description = "The session's current state", body = NavigationView),
+        (status = 404, description = "No such session, or it ended a while ago"),
+    ),
+)]
+pub async fn get_navigation(State(state): State<AppState>, Path(id): Path<String>) -> Response {
+    match state.navigations.get(&id) {
+        Some(view) => Json(view).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Follow one of the current article's links
+#[utoipa::path(
+    post,
+    path = "/navigations/{id}/moves",
+    params(("id" = String, Path, description = "Id returned when the session was started")),
+    request_body = NavigationMove,
+    responses(
+        (status = 200, description = "The session after the move", body = NavigationView),
+        (status = 404, description = "No such session, or it ended a while ago"),
+        (status = 409, description = "The session already finished, or another move was made first"),
+        (status = 410, description = "Time ran out"),
+        (status = 422, description = "The title is not linked from the current article"),
+        (status = 503, description = "The next article's links could not be retrieved"),
+    ),
+)]
+pub async fn create_move(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(request): Json<NavigationMove>,
+) -> Response {
+    let navigations = &state.navigations;
+    let (view, title) = match navigations.check_move(&id, &request.title) {
+        Ok(checked) => checked,
+        Err(e) => return move_error(&e),
+    };
+
+    let links = if title == KEVIN_BACON {
+        Vec::new()
+    } else {
+        let crawler = state.crawler.clone().with_link_mode(view.link_mode);
+        match crawler.linked_titles(&title).await {
+            Ok(links) => links,
+            Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, e.to_string()).into_response(),
+        }
+    };
+
+    let view = match navigations.advance(&id, view.hops, title, links) {
+        Ok(view) => view,
+        Err(e) => return move_error(&e),
+    };
+    if view.status == NavigationStatus::Finished {
+        // The search records the optimum itself, even if this request is
+        // dropped while waiting for it
+        if let Some(search) = navigations.take_search(&id) {
+            let _ = search.await;
+        }
+        return Json(navigations.get(&id).unwrap_or(view)).into_response();
+    }
+    Json(view).into_response()
+}
+
+fn move_error(error: &MoveError) -> Response {
+    match error {
+        MoveError::NotFound => StatusCode::NOT_FOUND.into_response(),
+        MoveError::Ended(NavigationStatus::Expired) => {
+            (StatusCode::GONE, "Time ran out").into_response()
+        }
+        MoveError::Ended(_) => {
+            (StatusCode::CONFLICT, "Kevin Bacon was already reached").into_response()
+        }
+        MoveError::Conflict => {
+            (StatusCode::CONFLICT, "Another move was made first").into_response()
+        }
+        MoveError::NotALink => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Not linked from the current article",
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,75 @@
+//! `OpenAPI` document of the HTTP routes, generated from the handlers'
+//! annotations at compile time. The `/ws` game protocol is described in
+//! `ws.rs` instead, as `OpenAPI` can't express it.
+
+use crate::{
+    daily, guesses, hints, jobs, leaderboard, navigate, random, response, sse, CreatedSearch,
+    Difficulty, GuessRequest, GuessResult, Hint, HintRequest, HintResponse, JobStatus, JobView,
+    LeaderboardEntry, LeaderboardPage, LeaderboardResult, LeaderboardSubmission, NavigationMove,
+    NavigationRequest, NavigationStatus, NavigationView, Outcome, PlayerStats, Puzzle, PuzzleView,
+    RandomArticle, Score, SearchError, SearchProgress, SearchRequest, SearchResponse, SearchStatus,
+};
+use axum::response::Json;
+use utoipa::OpenApi;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Where's Kevin?",
+        description = "Shortest link paths to Kevin Bacon's Wikipedia article"
+    ),
+    paths(
+        crate::get_path,
+        sse::get_path_events,
+        random::get_random,
+        daily::get_daily,
+        guesses::create_guess,
+        guesses::get_player_stats,
+        hints::create_hint,
+        leaderboard::submit_daily,
+        leaderboard::get_daily_leaderboard,
+        leaderboard::get_leaderboard,
+        navigate::create_navigation,
+        navigate::get_navigation,
+        navigate::create_move,
+        response::get_schema,
+        jobs::create_search,
+        jobs::get_search,
+        jobs::delete_search,
+    ),
+    components(schemas(
+        SearchResponse,
+        SearchStatus,
+        SearchError,
+        SearchProgress,
+        SearchRequest,
+        CreatedSearch,
+        JobView,
+        JobStatus,
+        RandomArticle,
+        Difficulty,
+        Puzzle,
+        PuzzleView,
+        GuessRequest,
+        GuessResult,
+        Score,
+        Outcome,
+        PlayerStats,
+        HintRequest,
+        HintResponse,
+        Hint,
+        LeaderboardSubmission,
+        LeaderboardResult,
+        LeaderboardPage,
+        LeaderboardEntry,
+        NavigationRequest,
+        NavigationMove,
+        NavigationView,
+        NavigationStatus,
+    ))
+)]
+pub struct ApiDoc;
+
+pub async fn get_openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
@@ -0,0 +1,217 @@
+//! Random starting articles of a requested difficulty. Candidates are
+//! searched before one is returned, so its answer is already cached when the
+//! game asks for it.
+
+use crate::{response, AppState, RandomArticle, SearchStatus};
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use crawler_common::{random_params, LinkFilter, LinkMode, API_URL};
+use dotenvy::dotenv;
+use puzzle::Difficulty;
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+use std::{env, time::Duration};
+use tokio::task::JoinSet;
+use utoipa::IntoParams;
+use wiki_response::WikiResponse;
+
+/// Candidates searched at once while looking for a random article
+const BATCH_SIZE: usize = 4;
+/// Batches tried before giving up
+const MAX_BATCHES: usize = 3;
+
+/// Limits of `/random`
+#[derive(Debug, Clone, Copy)]
+pub struct RandomConfig {
+    /// How long to look for an article before giving up, so one request
+    /// can't keep places in the search pool for long
+    pub time_limit: Duration,
+}
+
+impl Default for RandomConfig {
+    fn default() -> Self {
+        Self {
+            time_limit: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Somewhere random article titles can be drawn from
+#[async_trait]
+pub trait ArticleSampler: Send + Sync {
+    /// Draw up to `count` random titles
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no titles can be drawn.
+    async fn sample(&self, count: usize) -> anyhow::Result<Vec<String>>;
+}
+
+/// Samples Wikipedia's main namespace with `list=random`
+pub struct WikipediaSampler {
+    client: reqwest::Client,
+}
+
+impl WikipediaSampler {
+    /// # Errors
+    ///
+    /// `new` errors if:
+    ///   - The environment variable `CONTACT` cannot be found (used to create
+    ///     user agent for http requests)
+    ///   - There is an error encountered while creating the http client
+    pub fn new() -> anyhow::Result<Self> {
+        dotenv().ok();
+        let contact = env::var("CONTACT")?;
+        let user_agent = format!("MyWikiCrawler ({contact})");
+
+        let client = reqwest::Client::builder()
+            .user_agent(user_agent)
+            .timeout(Duration::from_secs(5))
+            .build()
+            .context("Error creating http client")?;
+
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl ArticleSampler for WikipediaSampler {
+    async fn sample(&self, count: usize) -> anyhow::Result<Vec<String>> {
+        let resp = self
+            .client
+            .get(API_URL)
+            .query(&random_params(count, &LinkFilter::default()))
+            .send()
+            .await
+            .map_err(|e| anyhow!("HTTP request failed: {e}"))?;
+
+        if !resp.status().is_success() {
+            return Err(anyhow!("HTTP error {} for random articles", resp.status()));
+        }
+
+        let data: WikiResponse = resp
+            .json()
+            .await
+            .map_err(|e| anyhow!("Failed to decode JSON for random articles: {e}"))?;
+
+        Ok(data
+            .query
+            .random
+            .into_iter()
+            .map(|link| link.title)
+            .collect())
+    }
+}
+
+/// Samples a fixed list of titles, such as the articles of an offline graph
+pub struct FixedSampler {
+    titles: Vec<String>,
+}
+
+impl FixedSampler {
+    #[must_use]
+    pub fn new(titles: Vec<String>) -> Self {
+        Self { titles }
+    }
+}
+
+#[async_trait]
+impl ArticleSampler for FixedSampler {
+    async fn sample(&self, count: usize) -> anyhow::Result<Vec<String>> {
+        if self.titles.is_empty() {
+            return Err(anyhow!("No titles to sample from"));
+        }
+
+        Ok(self
+            .titles
+            .choose_multiple(&mut rand::thread_rng(), count)
+            .cloned()
+            .collect())
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RandomParams {
+    /// Any reachable article when absent
+    difficulty: Option<Difficulty>,
+    /// Which of each article's links to follow
+    #[serde(default)]
+    links: LinkMode,
+}
+
+/// Pick a random article of the requested difficulty
+#[utoipa::path(
+    get,
+    path = "/random",
+    params(RandomParams),
+    responses(
+        (status = 200, description = "An article whose answer is cached", body = RandomArticle),
+        (status = 503, description = "No article of the difficulty was found in time"),
+    ),
+)]
+pub async fn get_random(
+    State(state): State<AppState>,
+    Query(params): Query<RandomParams>,
+) -> Response {
+    let time_limit = state.random.time_limit;
+    match tokio::time::timeout(time_limit, find_article(&state, &params)).await {
+        Ok(Ok(article)) => Json(article).into_response(),
+        Ok(Err(e)) => (StatusCode::SERVICE_UNAVAILABLE, e.to_string()).into_response(),
+        Err(_) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "No article of the requested difficulty was found in time",
+        )
+            .into_response(),
+    }
+}
+
+/// Search batches of candidates until one has the requested difficulty.
+/// Searches still running when it returns or is dropped are aborted.
+async fn find_article(state: &AppState, params: &RandomParams) -> anyhow::Result<RandomArticle> {
+    for _ in 0..MAX_BATCHES {
+        let candidates = state.sampler.sample(BATCH_SIZE).await?;
+
+        let mut searches = JoinSet::new();
+        for candidate in candidates {
+            let (state, links) = (state.clone(), params.links);
+            searches.spawn(async move {
+                // Candidates are crawled like any other search, so they share
+                // the pool's limit
+                let search_state = state.clone();
+                let search_candidate = candidate.clone();
+                let search = async move {
+                    response::cached_search(&search_state, &search_candidate, links, |_| {}).await
+                };
+                let (response, ..) = state.jobs.run(&state.crawler, search).await;
+                (candidate, response)
+            });
+        }
+
+        while let Some(joined) = searches.join_next().await {
+            // A panicked search is just a candidate fewer
+            let Ok((title, response)) = joined else {
+                continue;
+            };
+            let matches = params.difficulty.is_none_or(|difficulty| {
+                response
+                    .hops
+                    .is_some_and(|hops| difficulty.hops().contains(&hops))
+            });
+            if response.status == SearchStatus::Found && matches {
+                // The rest are aborted as `searches` is dropped
+                return Ok(RandomArticle {
+                    title,
+                    difficulty: params.difficulty,
+                });
+            }
+        }
+    }
+
+    Err(anyhow!("No article of the requested difficulty was found"))
+}
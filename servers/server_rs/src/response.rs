@@ -0,0 +1,148 @@
+//! The body describing a finished search, shared by `GET /:starting_page`
+//! and the `result` event of the SSE route. Its JSON Schema is served at
+//! `/schemas/search-response.json`.
+
+use crate::{
+    cache::{CacheKey, CacheStatus},
+    AppState, Crawler, SearchError, SearchResponse, SearchStatus,
+};
+use axum::response::Json;
+use crawler_common::{canonical_title, CrawlEvent, LinkMode};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use utoipa::{PartialSchema, ToSchema};
+
+/// JSON Schema of `SearchResponse`, built from its `OpenAPI` schema with the
+/// schemas it refers to under `$defs`
+fn json_schema() -> Value {
+    let mut components = Vec::new();
+    <SearchResponse as ToSchema>::schemas(&mut components);
+    let defs: Map<String, Value> = components
+        .into_iter()
+        .map(|(name, schema)| (name, to_json_schema(&schema)))
+        .collect();
+
+    let mut schema = to_json_schema(&<SearchResponse as PartialSchema>::schema());
+    schema["$schema"] = "https://json-schema.org/draft/2020-12/schema".into();
+    schema["title"] = SearchResponse::name().into();
+    schema["$defs"] = defs.into();
+    schema
+}
+
+/// An `OpenAPI` schema as JSON, pointing references to its components at
+/// `$defs` instead
+fn to_json_schema(schema: &impl Serialize) -> Value {
+    fn point_refs_at_defs(value: &mut Value) {
+        match value {
+            Value::Object(object) => {
+                for (key, value) in object.iter_mut() {
+                    match value {
+                        Value::String(reference) if key == "$ref" => {
+                            *reference = reference.replace("#/components/schemas/", "#/$defs/");
+                        }
+                        value => point_refs_at_defs(value),
+                    }
+                }
+            }
+            Value::Array(values) => values.iter_mut().for_each(point_refs_at_defs),
+            _ => {}
+        }
+    }
+
+    let mut value = serde_json::to_value(schema).expect("schemas should serialize");
+    point_refs_at_defs(&mut value);
+    value
+}
+
+/// Search from `starting_page`, passing every crawl event to `on_event`
+pub async fn search(
+    crawler: Crawler,
+    starting_page: &str,
+    link_mode: LinkMode,
+    on_event: impl Fn(CrawlEvent) + Send + Sync + 'static,
+) -> SearchResponse {
+    let canonical_start = canonical_title(starting_page);
+    let backend = crawler.backend();
+    let order = crawler.search_order();
+    let filter = crawler.filter().clone();
+    let target = crawler.target().to_string();
+
+    let pages_fetched = Arc::new(AtomicUsize::new(0));
+    let counter = pages_fetched.clone();
+    let crawler = crawler
+        .with_link_mode(link_mode)
+        .with_event_handler(move |event| {
+            if matches!(event, CrawlEvent::PageFetched { .. }) {
+                counter.fetch_add(1, Ordering::Relaxed);
+            }
+            on_event(event);
+        });
+
+    let started = Instant::now();
+    let result = crawler.crawl(&canonical_start).await;
+    let elapsed_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+    let (status, path, error) = match result {
+        Ok(result) => (SearchStatus::Found, Some(result.path), None),
+        Err(e) => (
+            SearchStatus::Failed,
+            None,
+            Some(SearchError {
+                message: e.to_string(),
+            }),
+        ),
+    };
+
+    SearchResponse {
+        status,
+        starting_page: starting_page.to_string(),
+        canonical_start,
+        target,
+        hops: path.as_ref().map(|path| path.len() - 1),
+        path,
+        shortest: order.finds_shortest(),
+        pages_fetched: pages_fetched.load(Ordering::Relaxed),
+        elapsed_ms,
+        crawler: backend,
+        order,
+        link_mode,
+        filter,
+        error,
+    }
+}
+
+/// Answer from the cache when possible, otherwise search and cache the
+/// result. Also returns how long the answer stays fresh.
+pub async fn cached_search(
+    state: &AppState,
+    starting_page: &str,
+    link_mode: LinkMode,
+    on_event: impl Fn(CrawlEvent) + Send + Sync + 'static,
+) -> (SearchResponse, CacheStatus, Duration) {
+    let key = CacheKey::new(starting_page, link_mode, &state.crawler);
+    if let Some((mut response, remaining)) = state.cache.get(&key) {
+        response.starting_page = starting_page.to_string();
+        return (response, CacheStatus::Hit, remaining);
+    }
+
+    let response = search(state.crawler.clone(), starting_page, link_mode, on_event).await;
+    state.cache.insert(key, &response);
+    (response, CacheStatus::Miss, state.cache.ttl())
+}
+
+/// JSON Schema of the search response body
+#[utoipa::path(
+    get,
+    path = "/schemas/search-response.json",
+    responses((status = 200, description = "JSON Schema document", body = Object)),
+)]
+pub async fn get_schema() -> Json<Value> {
+    Json(json_schema())
+}
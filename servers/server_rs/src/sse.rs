@@ -0,0 +1,65 @@
+use crate::{response, AbortOnDrop, AppState, SearchParams, SearchProgress, SearchResponse};
+use axum::extract::{Path, Query, State};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use crawler_common::CrawlEvent;
+use tokio::sync::mpsc;
+use tokio_stream::Stream;
+
+enum SearchUpdate {
+    Crawl(CrawlEvent),
+    Finished(SearchResponse),
+}
+
+/// Stream a search as Server-Sent Events: a `progress` event whenever a page
+/// is fetched or a depth completes, then a single `result` event holding the
+/// same body as `get_path`. The search is aborted if the client disconnects
+/// first.
+#[utoipa::path(
+    get,
+    path = "/search/{starting_page}/events",
+    params(("starting_page" = String, Path, description = "Article to start from"), SearchParams),
+    responses((
+        status = 200,
+        description = "`progress` events holding a `SearchProgress`, then one `result` event holding a `SearchResponse`",
+        content_type = "text/event-stream",
+    )),
+)]
+pub async fn get_path_events(
+    State(state): State<AppState>,
+    Path(starting_page): Path<String>,
+    Query(params): Query<SearchParams>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+
+    let search = tokio::spawn(async move {
+        let event_tx = tx.clone();
+        let (response, ..) =
+            response::cached_search(&state, &starting_page, params.links, move |event| {
+                let _ = event_tx.send(SearchUpdate::Crawl(event));
+            })
+            .await;
+        let _ = tx.send(SearchUpdate::Finished(response));
+    });
+
+    let abort = AbortOnDrop(search.abort_handle());
+    let stream = async_stream::stream! {
+        let _abort = abort;
+        let mut progress = SearchProgress::default();
+
+        while let Some(update) = rx.recv().await {
+            match update {
+                SearchUpdate::Crawl(event) => {
+                    if progress.apply(&event) {
+                        yield Event::default().event("progress").json_data(&progress);
+                    }
+                }
+                SearchUpdate::Finished(response) => {
+                    yield Event::default().event("result").json_data(&response);
+                    break;
+                }
+            }
+        }
+    };
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
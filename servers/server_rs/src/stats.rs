@@ -0,0 +1,147 @@
+//! Anonymous per-player statistics. Every scored guess is recorded under the
+//! player's id, and statistics are computed from a player's guesses when
+//! asked for.
+
+use crate::GuessRecord;
+use anyhow::Context;
+use rusqlite::{params, Connection};
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+/// Where players' guesses are kept
+pub trait StatsStore: Send + Sync {
+    /// Record a guess as the player's latest
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the guess could not be stored.
+    fn record(&self, player: &str, guess: &GuessRecord) -> anyhow::Result<()>;
+
+    /// Every guess of the player, oldest first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the guesses could not be read.
+    fn guesses(&self, player: &str) -> anyhow::Result<Vec<GuessRecord>>;
+}
+
+/// Guesses held in memory and lost on restart
+#[derive(Default)]
+pub struct InMemoryStats {
+    guesses: Mutex<HashMap<String, Vec<GuessRecord>>>,
+}
+
+impl InMemoryStats {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Vec<GuessRecord>>> {
+        // Guesses are only ever appended, so a poisoned map is still usable
+        self.guesses.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl StatsStore for InMemoryStats {
+    fn record(&self, player: &str, guess: &GuessRecord) -> anyhow::Result<()> {
+        self.lock()
+            .entry(player.to_string())
+            .or_default()
+            .push(guess.clone());
+        Ok(())
+    }
+
+    fn guesses(&self, player: &str) -> anyhow::Result<Vec<GuessRecord>> {
+        Ok(self.lock().get(player).cloned().unwrap_or_default())
+    }
+}
+
+/// Guesses kept in an embedded `SQLite` database
+pub struct SqliteStats {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStats {
+    /// Open the database at `path`, creating it if needed
+    ///
+    /// # Errors
+    ///
+    /// Errors if the database can't be opened or its table created.
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let connection = Connection::open(path)
+            .with_context(|| format!("Failed to open stats database {}", path.display()))?;
+        Self::new(connection)
+    }
+
+    /// A database that lives only as long as this store
+    ///
+    /// # Errors
+    ///
+    /// Errors if the database can't be created.
+    pub fn open_in_memory() -> anyhow::Result<Self> {
+        Self::new(Connection::open_in_memory()?)
+    }
+
+    fn new(connection: Connection) -> anyhow::Result<Self> {
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS guesses (
+                id INTEGER PRIMARY KEY,
+                player TEXT NOT NULL,
+                start TEXT NOT NULL,
+                guess INTEGER NOT NULL,
+                hops INTEGER NOT NULL,
+                hints INTEGER NOT NULL DEFAULT 0
+            );
+            CREATE INDEX IF NOT EXISTS guesses_by_player ON guesses (player, id);",
+        )?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Connection> {
+        // Every statement is atomic, so a poisoned connection is still usable
+        self.connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl StatsStore for SqliteStats {
+    fn record(&self, player: &str, guess: &GuessRecord) -> anyhow::Result<()> {
+        self.lock().execute(
+            "INSERT INTO guesses (player, start, guess, hops, hints) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![player, guess.start, guess.guess, guess.hops, guess.hints],
+        )?;
+        Ok(())
+    }
+
+    fn guesses(&self, player: &str) -> anyhow::Result<Vec<GuessRecord>> {
+        let connection = self.lock();
+        let mut statement = connection.prepare(
+            "SELECT start, guess, hops, hints FROM guesses WHERE player = ?1 ORDER BY id",
+        )?;
+        let guesses = statement
+            .query_map([player], |row| {
+                Ok(GuessRecord {
+                    start: row.get(0)?,
+                    guess: row.get(1)?,
+                    hops: row.get(2)?,
+                    hints: row.get(3)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(guesses)
+    }
+}
+
+/// Record a guess, logging rather than failing when it can't be stored
+pub(crate) fn record_guess(store: &dyn StatsStore, player: &str, record: &GuessRecord) {
+    if let Err(e) = store.record(player, record) {
+        eprintln!("Could not record guess of {player}: {e}");
+    }
+}
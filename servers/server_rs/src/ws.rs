@@ -0,0 +1,214 @@
+//! Game sessions over a WebSocket.
+//!
+//! A session plays one round: on connect the server sends `start` and begins
+//! searching immediately, in the search pool and through the result cache,
+//! streaming `progress` while the player decides. The
+//! player sends a single `guess`, and once both the guess and the search are
+//! in, the server sends `result` and closes the socket. Every message is a
+//! JSON object tagged by `type`. With a `player` id, hints the player used on
+//! the start lower the score, and the scored guess is recorded in their
+//! statistics.
+
+use crate::{
+    response, score, stats::record_guess, valid_player_id, AbortOnDrop, AppState, GuessRecord,
+    SearchProgress,
+};
+use axum::{
+    extract::{
+        ws::{Message, WebSocket},
+        Query, State, WebSocketUpgrade,
+    },
+    response::Response,
+};
+use crawler_common::{canonical_title, CrawlEvent, LinkMode};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+/// Version of the message protocol spoken on `/ws`
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Messages sent by the server
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// First message of a session, naming the article to start from in its
+    /// canonical form
+    Start {
+        version: u32,
+        start: String,
+        target: String,
+    },
+    /// The search advanced
+    Progress(SearchProgress),
+    /// The search finished and the guess was scored. `path` and `hops` are
+    /// absent when the target could not be reached.
+    Result {
+        path: Option<Vec<String>>,
+        hops: Option<usize>,
+        guess: usize,
+        score: u32,
+    },
+    /// A message could not be handled
+    Error { message: String },
+}
+
+/// Messages sent by the client
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// The player's guess of the minimum number of link hops
+    Guess { hops: usize },
+}
+
+#[derive(Deserialize)]
+pub struct GameParams {
+    /// Protocol version the client speaks; the current one when absent
+    version: Option<u32>,
+    /// Article to start from instead of a random starting page
+    start: Option<String>,
+    #[serde(default)]
+    links: LinkMode,
+    /// Anonymous id to record the scored guess under, if any
+    player: Option<String>,
+}
+
+enum SessionUpdate {
+    Crawl(CrawlEvent),
+    Finished(Option<Vec<String>>),
+}
+
+pub async fn game_session(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    Query(params): Query<GameParams>,
+) -> Response {
+    ws.on_upgrade(move |socket| play(socket, state, params))
+}
+
+async fn play(mut socket: WebSocket, state: AppState, params: GameParams) {
+    if let Some(message) = invalid_params(&params) {
+        send(&mut socket, &ServerMessage::Error { message }).await;
+        return;
+    }
+
+    let start = canonical_title(
+        params
+            .start
+            .as_deref()
+            .unwrap_or_else(|| state.random_starting_page()),
+    );
+
+    let start_message = ServerMessage::Start {
+        version: PROTOCOL_VERSION,
+        start: start.clone(),
+        target: state.crawler.target().to_string(),
+    };
+    if !send(&mut socket, &start_message).await {
+        return;
+    }
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let (store, hints) = (state.stats.clone(), state.hints.clone());
+    let (search_start, links) = (start.clone(), params.links);
+    let search = tokio::spawn(async move {
+        let event_tx = tx.clone();
+        let search_state = state.clone();
+        let search = async move {
+            response::cached_search(&search_state, &search_start, links, move |event| {
+                let _ = event_tx.send(SessionUpdate::Crawl(event));
+            })
+            .await
+        };
+        let (response, ..) = state.jobs.run(&state.crawler, search).await;
+        let _ = tx.send(SessionUpdate::Finished(response.path));
+    });
+    // Stop searching whenever the session ends, such as on disconnect
+    let _abort = AbortOnDrop(search.abort_handle());
+
+    let mut progress = SearchProgress::default();
+    let mut guess = None;
+    let mut path = None;
+
+    loop {
+        tokio::select! {
+            message = socket.recv() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_)) | Err(_)) | None => return,
+                    Some(Ok(_)) => continue,
+                };
+
+                let reply = match serde_json::from_str(&text) {
+                    Ok(ClientMessage::Guess { .. }) if guess.is_some() => Some(ServerMessage::Error {
+                        message: "A guess was already submitted".to_string(),
+                    }),
+                    Ok(ClientMessage::Guess { hops }) => {
+                        guess = Some(hops);
+                        None
+                    }
+                    Err(e) => Some(ServerMessage::Error {
+                        message: format!("Invalid message: {e}"),
+                    }),
+                };
+                if let Some(reply) = reply {
+                    if !send(&mut socket, &reply).await {
+                        return;
+                    }
+                }
+            }
+            Some(update) = rx.recv() => match update {
+                SessionUpdate::Crawl(event) => {
+                    if progress.apply(&event)
+                        && !send(&mut socket, &ServerMessage::Progress(progress.clone())).await
+                    {
+                        return;
+                    }
+                }
+                SessionUpdate::Finished(found) => path = Some(found),
+            },
+        }
+
+        if let (Some(guess), Some(path)) = (guess, &path) {
+            let hops = path.as_ref().map(|path| path.len() - 1);
+            let mut used_hints = 0;
+            if let (Some(player), Some(hops)) = (&params.player, hops) {
+                used_hints = hints.take(player, &start);
+                let record = GuessRecord {
+                    start: start.clone(),
+                    guess,
+                    hops,
+                    hints: used_hints,
+                };
+                record_guess(store.as_ref(), player, &record);
+            }
+            let result = ServerMessage::Result {
+                path: path.clone(),
+                hops,
+                guess,
+                score: hops.map_or(0, |hops| score(guess, hops).with_hints(used_hints).points),
+            };
+            send(&mut socket, &result).await;
+            let _ = socket.close().await;
+            return;
+        }
+    }
+}
+
+/// Why a session can't be played with `params`, if it can't
+fn invalid_params(params: &GameParams) -> Option<String> {
+    if let Some(version) = params.version.filter(|&v| v != PROTOCOL_VERSION) {
+        return Some(format!(
+            "Unsupported protocol version {version}; server speaks {PROTOCOL_VERSION}"
+        ));
+    }
+    if let Some(player) = params.player.as_deref().filter(|&id| !valid_player_id(id)) {
+        return Some(format!("Invalid player id '{player}'"));
+    }
+    None
+}
+
+/// Send a message, returning whether the socket is still open
+async fn send(socket: &mut WebSocket, message: &ServerMessage) -> bool {
+    let text = serde_json::to_string(message).expect("server messages should serialize");
+    socket.send(Message::Text(text)).await.is_ok()
+}
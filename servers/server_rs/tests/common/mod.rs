@@ -0,0 +1,75 @@
+use async_trait::async_trait;
+use crawler_common::{InMemoryLinks, LinkSource};
+use server_rs::Jobs;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Links where looking up "Slow" waits until `release` is notified. `started`
+/// is notified when that lookup begins, and every title looked up is recorded
+/// in `looked_up`.
+pub struct StalledLinks {
+    links: InMemoryLinks,
+    pub started: Arc<Notify>,
+    pub release: Arc<Notify>,
+    pub looked_up: Arc<Mutex<Vec<String>>>,
+}
+
+impl StalledLinks {
+    pub fn new(links: InMemoryLinks) -> Self {
+        Self {
+            links,
+            started: Arc::new(Notify::new()),
+            release: Arc::new(Notify::new()),
+            looked_up: Arc::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl LinkSource for StalledLinks {
+    async fn linked_titles(&self, title: &str) -> anyhow::Result<Vec<String>> {
+        self.looked_up.lock().unwrap().push(title.to_string());
+        if title == "Slow" {
+            self.started.notify_one();
+            self.release.notified().await;
+        }
+        self.links.linked_titles(title).await
+    }
+}
+
+/// Let "Slow" finish once the search has had time to be aborted, such as when
+/// its client disconnects, then check that it never went on to look up "After"
+#[allow(dead_code)] // Not every test stops a search
+pub async fn assert_stopped_after_slow(release: &Notify, looked_up: &Mutex<Vec<String>>) {
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    release.notify_one();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    assert!(
+        !looked_up
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|title| title == "After"),
+        "search should have been aborted"
+    );
+}
+
+/// Check that the pool stays full while a crawl stalled on "Slow" that can't
+/// be stopped is still running, and that its place is given back once
+/// `release` lets it end
+#[allow(dead_code)] // Not every test runs a crawl that can't be stopped
+pub async fn assert_place_kept_until_released(jobs: Arc<Jobs>, release: &Notify) {
+    let waiting = tokio::spawn(async move { drop(jobs.permit().await) });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let full = !waiting.is_finished();
+    // Released either way, or the stalled thread would keep the test running
+    release.notify_one();
+    assert!(full, "the pool should still be full");
+
+    tokio::time::timeout(Duration::from_secs(5), waiting)
+        .await
+        .expect("the place should be given back once the crawl ends")
+        .unwrap();
+}
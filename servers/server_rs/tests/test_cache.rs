@@ -0,0 +1,188 @@
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, Request, StatusCode},
+    Router,
+};
+use crawler_common::{InMemoryLinks, LinkSource};
+use http_body_util::BodyExt;
+use server_rs::{
+    router, AppState, Backend, CacheConfig, Crawler, SearchResponse, SearchStatus, X_CACHE,
+};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use titles::KEVIN_BACON;
+use tower::ServiceExt;
+
+/// Links that count how often they are looked up
+struct CountingLinks {
+    links: InMemoryLinks,
+    lookups: AtomicUsize,
+}
+
+#[async_trait]
+impl LinkSource for CountingLinks {
+    async fn linked_titles(&self, title: &str) -> anyhow::Result<Vec<String>> {
+        self.lookups.fetch_add(1, Ordering::Relaxed);
+        self.links.linked_titles(title).await
+    }
+}
+
+fn links() -> Arc<CountingLinks> {
+    Arc::new(CountingLinks {
+        links: InMemoryLinks::from_edges([
+            ("Start", vec!["Middle"]),
+            ("Middle", vec![KEVIN_BACON]),
+            ("Other", vec![KEVIN_BACON]),
+        ]),
+        lookups: AtomicUsize::new(0),
+    })
+}
+
+fn app(links: &Arc<CountingLinks>, config: CacheConfig) -> Router {
+    let crawler = Crawler::from_link_source(Backend::Async, 2, links.clone());
+    router(AppState::new(crawler).with_cache_config(config))
+}
+
+async fn get(app: &Router, uri: &str) -> (HeaderMap, SearchResponse) {
+    let request = Request::get(uri).body(Body::empty()).unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let headers = response.headers().clone();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (headers, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn repeated_search_hits_cache() {
+    let links = links();
+    let app = app(&links, CacheConfig::default());
+
+    let (headers, first) = get(&app, "/Start").await;
+    assert_eq!(headers[X_CACHE], "MISS");
+    assert_eq!(headers[header::CACHE_CONTROL], "public, max-age=3600");
+    let lookups = links.lookups.load(Ordering::Relaxed);
+
+    let (headers, second) = get(&app, "/start").await;
+    assert_eq!(headers[X_CACHE], "HIT");
+    assert!(headers[header::CACHE_CONTROL]
+        .to_str()
+        .unwrap()
+        .starts_with("public, max-age="));
+    assert_eq!(second.starting_page, "start");
+    assert_eq!(second.path, first.path);
+    assert_eq!(links.lookups.load(Ordering::Relaxed), lookups);
+}
+
+#[tokio::test]
+async fn options_are_cached_separately() {
+    let links = links();
+    let app = app(&links, CacheConfig::default());
+
+    get(&app, "/Start").await;
+    let (headers, response) = get(&app, "/Start?links=body").await;
+
+    assert_eq!(headers[X_CACHE], "MISS");
+    assert_eq!(response.link_mode, crawler_common::LinkMode::Body);
+}
+
+#[tokio::test]
+async fn searches_the_crawlers_target() {
+    let crawler = Crawler::from_link_source(Backend::Async, 2, links()).with_target("Middle");
+    let app = router(AppState::new(crawler));
+
+    let (_, response) = get(&app, "/Start").await;
+    assert_eq!(response.target, "Middle");
+    assert_eq!(
+        response.path,
+        Some(vec!["Start".to_string(), "Middle".to_string()])
+    );
+}
+
+#[tokio::test]
+async fn failures_are_not_cached() {
+    let links = links();
+    let app = app(&links, CacheConfig::default());
+
+    let (headers, response) = get(&app, "/Nowhere").await;
+    assert_eq!(response.status, SearchStatus::Failed);
+    assert_eq!(headers[header::CACHE_CONTROL], "no-store");
+
+    let (headers, _) = get(&app, "/Nowhere").await;
+    assert_eq!(headers[X_CACHE], "MISS");
+}
+
+#[tokio::test]
+async fn expired_results_are_searched_again() {
+    let links = links();
+    let app = app(
+        &links,
+        CacheConfig {
+            ttl: Duration::from_millis(50),
+            ..CacheConfig::default()
+        },
+    );
+
+    get(&app, "/Start").await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    assert_eq!(get(&app, "/Start").await.0[X_CACHE], "MISS");
+}
+
+#[tokio::test]
+async fn oldest_result_is_evicted() {
+    let links = links();
+    let app = app(
+        &links,
+        CacheConfig {
+            capacity: 1,
+            ..CacheConfig::default()
+        },
+    );
+
+    get(&app, "/Start").await;
+    get(&app, "/Other").await;
+
+    assert_eq!(get(&app, "/Other").await.0[X_CACHE], "HIT");
+    assert_eq!(get(&app, "/Start").await.0[X_CACHE], "MISS");
+}
+
+#[tokio::test]
+async fn cache_persists_across_restarts() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cache.json");
+    let config = CacheConfig {
+        path: Some(path.clone()),
+        save_delay: Duration::from_millis(50),
+        ..CacheConfig::default()
+    };
+
+    let first = app(&links(), config.clone());
+    get(&first, "/Start").await;
+    get(&first, "/Other").await;
+    // Wait for a save holding both results
+    for _ in 0..100 {
+        let saved: Vec<serde_json::Value> = std::fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        if saved.len() == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    let links = links();
+    let second = app(&links, config);
+    for start in ["/Start", "/Other"] {
+        let (headers, response) = get(&second, start).await;
+        assert_eq!(headers[X_CACHE], "HIT", "{start}");
+        assert!(response.hops.is_some());
+    }
+    assert_eq!(links.lookups.load(Ordering::Relaxed), 0);
+}
@@ -0,0 +1,106 @@
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    Router,
+};
+use crawler_common::InMemoryLinks;
+use http_body_util::BodyExt;
+use server_rs::{
+    router, AppState, Backend, Crawler, DailyPuzzles, Leaderboard, LeaderboardSubmission,
+    PuzzleView,
+};
+use std::sync::Arc;
+use time::{Duration, OffsetDateTime};
+use titles::KEVIN_BACON;
+use tower::ServiceExt;
+
+fn graph() -> InMemoryLinks {
+    InMemoryLinks::from_edges([
+        ("Start", vec!["Middle"]),
+        ("Middle", vec![KEVIN_BACON]),
+        ("Other", vec![KEVIN_BACON]),
+    ])
+}
+
+fn app(daily: Option<DailyPuzzles>) -> Router {
+    let crawler = Crawler::from_link_source(Backend::Async, 2, Arc::new(graph()));
+    let mut state = AppState::new(crawler);
+    if let Some(daily) = daily {
+        state = state
+            .with_daily_puzzles(daily)
+            .with_leaderboard(Leaderboard::open_in_memory().unwrap());
+    }
+    router(state)
+}
+
+async fn get(app: &Router, uri: &str) -> (StatusCode, Vec<u8>) {
+    let request = Request::get(uri).body(Body::empty()).unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, bytes.to_vec())
+}
+
+async fn view(app: &Router, uri: &str) -> PuzzleView {
+    let (status, body) = get(app, uri).await;
+    assert_eq!(status, StatusCode::OK);
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn hides_answer_until_the_day_is_over() {
+    let app = app(Some(DailyPuzzles::new(graph(), 3).unwrap()));
+    let today = OffsetDateTime::now_utc().date();
+    let yesterday = today - Duration::days(1);
+    let daily = DailyPuzzles::new(graph(), 3).unwrap();
+
+    let puzzle = view(&app, &format!("/daily/{today}")).await;
+    assert_eq!(puzzle, daily.puzzle(today).view(false));
+    assert_eq!(puzzle.hops, None);
+    assert_eq!(puzzle.paths, None);
+
+    let puzzle = view(&app, &format!("/daily/{yesterday}")).await;
+    assert_eq!(puzzle, daily.puzzle(yesterday).view(true));
+}
+
+#[tokio::test]
+async fn reveals_answer_once_submitted() {
+    let app = app(Some(DailyPuzzles::new(graph(), 3).unwrap()));
+    let uri = format!(
+        "/daily/{}?player=player-1",
+        OffsetDateTime::now_utc().date()
+    );
+    assert_eq!(view(&app, &uri).await.hops, None);
+
+    let body = LeaderboardSubmission {
+        player: "player-1".to_string(),
+        name: "Ann".to_string(),
+        guess: 2,
+    };
+    let request = Request::post("/leaderboard/daily")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(serde_json::to_vec(&body).unwrap()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert!(view(&app, &uri).await.hops.is_some());
+}
+
+#[tokio::test]
+async fn invalid_date() {
+    let app = app(Some(DailyPuzzles::new(graph(), 3).unwrap()));
+
+    assert_eq!(
+        get(&app, "/daily/2026-13-01").await.0,
+        StatusCode::BAD_REQUEST
+    );
+}
+
+#[tokio::test]
+async fn not_configured() {
+    assert_eq!(
+        get(&app(None), "/daily/2026-10-19").await.0,
+        StatusCode::NOT_FOUND
+    );
+}
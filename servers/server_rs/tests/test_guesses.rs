@@ -0,0 +1,184 @@
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    Router,
+};
+use crawler_common::{InMemoryLinks, LinkMode};
+use futures_util::{SinkExt, StreamExt};
+use http_body_util::BodyExt;
+use server_rs::{
+    router, score, AppState, Backend, ClientMessage, Crawler, GuessRecord, GuessRequest,
+    GuessResult, InMemoryStats, Outcome, PlayerStats, ServerMessage, SqliteStats, StatsStore,
+};
+use std::sync::Arc;
+use titles::KEVIN_BACON;
+use tokio::net::TcpListener;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tower::ServiceExt;
+
+fn state(store: Arc<dyn StatsStore>) -> AppState {
+    let links = InMemoryLinks::from_edges([
+        ("Near", vec![KEVIN_BACON]),
+        ("Middle", vec!["M1"]),
+        ("M1", vec![KEVIN_BACON]),
+        ("Island", vec![]),
+    ]);
+    AppState::new(Crawler::from_link_source(
+        Backend::Async,
+        2,
+        Arc::new(links),
+    ))
+    .with_stats_store(store)
+}
+
+fn app() -> Router {
+    router(state(Arc::new(InMemoryStats::new())))
+}
+
+async fn send(app: &Router, request: Request<Body>) -> (StatusCode, Vec<u8>) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, bytes.to_vec())
+}
+
+async fn post_guess(app: &Router, player: &str, start: &str, guess: usize) -> StatusCode {
+    let body = GuessRequest {
+        player: player.to_string(),
+        start: start.to_string(),
+        guess,
+        links: LinkMode::default(),
+    };
+    let request = Request::post("/guesses")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(serde_json::to_vec(&body).unwrap()))
+        .unwrap();
+    send(app, request).await.0
+}
+
+async fn player_stats(app: &Router, player: &str) -> PlayerStats {
+    let request = Request::get(format!("/players/{player}/stats"))
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = send(app, request).await;
+    assert_eq!(status, StatusCode::OK);
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[test]
+fn scores_guesses() {
+    assert_eq!(score(3, 3).outcome, Outcome::Exact);
+    assert_eq!(score(3, 3).points, 100);
+    assert_eq!(score(2, 3).outcome, Outcome::OffByOne);
+    assert_eq!(score(4, 3).points, 50);
+    assert_eq!(score(6, 3).outcome, Outcome::Miss);
+    assert_eq!(score(6, 3).points, 0);
+}
+
+#[tokio::test]
+async fn returns_scored_guess() {
+    let app = app();
+    let body = GuessRequest {
+        player: "alice".to_string(),
+        start: "middle".to_string(),
+        guess: 2,
+        links: LinkMode::default(),
+    };
+    let request = Request::post("/guesses")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(serde_json::to_vec(&body).unwrap()))
+        .unwrap();
+
+    let (status, body) = send(&app, request).await;
+    let result: GuessResult = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(result.start, "Middle");
+    assert_eq!(result.hops, 2);
+    assert_eq!(result.path, ["Middle", "M1", KEVIN_BACON]);
+    assert_eq!(result.score, score(2, 2));
+    assert_eq!(result.stats.games, 1);
+}
+
+#[tokio::test]
+async fn keeps_player_statistics() {
+    let app = app();
+
+    for (start, guess) in [("Near", 1), ("Middle", 2), ("Near", 2), ("Middle", 2)] {
+        assert_eq!(post_guess(&app, "bob", start, guess).await, StatusCode::OK);
+    }
+    let stats = player_stats(&app, "bob").await;
+
+    assert_eq!(stats.games, 4);
+    assert_eq!(stats.exact, 3);
+    assert_eq!(stats.off_by_one, 1);
+    assert_eq!(stats.points, 350);
+    assert!((stats.accuracy - 0.75).abs() < f64::EPSILON);
+    assert!((stats.average_error - 0.25).abs() < f64::EPSILON);
+    assert_eq!(stats.current_streak, 1);
+    assert_eq!(stats.best_streak, 2);
+    assert_eq!(player_stats(&app, "nobody").await.games, 0);
+}
+
+#[tokio::test]
+async fn rejects_invalid_player_and_unreachable_start() {
+    let app = app();
+
+    assert_eq!(
+        post_guess(&app, "not valid!", "Near", 1).await,
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        post_guess(&app, "carol", "Island", 1).await,
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+    assert_eq!(player_stats(&app, "carol").await.games, 0);
+}
+
+#[test]
+fn sqlite_stats_persist() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("stats.db");
+    let record = GuessRecord {
+        start: "Near".to_string(),
+        guess: 1,
+        hops: 1,
+        hints: 2,
+    };
+
+    SqliteStats::open(&path)
+        .unwrap()
+        .record("dave", &record)
+        .unwrap();
+    let reopened = SqliteStats::open(&path).unwrap();
+
+    assert_eq!(reopened.guesses("dave").unwrap(), [record]);
+    assert!(reopened.guesses("erin").unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn records_websocket_guesses_of_player() {
+    let store = Arc::new(InMemoryStats::new());
+    let app = router(state(store.clone()));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let (mut socket, _) = connect_async(format!("ws://{addr}/ws?start=Near&player=frank"))
+        .await
+        .unwrap();
+    let text = serde_json::to_string(&ClientMessage::Guess { hops: 1 }).unwrap();
+    socket.send(Message::Text(text)).await.unwrap();
+    while let Some(Ok(message)) = socket.next().await {
+        if let Message::Text(text) = message {
+            if let ServerMessage::Result { score, .. } = serde_json::from_str(&text).unwrap() {
+                assert_eq!(score, 100);
+                break;
+            }
+        }
+    }
+
+    let guesses = store.guesses("frank").unwrap();
+    assert_eq!(guesses.len(), 1);
+    assert_eq!(guesses[0].hops, 1);
+}
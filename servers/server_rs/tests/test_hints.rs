@@ -0,0 +1,232 @@
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    Router,
+};
+use crawler_common::{InMemoryLinks, LinkMode};
+use http_body_util::BodyExt;
+use server_rs::{
+    likely_route, plan_hints, router, AppState, Backend, CategorySource, Crawler, DailyPuzzles,
+    Difficulty, FixedCategories, GuessRequest, GuessResult, Hint, HintConfig, HintLedger,
+    HintRequest, HintResponse, HINT_PENALTY,
+};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use time::OffsetDateTime;
+use titles::KEVIN_BACON;
+use tower::ServiceExt;
+
+fn path(titles: &[&str]) -> Vec<String> {
+    titles.iter().map(ToString::to_string).collect()
+}
+
+fn article(hop: usize, title: &str, paths_through: usize) -> Hint {
+    Hint::Article {
+        hop,
+        title: title.to_string(),
+        paths_through,
+    }
+}
+
+fn app(links: InMemoryLinks, daily: Option<DailyPuzzles>) -> Router {
+    let crawler = Crawler::from_link_source(Backend::Async, 2, Arc::new(links));
+    let categories = FixedCategories::new(HashMap::from([(
+        "Middle".to_string(),
+        vec!["Films".to_string()],
+    )]));
+    let mut state = AppState::new(crawler).with_categories(Arc::new(categories));
+    if let Some(daily) = daily {
+        state = state.with_daily_puzzles(daily);
+    }
+    router(state)
+}
+
+async fn post<T: serde::de::DeserializeOwned>(
+    app: &Router,
+    uri: &str,
+    body: &impl serde::Serialize,
+) -> (StatusCode, Option<T>) {
+    let request = Request::post(uri)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(serde_json::to_vec(body).unwrap()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&bytes).ok())
+}
+
+async fn hint(app: &Router, start: &str) -> (StatusCode, Option<HintResponse>) {
+    let request = HintRequest {
+        player: "player-1".to_string(),
+        start: start.to_string(),
+        links: LinkMode::All,
+    };
+    post(app, "/hints", &request).await
+}
+
+#[test]
+fn route_follows_most_shortest_paths() {
+    let paths = [
+        path(&["Start", "A", "X", KEVIN_BACON]),
+        path(&["Start", "B", "Y", KEVIN_BACON]),
+        path(&["Start", "B", "X", KEVIN_BACON]),
+    ];
+
+    assert_eq!(
+        likely_route(&paths),
+        [("B".to_string(), 2), ("X".to_string(), 1)]
+    );
+}
+
+#[test]
+fn plans_hints_in_order() {
+    let paths = [path(&["Start", "A", "X", KEVIN_BACON])];
+
+    assert_eq!(
+        plan_hints(&paths, vec!["Films".to_string()]),
+        [
+            Hint::HopBound {
+                difficulty: Difficulty::Medium,
+                at_least: 3,
+                at_most: Some(3),
+            },
+            Hint::Category {
+                categories: vec!["Films".to_string()],
+            },
+            article(1, "A", 1),
+            article(2, "X", 1),
+        ]
+    );
+    assert_eq!(plan_hints(&paths, Vec::new()).len(), 3);
+    assert_eq!(
+        plan_hints(&[path(&["Start", KEVIN_BACON])], vec!["Films".to_string()]),
+        [Hint::HopBound {
+            difficulty: Difficulty::Easy,
+            at_least: 0,
+            at_most: Some(2),
+        }]
+    );
+}
+
+#[test]
+fn ledger_forgets_the_oldest_when_full() {
+    let ledger = HintLedger::new(HintConfig {
+        capacity: 2,
+        ..HintConfig::default()
+    });
+
+    ledger.reveal("player-1", "Start", 3);
+    ledger.reveal("player-2", "Start", 3);
+    ledger.reveal("player-1", "Start", 3);
+    ledger.reveal("player-3", "Start", 3);
+
+    assert_eq!(ledger.used("player-1", "Start"), 2);
+    assert_eq!(ledger.used("player-2", "Start"), 0);
+    assert_eq!(ledger.used("player-3", "Start"), 1);
+}
+
+#[test]
+fn ledger_forgets_expired_hints() {
+    let ledger = HintLedger::new(HintConfig {
+        ttl: Duration::ZERO,
+        ..HintConfig::default()
+    });
+
+    assert_eq!(ledger.reveal("player-1", "Start", 3), Some(1));
+    assert_eq!(ledger.used("player-1", "Start"), 0);
+    assert_eq!(ledger.take("player-1", "Start"), 0);
+}
+
+#[tokio::test]
+async fn hints_reduce_the_next_score() {
+    let links =
+        InMemoryLinks::from_edges([("Start", vec!["Middle"]), ("Middle", vec![KEVIN_BACON])]);
+    let app = app(links, None);
+
+    for revealed in 1..=3 {
+        let (status, response) = hint(&app, "start").await;
+        let response = response.unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response.hints.len(), revealed);
+        assert_eq!(response.remaining, 3 - revealed);
+    }
+    let (status, _) = hint(&app, "Start").await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let guess = GuessRequest {
+        player: "player-1".to_string(),
+        start: "Start".to_string(),
+        guess: 2,
+        links: LinkMode::All,
+    };
+    let (status, result) = post::<GuessResult>(&app, "/guesses", &guess).await;
+    let result = result.unwrap();
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(result.score.hints, 3);
+    assert_eq!(result.score.points, 100 - 3 * HINT_PENALTY);
+    assert_eq!(result.stats.points, u64::from(100 - 3 * HINT_PENALTY));
+
+    // Guessing starts the hints over
+    assert_eq!(hint(&app, "Start").await.1.unwrap().hints.len(), 1);
+}
+
+/// Categories that can never be retrieved
+struct Unreachable;
+
+#[async_trait]
+impl CategorySource for Unreachable {
+    async fn categories(&self, _title: &str) -> anyhow::Result<Vec<String>> {
+        anyhow::bail!("Wikipedia is unreachable")
+    }
+}
+
+#[tokio::test]
+async fn failed_category_lookup_is_a_bad_gateway() {
+    let links =
+        InMemoryLinks::from_edges([("Start", vec!["Middle"]), ("Middle", vec![KEVIN_BACON])]);
+    let crawler = Crawler::from_link_source(Backend::Async, 2, Arc::new(links));
+    let app = router(AppState::new(crawler).with_categories(Arc::new(Unreachable)));
+
+    assert_eq!(hint(&app, "Start").await.0, StatusCode::BAD_GATEWAY);
+}
+
+#[tokio::test]
+async fn rejects_invalid_requests() {
+    let links = InMemoryLinks::from_edges([("Island", vec![])]);
+    let app = app(links, None);
+
+    assert_eq!(
+        hint(&app, "Island").await.0,
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+    let request = HintRequest {
+        player: "not valid!".to_string(),
+        start: "Island".to_string(),
+        links: LinkMode::All,
+    };
+    let (status, _) = post::<HintResponse>(&app, "/hints", &request).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn daily_puzzle_hints_use_every_shortest_path() {
+    let graph = InMemoryLinks::from_edges([
+        ("Start", vec!["A", "B"]),
+        ("A", vec!["X"]),
+        ("B", vec!["X", "Y"]),
+        ("X", vec![KEVIN_BACON]),
+        ("Y", vec![KEVIN_BACON]),
+    ]);
+    let daily = DailyPuzzles::new(graph.clone(), 0).unwrap();
+    let puzzle = daily.puzzle(OffsetDateTime::now_utc().date());
+    // The crawler knows no links, so only the puzzle's paths can be hinted at
+    let app = app(InMemoryLinks::default(), Some(daily));
+
+    let mut last = None;
+    while let (StatusCode::OK, Some(response)) = hint(&app, &puzzle.start).await {
+        last = Some(response);
+    }
+
+    assert_eq!(last.unwrap().hints, plan_hints(&puzzle.paths, Vec::new()));
+}
@@ -0,0 +1,300 @@
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    Router,
+};
+use crawler_common::InMemoryLinks;
+use http_body_util::BodyExt;
+use server_rs::{
+    router, score, AppState, Backend, Crawler, DailyPuzzles, InMemoryStats, Leaderboard,
+    LeaderboardPage, LeaderboardResult, LeaderboardSubmission, Page, StatsStore, Submission,
+};
+use std::sync::Arc;
+use time::{Date, OffsetDateTime};
+use titles::KEVIN_BACON;
+use tower::ServiceExt;
+
+fn date(date: &str) -> Date {
+    puzzle::parse_date(date).unwrap()
+}
+
+fn submission(player: &str, date: Date, points: u32, submitted_at: u64) -> Submission {
+    Submission {
+        player: player.to_string(),
+        name: player.to_uppercase(),
+        date,
+        guess: 2,
+        hops: 2,
+        points,
+        submitted_at,
+    }
+}
+
+fn names(page: &LeaderboardPage) -> Vec<&str> {
+    page.entries
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect()
+}
+
+fn graph() -> InMemoryLinks {
+    InMemoryLinks::from_edges([
+        ("Start", vec!["Middle"]),
+        ("Middle", vec![KEVIN_BACON]),
+        ("Other", vec![KEVIN_BACON]),
+    ])
+}
+
+fn app(store: Arc<dyn StatsStore>) -> Router {
+    let crawler = Crawler::from_link_source(Backend::Async, 2, Arc::new(graph()));
+    let state = AppState::new(crawler)
+        .with_daily_puzzles(DailyPuzzles::new(graph(), 0).unwrap())
+        .with_leaderboard(Leaderboard::open_in_memory().unwrap())
+        .with_stats_store(store);
+    router(state)
+}
+
+async fn send(app: &Router, request: Request<Body>) -> (StatusCode, Vec<u8>) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, bytes.to_vec())
+}
+
+async fn submit(app: &Router, player: &str, name: &str, guess: usize) -> (StatusCode, Vec<u8>) {
+    let body = LeaderboardSubmission {
+        player: player.to_string(),
+        name: name.to_string(),
+        guess,
+    };
+    let request = Request::post("/leaderboard/daily")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(serde_json::to_vec(&body).unwrap()))
+        .unwrap();
+    send(app, request).await
+}
+
+async fn get(app: &Router, uri: &str) -> (StatusCode, Vec<u8>) {
+    send(app, Request::get(uri).body(Body::empty()).unwrap()).await
+}
+
+#[test]
+fn ties_rank_earlier_submission_first() {
+    let leaderboard = Leaderboard::open_in_memory().unwrap();
+    let day = date("2026-10-19");
+
+    assert_eq!(
+        leaderboard
+            .submit(&submission("late", day, 100, 30))
+            .unwrap(),
+        Some(1)
+    );
+    assert_eq!(
+        leaderboard
+            .submit(&submission("early", day, 100, 10))
+            .unwrap(),
+        Some(1)
+    );
+    assert_eq!(
+        leaderboard.submit(&submission("low", day, 50, 5)).unwrap(),
+        Some(3)
+    );
+    let page = leaderboard.daily(day, Page::default()).unwrap();
+
+    assert_eq!(names(&page), ["EARLY", "LATE", "LOW"]);
+    assert_eq!(
+        page.entries
+            .iter()
+            .map(|entry| entry.rank)
+            .collect::<Vec<_>>(),
+        [1, 2, 3]
+    );
+}
+
+#[test]
+fn one_submission_per_player_and_day() {
+    let leaderboard = Leaderboard::open_in_memory().unwrap();
+
+    assert!(leaderboard
+        .submit(&submission("ann", date("2026-10-19"), 0, 1))
+        .unwrap()
+        .is_some());
+    assert_eq!(
+        leaderboard
+            .submit(&submission("ann", date("2026-10-19"), 100, 2))
+            .unwrap(),
+        None
+    );
+    assert!(leaderboard
+        .submit(&submission("ann", date("2026-10-20"), 100, 3))
+        .unwrap()
+        .is_some());
+
+    let page = leaderboard
+        .daily(date("2026-10-19"), Page::default())
+        .unwrap();
+    assert_eq!(page.entries[0].points, 0);
+}
+
+#[test]
+fn pages_through_entries() {
+    let leaderboard = Leaderboard::open_in_memory().unwrap();
+    let day = date("2026-10-19");
+    for (i, player) in ["a", "b", "c", "d", "e"].into_iter().enumerate() {
+        leaderboard
+            .submit(&submission(player, day, 100, i as u64))
+            .unwrap();
+    }
+
+    let second = leaderboard
+        .daily(
+            day,
+            Page {
+                page: 2,
+                per_page: 2,
+            },
+        )
+        .unwrap();
+    let last = leaderboard
+        .daily(
+            day,
+            Page {
+                page: 3,
+                per_page: 2,
+            },
+        )
+        .unwrap();
+
+    assert_eq!(names(&second), ["C", "D"]);
+    assert_eq!(second.entries[0].rank, 3);
+    assert_eq!(second.total, 5);
+    assert_eq!(names(&last), ["E"]);
+}
+
+#[test]
+fn all_time_totals_every_day() {
+    let leaderboard = Leaderboard::open_in_memory().unwrap();
+    for (player, day, points, at) in [
+        ("steady", "2026-10-18", 50, 1),
+        ("steady", "2026-10-19", 50, 4),
+        ("lucky", "2026-10-19", 100, 2),
+        ("new", "2026-10-20", 100, 3),
+    ] {
+        leaderboard
+            .submit(&submission(player, date(day), points, at))
+            .unwrap();
+    }
+
+    let page = leaderboard.all_time(Page::default()).unwrap();
+
+    assert_eq!(names(&page), ["LUCKY", "NEW", "STEADY"]);
+    assert_eq!(page.entries[2].points, 100);
+    assert_eq!(page.entries[2].games, 2);
+    assert_eq!(page.total, 3);
+}
+
+#[test]
+fn persists_across_restarts() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("leaderboard.db");
+    let day = date("2026-10-19");
+
+    Leaderboard::open(&path)
+        .unwrap()
+        .submit(&submission("ann", day, 100, 1))
+        .unwrap();
+    let reopened = Leaderboard::open(&path).unwrap();
+
+    assert_eq!(
+        names(&reopened.daily(day, Page::default()).unwrap()),
+        ["ANN"]
+    );
+    assert_eq!(
+        reopened.submit(&submission("ann", day, 100, 2)).unwrap(),
+        None
+    );
+}
+
+#[tokio::test]
+async fn scores_submission_against_server_path() {
+    let store = Arc::new(InMemoryStats::new());
+    let app = app(store.clone());
+
+    let (status, body) = submit(&app, "player-1", " Ann ", 2).await;
+    assert_eq!(status, StatusCode::OK);
+    let result: LeaderboardResult = serde_json::from_slice(&body).unwrap();
+    let today = OffsetDateTime::now_utc().date();
+    let expected = DailyPuzzles::new(graph(), 0).unwrap().puzzle(today);
+
+    assert_eq!(result.puzzle, expected.clone().view(false));
+    assert_eq!(result.score, score(2, expected.hops));
+    assert_eq!(result.rank, 1);
+    assert_eq!(store.guesses("player-1").unwrap().len(), 1);
+
+    let (status, _) = submit(&app, "player-1", "Ann", expected.hops).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, body) = get(&app, &format!("/leaderboard/daily/{today}")).await;
+    assert_eq!(status, StatusCode::OK);
+    let page: LeaderboardPage = serde_json::from_slice(&body).unwrap();
+    assert_eq!(names(&page), ["Ann"]);
+    assert_eq!(page.entries[0].points, u64::from(result.score.points));
+
+    let (status, body) = get(&app, "/leaderboard?per_page=10").await;
+    assert_eq!(status, StatusCode::OK);
+    let page: LeaderboardPage = serde_json::from_slice(&body).unwrap();
+    assert_eq!(page.per_page, 10);
+    assert_eq!(page.total, 1);
+}
+
+#[tokio::test]
+async fn rejected_submission_keeps_hints() {
+    let crawler = Crawler::from_link_source(Backend::Async, 2, Arc::new(graph()));
+    let state = AppState::new(crawler)
+        .with_daily_puzzles(DailyPuzzles::new(graph(), 0).unwrap())
+        .with_leaderboard(Leaderboard::open_in_memory().unwrap());
+    let hints = state.hints.clone();
+    let app = router(state);
+    let start = DailyPuzzles::new(graph(), 0)
+        .unwrap()
+        .puzzle(OffsetDateTime::now_utc().date())
+        .start;
+
+    assert_eq!(submit(&app, "player-1", "Ann", 2).await.0, StatusCode::OK);
+    hints.reveal("player-1", &start, 3);
+
+    let (status, _) = submit(&app, "player-1", "Ann", 2).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(hints.used("player-1", &start), 1);
+}
+
+#[tokio::test]
+async fn rejects_invalid_requests() {
+    let app = app(Arc::new(InMemoryStats::new()));
+
+    for (player, name) in [("not valid!", "Ann"), ("player-1", ""), ("player-1", "\n")] {
+        assert_eq!(
+            submit(&app, player, name, 2).await.0,
+            StatusCode::BAD_REQUEST
+        );
+    }
+    for uri in [
+        "/leaderboard?page=0",
+        "/leaderboard?per_page=101",
+        "/leaderboard/daily/someday",
+    ] {
+        assert_eq!(get(&app, uri).await.0, StatusCode::BAD_REQUEST, "{uri}");
+    }
+}
+
+#[tokio::test]
+async fn not_found_without_daily_puzzles() {
+    let crawler = Crawler::from_link_source(Backend::Async, 2, Arc::new(graph()));
+    let app = router(AppState::new(crawler));
+
+    assert_eq!(
+        submit(&app, "player-1", "Ann", 2).await.0,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(get(&app, "/leaderboard").await.0, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,215 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    Router,
+};
+use common::{assert_place_kept_until_released, StalledLinks};
+use crawler_common::{InMemoryLinks, LinkMode};
+use http_body_util::BodyExt;
+use server_rs::{
+    router, AppState, Backend, Crawler, JobConfig, MoveError, NavigationConfig, NavigationMove,
+    NavigationRequest, NavigationStatus, NavigationView, Navigations,
+};
+use std::{sync::Arc, time::Duration};
+use titles::KEVIN_BACON;
+use tower::ServiceExt;
+
+fn app(config: NavigationConfig) -> Router {
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["Middle", "Detour"]),
+        ("Detour", vec!["Middle"]),
+        ("Middle", vec![KEVIN_BACON]),
+    ]);
+    let crawler = Crawler::from_link_source(Backend::Async, 2, Arc::new(links));
+    router(AppState::new(crawler).with_navigation_config(config))
+}
+
+async fn send(app: &Router, request: Request<Body>) -> (StatusCode, Vec<u8>) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, bytes.to_vec())
+}
+
+async fn post(app: &Router, uri: &str, body: &impl serde::Serialize) -> (StatusCode, Vec<u8>) {
+    let request = Request::post(uri)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(serde_json::to_vec(body).unwrap()))
+        .unwrap();
+    send(app, request).await
+}
+
+async fn start(app: &Router, start: &str) -> NavigationView {
+    let request = NavigationRequest {
+        start: Some(start.to_string()),
+        links: LinkMode::All,
+    };
+    let (status, body) = post(app, "/navigations", &request).await;
+    assert_eq!(status, StatusCode::CREATED);
+    serde_json::from_slice(&body).unwrap()
+}
+
+async fn move_to(app: &Router, id: &str, title: &str) -> (StatusCode, Option<NavigationView>) {
+    let request = NavigationMove {
+        title: title.to_string(),
+    };
+    let (status, body) = post(app, &format!("/navigations/{id}/moves"), &request).await;
+    (status, serde_json::from_slice(&body).ok())
+}
+
+async fn get(app: &Router, id: &str) -> (StatusCode, Option<NavigationView>) {
+    let request = Request::get(format!("/navigations/{id}"))
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = send(app, request).await;
+    (status, serde_json::from_slice(&body).ok())
+}
+
+#[tokio::test]
+async fn navigates_to_kevin_bacon() {
+    let app = app(NavigationConfig::default());
+
+    let session = start(&app, "start").await;
+    assert_eq!(session.current, "Start");
+    assert_eq!(session.status, NavigationStatus::Playing);
+    assert!(session.remaining_secs > 0);
+
+    for title in ["Detour", "middle"] {
+        let (status, _) = move_to(&app, &session.id, title).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    let (status, finished) = move_to(&app, &session.id, "Kevin_Bacon").await;
+    let finished = finished.unwrap();
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(finished.status, NavigationStatus::Finished);
+    assert_eq!(finished.route, ["Start", "Detour", "Middle", KEVIN_BACON]);
+    assert_eq!(finished.hops, 3);
+    assert_eq!(
+        finished.optimum,
+        Some(vec![
+            "Start".to_string(),
+            "Middle".to_string(),
+            KEVIN_BACON.to_string()
+        ])
+    );
+    assert_eq!(finished.extra_hops, Some(1));
+    assert!(finished.links.is_empty());
+}
+
+#[tokio::test]
+async fn rejects_moves_that_are_not_links() {
+    let app = app(NavigationConfig::default());
+    let session = start(&app, "Start").await;
+
+    let (status, _) = move_to(&app, &session.id, KEVIN_BACON).await;
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(get(&app, &session.id).await.1.unwrap().hops, 0);
+}
+
+#[tokio::test]
+async fn resumes_session_by_id() {
+    let app = app(NavigationConfig::default());
+    let session = start(&app, "Start").await;
+    let (_, moved) = move_to(&app, &session.id, "Middle").await;
+
+    let (status, resumed) = get(&app, &session.id).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(resumed.unwrap().route, moved.unwrap().route);
+    assert_eq!(get(&app, "unknown").await.0, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn ended_sessions_take_no_moves() {
+    let app = app(NavigationConfig::default());
+    let session = start(&app, "Middle").await;
+    move_to(&app, &session.id, KEVIN_BACON).await;
+
+    assert_eq!(
+        move_to(&app, &session.id, KEVIN_BACON).await.0,
+        StatusCode::CONFLICT
+    );
+
+    let finished = start(&app, "Kevin Bacon").await;
+    assert_eq!(finished.status, NavigationStatus::Finished);
+    assert_eq!(finished.extra_hops, Some(0));
+}
+
+#[tokio::test]
+async fn sessions_expire() {
+    let app = app(NavigationConfig {
+        time_limit: Duration::ZERO,
+        ..NavigationConfig::default()
+    });
+    let session = start(&app, "Start").await;
+
+    assert_eq!(
+        move_to(&app, &session.id, "Middle").await.0,
+        StatusCode::GONE
+    );
+    let (status, expired) = get(&app, &session.id).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(expired.unwrap().status, NavigationStatus::Expired);
+
+    let app = app_without_retention();
+    let session = start(&app, "Start").await;
+    assert_eq!(get(&app, &session.id).await.0, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn expired_blocking_search_keeps_its_place() {
+    let links = StalledLinks::new(InMemoryLinks::from_edges([("Start", vec!["Slow"])]));
+    let (started, release) = (links.started.clone(), links.release.clone());
+    let crawler = Crawler::from_link_source(Backend::Blocking, 2, Arc::new(links));
+    let state = AppState::new(crawler)
+        .with_job_config(JobConfig {
+            concurrency: 1,
+            ..JobConfig::default()
+        })
+        .with_navigation_config(NavigationConfig {
+            time_limit: Duration::from_millis(50),
+            ..NavigationConfig::default()
+        });
+    let jobs = state.jobs.clone();
+    let app = router(state);
+
+    let session = start(&app, "Start").await;
+    started.notified().await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let (_, expired) = get(&app, &session.id).await;
+    assert_eq!(expired.unwrap().status, NavigationStatus::Expired);
+
+    // The crawl of "Slow" can't be stopped, so it keeps its place until it ends
+    assert_place_kept_until_released(jobs, &release).await;
+}
+
+fn app_without_retention() -> Router {
+    app(NavigationConfig {
+        time_limit: Duration::ZERO,
+        retention: Duration::ZERO,
+    })
+}
+
+#[test]
+fn moves_from_a_stale_article_conflict() {
+    let navigations = Navigations::new(NavigationConfig::default());
+    let session = navigations.start(
+        "Start".to_string(),
+        vec!["Middle".to_string()],
+        LinkMode::All,
+    );
+
+    let (checked, title) = navigations.check_move(&session.id, "Middle").unwrap();
+    navigations
+        .advance(&session.id, checked.hops, title.clone(), Vec::new())
+        .unwrap();
+
+    assert_eq!(
+        navigations.advance(&session.id, checked.hops, title, Vec::new()),
+        Err(MoveError::Conflict)
+    );
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use common::{assert_place_kept_until_released, assert_stopped_after_slow, StalledLinks};
+use crawler_common::{InMemoryLinks, LinkSource};
+use http_body_util::BodyExt;
+use server_rs::{
+    router, AppState, Backend, Crawler, Difficulty, FixedSampler, JobConfig, RandomArticle,
+    RandomConfig, X_CACHE,
+};
+use std::{sync::Arc, time::Duration};
+use titles::KEVIN_BACON;
+use tower::ServiceExt;
+
+fn links() -> InMemoryLinks {
+    InMemoryLinks::from_edges([
+        ("Near", vec![KEVIN_BACON]),
+        ("Middle", vec!["M1"]),
+        ("M1", vec!["M2"]),
+        ("M2", vec![KEVIN_BACON]),
+        ("Far", vec!["F1"]),
+        ("F1", vec!["F2"]),
+        ("F2", vec!["F3"]),
+        ("F3", vec![KEVIN_BACON]),
+        ("Island", vec![]),
+        ("Slow", vec![KEVIN_BACON]),
+    ])
+}
+
+fn state(source: Arc<dyn LinkSource>, titles: &[&str]) -> AppState {
+    let crawler = Crawler::from_link_source(Backend::Async, 2, source);
+    let sampler = FixedSampler::new(titles.iter().map(ToString::to_string).collect());
+    AppState::new(crawler).with_sampler(Arc::new(sampler))
+}
+
+fn app(titles: &[&str]) -> Router {
+    router(state(Arc::new(links()), titles))
+}
+
+async fn get(app: &Router, uri: &str) -> (StatusCode, axum::http::HeaderMap, Vec<u8>) {
+    let request = Request::get(uri).body(Body::empty()).unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let headers = response.headers().clone();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, headers, bytes.to_vec())
+}
+
+async fn random(app: &Router, query: &str) -> RandomArticle {
+    let (status, _, body) = get(app, &format!("/random{query}")).await;
+    assert_eq!(status, StatusCode::OK);
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn picks_article_of_difficulty() {
+    let app = app(&["Near", "Middle", "Far", "Island"]);
+
+    for (query, difficulty, title) in [
+        ("?difficulty=easy", Difficulty::Easy, "Near"),
+        ("?difficulty=medium", Difficulty::Medium, "Middle"),
+        ("?difficulty=hard", Difficulty::Hard, "Far"),
+    ] {
+        let article = random(&app, query).await;
+
+        assert_eq!(article.title, title);
+        assert_eq!(article.difficulty, Some(difficulty));
+    }
+}
+
+#[tokio::test]
+async fn answer_is_cached() {
+    let app = app(&["Middle"]);
+
+    let article = random(&app, "?difficulty=medium").await;
+    let (_, headers, _) = get(&app, &format!("/{}", article.title)).await;
+
+    assert_eq!(headers[X_CACHE], "HIT");
+}
+
+#[tokio::test]
+async fn any_reachable_article_without_difficulty() {
+    let app = app(&["Island", "Far"]);
+
+    assert_eq!(random(&app, "").await.title, "Far");
+}
+
+#[tokio::test]
+async fn no_article_of_difficulty() {
+    let app = app(&["Near", "Island"]);
+
+    let (status, ..) = get(&app, "/random?difficulty=hard").await;
+
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn gives_up_in_time() {
+    let links = StalledLinks::new(links());
+    let (release, looked_up) = (links.release.clone(), links.looked_up.clone());
+    let app = router(
+        state(Arc::new(links), &["Slow"]).with_random_config(RandomConfig {
+            time_limit: Duration::from_millis(50),
+        }),
+    );
+
+    let (status, ..) = get(&app, "/random").await;
+
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_stopped_after_slow(&release, &looked_up).await;
+}
+
+#[tokio::test]
+async fn blocking_search_keeps_its_place_once_given_up() {
+    let links = StalledLinks::new(links());
+    let release = links.release.clone();
+    let crawler = Crawler::from_link_source(Backend::Blocking, 2, Arc::new(links));
+    let state = AppState::new(crawler)
+        .with_sampler(Arc::new(FixedSampler::new(vec!["Slow".to_string()])))
+        .with_job_config(JobConfig {
+            concurrency: 1,
+            ..JobConfig::default()
+        })
+        .with_random_config(RandomConfig {
+            time_limit: Duration::from_millis(50),
+        });
+    let jobs = state.jobs.clone();
+    let app = router(state);
+
+    let (status, ..) = get(&app, "/random").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+    // The crawl of "Slow" can't be stopped, so it keeps its place until it ends
+    assert_place_kept_until_released(jobs, &release).await;
+}
@@ -0,0 +1,217 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use common::{assert_stopped_after_slow, StalledLinks};
+use crawler_common::{InMemoryLinks, LinkMode, SearchOrder, SearchOrderKind};
+use http_body_util::BodyExt;
+use serde_json::Value;
+use server_rs::{router, ApiDoc, AppState, Backend, Crawler, SearchResponse, SearchStatus};
+use std::sync::Arc;
+use titles::KEVIN_BACON;
+use tower::ServiceExt;
+use utoipa::OpenApi;
+
+const BACKENDS: [Backend; 5] = [
+    Backend::Blocking,
+    Backend::Async,
+    Backend::Channels,
+    Backend::Pipelined,
+    Backend::Graph,
+];
+
+fn app(backend: Backend) -> Router {
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["Middle", "Elsewhere"]),
+        ("Middle", vec![KEVIN_BACON]),
+        ("Island", vec![]),
+    ]);
+    let crawler = Crawler::from_graph(backend, 2, links);
+    router(AppState::new(crawler))
+}
+
+async fn get(app: &Router, uri: &str) -> (StatusCode, String) {
+    let request = Request::get(uri).body(Body::empty()).unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, String::from_utf8(bytes.to_vec()).unwrap())
+}
+
+async fn get_json(app: &Router, uri: &str) -> Value {
+    let (status, body) = get(app, uri).await;
+    assert_eq!(status, StatusCode::OK);
+    serde_json::from_str(&body).unwrap()
+}
+
+async fn search(app: &Router, uri: &str) -> SearchResponse {
+    serde_json::from_value(get_json(app, uri).await).unwrap()
+}
+
+#[tokio::test]
+async fn finds_path() {
+    for backend in BACKENDS {
+        let app = app(backend);
+
+        let response = search(&app, "/Start").await;
+
+        assert_eq!(response.status, SearchStatus::Found, "{backend} backend");
+        assert_eq!(
+            response.path,
+            Some(vec![
+                "Start".to_string(),
+                "Middle".to_string(),
+                KEVIN_BACON.to_string()
+            ])
+        );
+        assert_eq!(response.hops, Some(2));
+        assert!(response.shortest);
+        assert_eq!(response.target, KEVIN_BACON);
+        // The graph backend has no pages to fetch
+        let pages_fetched = if backend == Backend::Graph { 0 } else { 2 };
+        assert_eq!(response.pages_fetched, pages_fetched, "{backend} backend");
+        assert_eq!(response.crawler, backend);
+        assert_eq!(response.order, SearchOrderKind::Breadth);
+        assert_eq!(response.link_mode, LinkMode::All);
+        assert_eq!(response.error, None);
+    }
+}
+
+#[tokio::test]
+async fn best_first_paths_are_not_known_shortest() {
+    let links = InMemoryLinks::from_edges([("Start", vec![KEVIN_BACON])]);
+    let order = || SearchOrder::BestFirst(Arc::new(|_: &str| 0));
+    let crawler = Crawler::from_graph(Backend::Pipelined, 2, links.clone())
+        .with_search_order(order())
+        .unwrap();
+    let app = router(AppState::new(crawler));
+
+    let response = search(&app, "/Start").await;
+
+    assert_eq!(response.hops, Some(1));
+    assert_eq!(response.order, SearchOrderKind::BestFirst);
+    assert!(!response.shortest);
+    // Only the pipelined crawler searches in other orders
+    assert!(Crawler::from_graph(Backend::Async, 2, links)
+        .with_search_order(order())
+        .is_err());
+}
+
+#[tokio::test]
+async fn canonicalizes_start() {
+    let app = app(Backend::Async);
+
+    let response = search(&app, "/start").await;
+
+    assert_eq!(response.starting_page, "start");
+    assert_eq!(response.canonical_start, "Start");
+    assert_eq!(response.hops, Some(2));
+}
+
+#[tokio::test]
+async fn unreachable_start() {
+    for backend in BACKENDS {
+        let app = app(backend);
+
+        let response = search(&app, "/Island?links=body").await;
+
+        assert_eq!(response.status, SearchStatus::Failed, "{backend} backend");
+        assert_eq!(response.path, None);
+        assert_eq!(response.hops, None);
+        assert_eq!(response.link_mode, LinkMode::Body);
+        assert!(response.error.is_some());
+    }
+}
+
+#[tokio::test]
+async fn serves_schema() {
+    let schema = get_json(&app(Backend::Async), "/schemas/search-response.json").await;
+
+    assert_eq!(schema["title"], "SearchResponse");
+    for field in [
+        "status",
+        "path",
+        "hops",
+        "canonical_start",
+        "target",
+        "pages_fetched",
+        "elapsed_ms",
+        "crawler",
+        "error",
+    ] {
+        assert!(schema["properties"].get(field).is_some(), "{field}");
+    }
+    // Every referenced schema is defined in the document itself
+    assert_eq!(
+        schema["properties"]["status"]["$ref"],
+        "#/$defs/SearchStatus"
+    );
+    for name in ["SearchStatus", "SearchError", "Backend", "LinkMode"] {
+        assert!(schema["$defs"].get(name).is_some(), "{name}");
+    }
+}
+
+#[tokio::test]
+async fn streams_events() {
+    for backend in BACKENDS {
+        let app = app(backend);
+
+        let (status, body) = get(&app, "/search/Start/events").await;
+
+        assert_eq!(status, StatusCode::OK, "{backend} backend");
+        assert!(body.contains("event: progress"), "{backend} backend");
+        let result = body
+            .split("event: result\ndata: ")
+            .nth(1)
+            .and_then(|rest| rest.lines().next())
+            .unwrap();
+        let result: SearchResponse = serde_json::from_str(result).unwrap();
+        assert_eq!(result.hops, Some(2), "{backend} backend");
+    }
+}
+
+#[tokio::test]
+async fn aborts_events_search_on_disconnect() {
+    let links = StalledLinks::new(InMemoryLinks::from_edges([
+        ("Start", vec!["Slow"]),
+        ("Slow", vec!["After"]),
+    ]));
+    let (started, release, looked_up) = (
+        links.started.clone(),
+        links.release.clone(),
+        links.looked_up.clone(),
+    );
+    let crawler = Crawler::from_link_source(Backend::Async, 2, Arc::new(links));
+    let app = router(AppState::new(crawler));
+
+    let request = Request::get("/search/Start/events")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    started.notified().await;
+    drop(response);
+
+    assert_stopped_after_slow(&release, &looked_up).await;
+}
+
+/// The committed `openapi.json` is what the frontend and other consumers read;
+/// regenerate it with `UPDATE_OPENAPI=1 cargo test`
+#[tokio::test]
+async fn serves_openapi() {
+    let served = get_json(&app(Backend::Async), "/openapi.json").await;
+    assert_eq!(served, serde_json::to_value(ApiDoc::openapi()).unwrap());
+
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/openapi.json");
+    let generated = serde_json::to_string_pretty(&served).unwrap() + "\n";
+    if std::env::var_os("UPDATE_OPENAPI").is_some() {
+        std::fs::write(path, &generated).unwrap();
+    }
+    assert_eq!(
+        std::fs::read_to_string(path).unwrap_or_default(),
+        generated,
+        "openapi.json is out of date; rerun with UPDATE_OPENAPI=1"
+    );
+}
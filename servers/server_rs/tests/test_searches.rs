@@ -0,0 +1,186 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use common::{assert_stopped_after_slow, StalledLinks};
+use crawler_common::InMemoryLinks;
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use server_rs::{router, AppState, Backend, Crawler, JobConfig, JobStatus, JobView, X_CACHE};
+use std::{sync::Arc, time::Duration};
+use titles::KEVIN_BACON;
+use tower::ServiceExt;
+
+fn links() -> StalledLinks {
+    StalledLinks::new(InMemoryLinks::from_edges([
+        ("Start", vec!["Middle"]),
+        ("Middle", vec![KEVIN_BACON]),
+        ("Slow", vec!["After"]),
+    ]))
+}
+
+fn app(config: JobConfig) -> Router {
+    app_with_links(links(), config)
+}
+
+fn app_with_links(links: StalledLinks, config: JobConfig) -> Router {
+    let crawler = Crawler::from_link_source(Backend::Async, 2, Arc::new(links));
+    router(AppState::new(crawler).with_job_config(config))
+}
+
+async fn send(app: &Router, request: Request<Body>) -> (StatusCode, Value) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let body = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, body)
+}
+
+async fn create(app: &Router, start: &str) -> u64 {
+    let request = Request::post("/searches")
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "start": start }).to_string()))
+        .unwrap();
+    let (status, body) = send(app, request).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(body["status"], "queued");
+    body["id"].as_u64().unwrap()
+}
+
+async fn get(app: &Router, id: u64) -> (StatusCode, Value) {
+    send(
+        app,
+        Request::get(format!("/searches/{id}"))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await
+}
+
+async fn poll_until_finished(app: &Router, id: u64) -> JobView {
+    for _ in 0..100 {
+        let (status, body) = get(app, id).await;
+        assert_eq!(status, StatusCode::OK);
+        let job: JobView = serde_json::from_value(body).unwrap();
+        if matches!(job.status, JobStatus::Done | JobStatus::Failed) {
+            return job;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("search {id} did not finish");
+}
+
+#[tokio::test]
+async fn search_runs_to_completion() {
+    let app = app(JobConfig::default());
+    let id = create(&app, "Start").await;
+
+    let job = poll_until_finished(&app, id).await;
+
+    assert_eq!(job.status, JobStatus::Done);
+    assert_eq!(
+        job.path,
+        Some(vec![
+            "Start".to_string(),
+            "Middle".to_string(),
+            KEVIN_BACON.to_string()
+        ])
+    );
+    assert_eq!(job.progress.pages_visited, 2);
+}
+
+#[tokio::test]
+async fn finished_search_is_cached() {
+    let app = app(JobConfig::default());
+    let id = create(&app, "Start").await;
+    poll_until_finished(&app, id).await;
+
+    let request = Request::get("/Start").body(Body::empty()).unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.headers()[X_CACHE], "HIT");
+}
+
+#[tokio::test]
+async fn cached_search_is_done_at_once() {
+    let app = app(JobConfig::default());
+    let (status, _) = send(&app, Request::get("/Start").body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let request = Request::post("/searches")
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "start": "start" }).to_string()))
+        .unwrap();
+    let (status, body) = send(&app, request).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(body["status"], "done");
+
+    let (_, body) = get(&app, body["id"].as_u64().unwrap()).await;
+    let job: JobView = serde_json::from_value(body).unwrap();
+    assert_eq!(job.start, "Start");
+    assert_eq!(job.path.map(|path| path.len()), Some(3));
+}
+
+#[tokio::test]
+async fn unreachable_search_fails() {
+    let app = app(JobConfig::default());
+    let id = create(&app, "Nowhere").await;
+
+    let job = poll_until_finished(&app, id).await;
+
+    assert_eq!(job.status, JobStatus::Failed);
+    assert!(job.error.is_some());
+}
+
+#[tokio::test]
+async fn unknown_search() {
+    let app = app(JobConfig::default());
+
+    assert_eq!(get(&app, 42).await.0, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn concurrency_limit_and_cancel() {
+    let links = links();
+    let (release, looked_up) = (links.release.clone(), links.looked_up.clone());
+    let app = app_with_links(
+        links,
+        JobConfig {
+            concurrency: 1,
+            ..JobConfig::default()
+        },
+    );
+    let slow = create(&app, "Slow").await;
+    let queued = create(&app, "Start").await;
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(get(&app, slow).await.1["status"], "running");
+    assert_eq!(get(&app, queued).await.1["status"], "queued");
+
+    let delete = Request::delete(format!("/searches/{slow}"))
+        .body(Body::empty())
+        .unwrap();
+    assert_eq!(send(&app, delete).await.0, StatusCode::NO_CONTENT);
+    assert_eq!(get(&app, slow).await.0, StatusCode::NOT_FOUND);
+    assert_stopped_after_slow(&release, &looked_up).await;
+
+    assert_eq!(
+        poll_until_finished(&app, queued).await.status,
+        JobStatus::Done
+    );
+}
+
+#[tokio::test]
+async fn finished_searches_expire() {
+    let app = app(JobConfig {
+        retention: Duration::ZERO,
+        ..JobConfig::default()
+    });
+    let id = create(&app, "Start").await;
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    assert_eq!(get(&app, id).await.0, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,236 @@
+mod common;
+
+use common::{assert_stopped_after_slow, StalledLinks};
+use crawler_common::InMemoryLinks;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::json;
+use server_rs::{
+    router, score, AppState, Backend, ClientMessage, Crawler, ServerMessage, PROTOCOL_VERSION,
+};
+use std::{net::SocketAddr, sync::Arc};
+use titles::KEVIN_BACON;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn spawn_server() -> SocketAddr {
+    let links = InMemoryLinks::from_edges([
+        ("Start", vec!["Middle", "Elsewhere"]),
+        ("Middle", vec![KEVIN_BACON]),
+        ("Island", vec![]),
+    ]);
+    let mut state = AppState::new(Crawler::from_link_source(
+        Backend::Async,
+        2,
+        Arc::new(links),
+    ));
+    state.starting_pages = Arc::new(vec!["Start".to_string()]);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router(state)).await.unwrap() });
+    addr
+}
+
+async fn connect(query: &str) -> Socket {
+    let addr = spawn_server().await;
+    let (socket, _) = connect_async(format!("ws://{addr}/ws{query}"))
+        .await
+        .unwrap();
+    socket
+}
+
+async fn receive(socket: &mut Socket) -> ServerMessage {
+    loop {
+        match socket.next().await.unwrap().unwrap() {
+            Message::Text(text) => return serde_json::from_str(&text).unwrap(),
+            Message::Close(_) => panic!("socket closed while waiting for a message"),
+            _ => {}
+        }
+    }
+}
+
+async fn guess(socket: &mut Socket, hops: usize) {
+    let text = serde_json::to_string(&ClientMessage::Guess { hops }).unwrap();
+    socket.send(Message::Text(text)).await.unwrap();
+}
+
+/// Skip progress messages until the next other message
+async fn receive_after_progress(socket: &mut Socket) -> ServerMessage {
+    loop {
+        match receive(socket).await {
+            ServerMessage::Progress(_) => {}
+            message => return message,
+        }
+    }
+}
+
+#[tokio::test]
+async fn plays_a_round() {
+    let mut socket = connect("").await;
+
+    assert_eq!(
+        receive(&mut socket).await,
+        ServerMessage::Start {
+            version: PROTOCOL_VERSION,
+            start: "Start".to_string(),
+            target: KEVIN_BACON.to_string(),
+        }
+    );
+
+    guess(&mut socket, 2).await;
+
+    assert_eq!(
+        receive_after_progress(&mut socket).await,
+        ServerMessage::Result {
+            path: Some(vec![
+                "Start".to_string(),
+                "Middle".to_string(),
+                KEVIN_BACON.to_string()
+            ]),
+            hops: Some(2),
+            guess: 2,
+            score: 100,
+        }
+    );
+}
+
+#[tokio::test]
+async fn streams_progress() {
+    let mut socket = connect("?start=Start").await;
+    receive(&mut socket).await;
+
+    assert!(matches!(
+        receive(&mut socket).await,
+        ServerMessage::Progress(_)
+    ));
+}
+
+#[tokio::test]
+async fn searches_canonical_start_through_cache() {
+    let addr = spawn_server().await;
+    let (mut socket, _) = connect_async(format!("ws://{addr}/ws?start=start"))
+        .await
+        .unwrap();
+
+    let ServerMessage::Start { start, .. } = receive(&mut socket).await else {
+        panic!("expected the start");
+    };
+    assert_eq!(start, "Start");
+    guess(&mut socket, 2).await;
+    let ServerMessage::Result { hops, .. } = receive_after_progress(&mut socket).await else {
+        panic!("expected a result");
+    };
+    assert_eq!(hops, Some(2));
+
+    let cached = reqwest::get(format!("http://{addr}/Start")).await.unwrap();
+    assert_eq!(cached.headers()["x-cache"], "HIT");
+}
+
+#[tokio::test]
+async fn off_by_one_guess() {
+    let mut socket = connect("?start=Start").await;
+    receive(&mut socket).await;
+    guess(&mut socket, 3).await;
+
+    let ServerMessage::Result { score, .. } = receive_after_progress(&mut socket).await else {
+        panic!("expected a result");
+    };
+    assert_eq!(score, 50);
+}
+
+#[tokio::test]
+async fn hints_lower_the_score() {
+    let addr = spawn_server().await;
+    let hint = reqwest::Client::new()
+        .post(format!("http://{addr}/hints"))
+        .json(&json!({ "player": "player-1", "start": "Start" }))
+        .send()
+        .await
+        .unwrap();
+    assert!(hint.status().is_success());
+
+    let (mut socket, _) = connect_async(format!("ws://{addr}/ws?start=Start&player=player-1"))
+        .await
+        .unwrap();
+    receive(&mut socket).await;
+    guess(&mut socket, 2).await;
+
+    let ServerMessage::Result { score: points, .. } = receive_after_progress(&mut socket).await
+    else {
+        panic!("expected a result");
+    };
+    assert_eq!(points, score(2, 2).with_hints(1).points);
+}
+
+#[tokio::test]
+async fn unreachable_start() {
+    let mut socket = connect("?start=Island").await;
+    receive(&mut socket).await;
+    guess(&mut socket, 1).await;
+
+    assert_eq!(
+        receive_after_progress(&mut socket).await,
+        ServerMessage::Result {
+            path: None,
+            hops: None,
+            guess: 1,
+            score: 0,
+        }
+    );
+}
+
+#[tokio::test]
+async fn rejects_unsupported_version() {
+    let mut socket = connect("?version=99").await;
+
+    assert!(matches!(
+        receive(&mut socket).await,
+        ServerMessage::Error { .. }
+    ));
+}
+
+#[tokio::test]
+async fn rejects_invalid_message() {
+    let mut socket = connect("?start=Start").await;
+    receive(&mut socket).await;
+    socket
+        .send(Message::Text(r#"{"type":"shrug"}"#.to_string()))
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        receive_after_progress(&mut socket).await,
+        ServerMessage::Error { .. }
+    ));
+}
+
+#[tokio::test]
+async fn aborts_search_on_disconnect() {
+    let links = StalledLinks::new(InMemoryLinks::from_edges([
+        ("Start", vec!["Slow"]),
+        ("Slow", vec!["After"]),
+    ]));
+    let (started, release, looked_up) = (
+        links.started.clone(),
+        links.release.clone(),
+        links.looked_up.clone(),
+    );
+    let state = AppState::new(Crawler::from_link_source(
+        Backend::Async,
+        2,
+        Arc::new(links),
+    ));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router(state)).await.unwrap() });
+
+    let (socket, _) = connect_async(format!("ws://{addr}/ws?start=Start"))
+        .await
+        .unwrap();
+    started.notified().await;
+    drop(socket);
+
+    assert_stopped_after_slow(&release, &looked_up).await;
+}
@@ -0,0 +1,28 @@
+use crate::{PlayerStats, Score};
+use crawler_common::LinkMode;
+use serde::{Deserialize, Serialize};
+
+/// Body of `POST /guesses`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct GuessRequest {
+    /// Anonymous id the player's statistics are kept under
+    pub player: String,
+    pub start: String,
+    /// The player's guess of the minimum number of link hops
+    pub guess: usize,
+    #[serde(default)]
+    pub links: LinkMode,
+}
+
+/// A scored guess along with the player's updated statistics
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct GuessResult {
+    pub start: String,
+    pub guess: usize,
+    pub hops: usize,
+    pub path: Vec<String>,
+    pub score: Score,
+    pub stats: PlayerStats,
+}
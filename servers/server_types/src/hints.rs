@@ -0,0 +1,50 @@
+use crawler_common::LinkMode;
+use puzzle::Difficulty;
+use serde::{Deserialize, Serialize};
+
+/// Something revealed about the shortest path from a start
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Hint {
+    /// The shortest path takes `at_least` to `at_most` hops; no upper bound
+    /// when `at_most` is absent
+    HopBound {
+        difficulty: Difficulty,
+        at_least: usize,
+        at_most: Option<usize>,
+    },
+    /// Categories of the first article after the start
+    Category { categories: Vec<String> },
+    /// The article `hop` hops along a shortest path, and how many of the
+    /// known shortest paths go through it
+    Article {
+        hop: usize,
+        title: String,
+        paths_through: usize,
+    },
+}
+
+/// Body of `POST /hints`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct HintRequest {
+    /// Anonymous id the player's statistics are kept under
+    pub player: String,
+    pub start: String,
+    #[serde(default)]
+    pub links: LinkMode,
+}
+
+/// The hints revealed so far for a start
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct HintResponse {
+    pub start: String,
+    /// Every hint revealed so far, the newest last
+    pub hints: Vec<Hint>,
+    /// Hints still to be revealed
+    pub remaining: usize,
+    /// Points the hints take off the next score for this start
+    pub penalty: u32,
+}
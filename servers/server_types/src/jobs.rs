@@ -0,0 +1,42 @@
+use crate::SearchProgress;
+use crawler_common::LinkMode;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+}
+
+/// A search job as reported by `GET /searches/:id`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct JobView {
+    pub id: u64,
+    pub start: String,
+    pub status: JobStatus,
+    pub progress: SearchProgress,
+    pub path: Option<Vec<String>>,
+    pub error: Option<String>,
+}
+
+/// Body of `POST /searches`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct SearchRequest {
+    pub start: String,
+    #[serde(default)]
+    pub links: LinkMode,
+}
+
+/// Reply to `POST /searches`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct CreatedSearch {
+    pub id: u64,
+    pub status: JobStatus,
+}
@@ -0,0 +1,49 @@
+use crate::{PuzzleView, Score};
+use serde::{Deserialize, Serialize};
+
+/// One player's place on a leaderboard
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct LeaderboardEntry {
+    pub rank: u64,
+    /// Display name of the player's latest submission
+    pub name: String,
+    pub points: u64,
+    /// Daily puzzles submitted
+    pub games: u64,
+    /// When the entry reached its points, in milliseconds since the Unix epoch
+    pub submitted_at: u64,
+}
+
+/// A page of a leaderboard, best first
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct LeaderboardPage {
+    pub entries: Vec<LeaderboardEntry>,
+    pub page: u32,
+    pub per_page: u32,
+    /// Entries on every page together
+    pub total: u64,
+}
+
+/// Body of `POST /leaderboard/daily`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct LeaderboardSubmission {
+    /// Anonymous id the player's statistics are kept under
+    pub player: String,
+    /// Name shown on the leaderboards
+    pub name: String,
+    /// The player's guess of the minimum number of link hops
+    pub guess: usize,
+}
+
+/// A scored submission and its place on today's leaderboard
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct LeaderboardResult {
+    /// Today's puzzle, without its answer until the day is over
+    pub puzzle: PuzzleView,
+    pub score: Score,
+    pub rank: u64,
+}
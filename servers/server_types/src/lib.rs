@@ -0,0 +1,28 @@
+//! Request and response bodies of `server_rs`, shared with its clients so
+//! they don't depend on the server itself.
+
+mod guesses;
+mod hints;
+mod jobs;
+mod leaderboard;
+mod navigate;
+mod progress;
+mod random;
+mod response;
+mod scoring;
+mod stats;
+
+pub use crawler_common::{Backend, LinkMode};
+pub use guesses::{GuessRequest, GuessResult};
+pub use hints::{Hint, HintRequest, HintResponse};
+pub use jobs::{CreatedSearch, JobStatus, JobView, SearchRequest};
+pub use leaderboard::{
+    LeaderboardEntry, LeaderboardPage, LeaderboardResult, LeaderboardSubmission,
+};
+pub use navigate::{NavigationMove, NavigationRequest, NavigationStatus, NavigationView};
+pub use progress::SearchProgress;
+pub use puzzle::{Difficulty, Puzzle, PuzzleView};
+pub use random::RandomArticle;
+pub use response::{SearchError, SearchResponse, SearchStatus};
+pub use scoring::{hint_penalty, score, Outcome, Score, HINT_PENALTY};
+pub use stats::{GuessRecord, PlayerStats};
@@ -0,0 +1,53 @@
+use crawler_common::LinkMode;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum NavigationStatus {
+    Playing,
+    /// Kevin Bacon was reached
+    Finished,
+    /// Time ran out before Kevin Bacon was reached
+    Expired,
+}
+
+/// A navigation session as reported by `GET /navigations/:id`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct NavigationView {
+    pub id: String,
+    pub status: NavigationStatus,
+    pub link_mode: LinkMode,
+    pub current: String,
+    /// Articles visited so far, starting with the start article
+    pub route: Vec<String>,
+    pub hops: usize,
+    /// Articles the next move can go to; empty once the session has ended
+    pub links: Vec<String>,
+    /// Seconds left to reach Kevin Bacon
+    pub remaining_secs: u64,
+    /// A shortest path from the start, once the session has finished and the
+    /// server found one
+    pub optimum: Option<Vec<String>>,
+    /// Hops taken beyond the shortest path
+    pub extra_hops: Option<usize>,
+}
+
+/// Body of `POST /navigations`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct NavigationRequest {
+    /// Article to start from instead of a random starting page
+    pub start: Option<String>,
+    #[serde(default)]
+    pub links: LinkMode,
+}
+
+/// Body of `POST /navigations/:id/moves`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct NavigationMove {
+    /// Article to move to, one of the current article's links
+    pub title: String,
+}
@@ -0,0 +1,44 @@
+use crawler_common::CrawlEvent;
+use serde::{Deserialize, Serialize};
+
+/// Snapshot of a running search, folded from the crawler's events
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct SearchProgress {
+    pub depth: usize,
+    pub pages_visited: usize,
+    pub frontier_size: usize,
+}
+
+impl Default for SearchProgress {
+    fn default() -> Self {
+        Self {
+            depth: 0,
+            pages_visited: 0,
+            frontier_size: 1,
+        }
+    }
+}
+
+impl SearchProgress {
+    /// Update the snapshot with an event, returning whether it changed
+    pub fn apply(&mut self, event: &CrawlEvent) -> bool {
+        match *event {
+            CrawlEvent::PageFetched { depth, .. } => {
+                self.depth = depth;
+                self.pages_visited += 1;
+                true
+            }
+            CrawlEvent::DepthCompleted {
+                depth,
+                frontier_size,
+                ..
+            } => {
+                self.depth = depth + 1;
+                self.frontier_size = frontier_size;
+                true
+            }
+            CrawlEvent::FetchFailed { .. } | CrawlEvent::TargetFound { .. } => false,
+        }
+    }
+}
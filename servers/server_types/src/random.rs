@@ -0,0 +1,10 @@
+use puzzle::Difficulty;
+use serde::{Deserialize, Serialize};
+
+/// A random starting article, with its answer already cached
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct RandomArticle {
+    pub title: String,
+    pub difficulty: Option<Difficulty>,
+}
@@ -0,0 +1,49 @@
+use crawler_common::{Backend, LinkFilter, LinkMode, SearchOrderKind};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum SearchStatus {
+    /// A path to the target was found
+    Found,
+    /// No path was found; `error` says why
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct SearchError {
+    pub message: String,
+}
+
+/// Outcome of one search
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct SearchResponse {
+    pub status: SearchStatus,
+    /// Article the search started from, as requested
+    pub starting_page: String,
+    /// `starting_page` in the form Wikipedia stores titles in
+    pub canonical_start: String,
+    pub target: String,
+    /// Articles from `canonical_start` to `target`, when found
+    pub path: Option<Vec<String>>,
+    /// Link hops along `path`, when found
+    pub hops: Option<usize>,
+    /// Whether `path` is as short as any path there is. Best-first searches
+    /// find paths sooner that may not be.
+    pub shortest: bool,
+    /// Articles whose links were looked up
+    pub pages_fetched: usize,
+    pub elapsed_ms: u64,
+    /// Backend that ran the search
+    pub crawler: Backend,
+    /// Order the backend fetched pages in
+    pub order: SearchOrderKind,
+    pub link_mode: LinkMode,
+    /// Rules deciding which links the search followed
+    pub filter: LinkFilter,
+    /// Present when `status` is `failed`
+    pub error: Option<SearchError>,
+}
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+/// Points taken off a score for each hint used
+pub const HINT_PENALTY: u32 = 20;
+
+/// How close a guess came to the real number of hops
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Exact,
+    OffByOne,
+    Miss,
+}
+
+/// A guess measured against the shortest path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct Score {
+    pub outcome: Outcome,
+    pub points: u32,
+    /// Hints used before guessing
+    #[serde(default)]
+    pub hints: usize,
+}
+
+impl Score {
+    /// The score after `hints` hints, each costing [`HINT_PENALTY`] points
+    #[must_use]
+    pub fn with_hints(self, hints: usize) -> Self {
+        Self {
+            points: self.points.saturating_sub(hint_penalty(hints)),
+            hints: self.hints + hints,
+            ..self
+        }
+    }
+}
+
+/// Points taken off a score for using `hints` hints
+#[must_use]
+pub fn hint_penalty(hints: usize) -> u32 {
+    u32::try_from(hints)
+        .unwrap_or(u32::MAX)
+        .saturating_mul(HINT_PENALTY)
+}
+
+/// Score a guess of the minimum number of link hops: full marks when exact,
+/// half when off by one
+#[must_use]
+pub fn score(guess: usize, hops: usize) -> Score {
+    let (outcome, points) = match hops.abs_diff(guess) {
+        0 => (Outcome::Exact, 100),
+        1 => (Outcome::OffByOne, 50),
+        _ => (Outcome::Miss, 0),
+    };
+    Score {
+        outcome,
+        points,
+        hints: 0,
+    }
+}
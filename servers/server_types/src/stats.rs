@@ -0,0 +1,73 @@
+use crate::{score, Outcome};
+use serde::{Deserialize, Serialize};
+
+/// One scored guess
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuessRecord {
+    pub start: String,
+    pub guess: usize,
+    pub hops: usize,
+    /// Hints used before guessing
+    #[serde(default)]
+    pub hints: usize,
+}
+
+/// Statistics of one player, as reported by `GET /players/:id/stats`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct PlayerStats {
+    pub games: usize,
+    pub exact: usize,
+    pub off_by_one: usize,
+    pub points: u64,
+    /// Share of guesses that were exact, from 0 to 1
+    pub accuracy: f64,
+    /// Mean distance between the guess and the real number of hops
+    pub average_error: f64,
+    /// Exact guesses in a row, up to the latest guess
+    pub current_streak: usize,
+    pub best_streak: usize,
+}
+
+impl PlayerStats {
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn from_guesses(guesses: &[GuessRecord]) -> Self {
+        let mut stats = Self {
+            games: guesses.len(),
+            exact: 0,
+            off_by_one: 0,
+            points: 0,
+            accuracy: 0.0,
+            average_error: 0.0,
+            current_streak: 0,
+            best_streak: 0,
+        };
+        let mut total_error = 0;
+
+        for guess in guesses {
+            let score = score(guess.guess, guess.hops).with_hints(guess.hints);
+            stats.points += u64::from(score.points);
+            total_error += guess.guess.abs_diff(guess.hops);
+
+            match score.outcome {
+                Outcome::Exact => {
+                    stats.exact += 1;
+                    stats.current_streak += 1;
+                    stats.best_streak = stats.best_streak.max(stats.current_streak);
+                }
+                Outcome::OffByOne => {
+                    stats.off_by_one += 1;
+                    stats.current_streak = 0;
+                }
+                Outcome::Miss => stats.current_streak = 0,
+            }
+        }
+
+        if stats.games > 0 {
+            stats.accuracy = stats.exact as f64 / stats.games as f64;
+            stats.average_error = total_error as f64 / stats.games as f64;
+        }
+        stats
+    }
+}
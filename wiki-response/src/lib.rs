@@ -3,19 +3,28 @@ use std::collections::HashMap;
 
 #[derive(Debug, Deserialize)]
 pub struct WikiResponse {
+    #[serde(default)]
     pub query: Query,
     #[serde(rename = "continue")]
     pub continuation: Option<HashMap<String, String>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct Query {
+    #[serde(default)]
     pub pages: HashMap<String, Page>,
+    /// Articles returned by `list=random`
+    #[serde(default)]
+    pub random: Vec<Link>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct Page {
     pub links: Option<Vec<Link>>,
+    /// Categories returned by `prop=categories`
+    pub categories: Option<Vec<Link>>,
+    /// Revisions returned by `prop=revisions`
+    pub revisions: Option<Vec<Revision>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -23,3 +32,20 @@ pub struct Link {
     pub ns: u32,
     pub title: String,
 }
+
+#[derive(Debug, Deserialize)]
+pub struct Revision {
+    pub slots: Slots,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Slots {
+    pub main: Slot,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Slot {
+    /// The wikitext of the revision
+    #[serde(rename = "*")]
+    pub content: String,
+}
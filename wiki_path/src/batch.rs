@@ -0,0 +1,363 @@
+//! Paths from many starts at once, such as candidate puzzle starts. Each
+//! result is written as soon as its search finishes, so an interrupted batch
+//! resumes from its partial output.
+
+use crate::{find_path, Budget, PathCache};
+use anyhow::{anyhow, Context};
+use crawler_common::{canonical_title, LinkMode};
+use serde::{Deserialize, Serialize};
+use server_rs::Crawler;
+use std::{
+    collections::HashSet,
+    fmt,
+    fs::{self, OpenOptions},
+    io::{self, BufRead, Seek, SeekFrom, Write},
+    path::Path,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use titles::KEVIN_BACON;
+use tokio::{task::JoinSet, time::MissedTickBehavior};
+
+const PATH_SEPARATOR: &str = " -> ";
+
+/// How batch results are written
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BatchFormat {
+    /// One JSON object per line
+    #[default]
+    JsonLines,
+    /// A header row, then one row per start with the path's titles joined by
+    /// ` -> `
+    Csv,
+}
+
+impl FromStr for BatchFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "jsonl" => Ok(Self::JsonLines),
+            "csv" => Ok(Self::Csv),
+            _ => Err(anyhow!("Unknown batch format '{s}'; expected jsonl or csv")),
+        }
+    }
+}
+
+impl fmt::Display for BatchFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::JsonLines => "jsonl",
+            Self::Csv => "csv",
+        })
+    }
+}
+
+/// The outcome of the search from one start
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchRecord {
+    pub start: String,
+    pub target: String,
+    pub path: Option<Vec<String>>,
+    pub hops: Option<usize>,
+    /// Time the search took, in milliseconds
+    pub elapsed_ms: u64,
+    /// Why no path was found
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CsvRow {
+    start: String,
+    target: String,
+    hops: Option<usize>,
+    path: String,
+    elapsed_ms: u64,
+    error: Option<String>,
+}
+
+impl From<&BatchRecord> for CsvRow {
+    fn from(record: &BatchRecord) -> Self {
+        Self {
+            start: record.start.clone(),
+            target: record.target.clone(),
+            hops: record.hops,
+            path: record
+                .path
+                .as_ref()
+                .map(|path| path.join(PATH_SEPARATOR))
+                .unwrap_or_default(),
+            elapsed_ms: record.elapsed_ms,
+            error: record.error.clone(),
+        }
+    }
+}
+
+/// How a batch searches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchConfig {
+    pub target: String,
+    pub link_mode: LinkMode,
+    pub budget: Budget,
+    /// Searches run at once
+    pub concurrency: usize,
+    /// Least time between the starts of two searches, shared by all of them
+    pub interval: Duration,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            target: KEVIN_BACON.to_string(),
+            link_mode: LinkMode::default(),
+            budget: Budget::default(),
+            concurrency: 4,
+            interval: Duration::ZERO,
+        }
+    }
+}
+
+/// Counts of a finished batch
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BatchSummary {
+    /// Starts a path was found from
+    pub found: usize,
+    /// Starts no path was found from
+    pub failed: usize,
+    /// Starts already in the output, so not searched again
+    pub skipped: usize,
+}
+
+/// Start titles, one per line. Blank lines, `#` comments and repeated titles
+/// are left out.
+///
+/// # Errors
+///
+/// Errors if the titles can't be read.
+pub fn read_titles(reader: impl BufRead) -> io::Result<Vec<String>> {
+    let mut seen = HashSet::new();
+    let mut titles = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let title = line.trim();
+        if !title.is_empty() && !title.starts_with('#') && seen.insert(canonical_title(title)) {
+            titles.push(title.to_string());
+        }
+    }
+    Ok(titles)
+}
+
+enum Sink {
+    JsonLines(Box<dyn Write>),
+    Csv(Box<csv::Writer<Box<dyn Write>>>),
+}
+
+/// Where batch results are written, and the searches already written there
+pub struct BatchOutput {
+    sink: Sink,
+    /// `(start, target)` of every record written
+    completed: HashSet<(String, String)>,
+}
+
+impl BatchOutput {
+    /// Write to `out`, starting afresh
+    #[must_use]
+    pub fn new(out: Box<dyn Write>, format: BatchFormat) -> Self {
+        Self::with_completed(out, format, true, HashSet::new())
+    }
+
+    /// Append to the output file at `path`, creating it if missing. Searches
+    /// already in it are completed; a last line cut off by an interruption is
+    /// dropped so its search runs again.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file can't be read or written, or if one of its records
+    /// can't be decoded.
+    pub fn resume(path: &Path, format: BatchFormat) -> anyhow::Result<Self> {
+        let mut contents = match fs::read(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        let complete = contents
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |i| i + 1);
+        contents.truncate(complete);
+
+        let records = match format {
+            BatchFormat::JsonLines => contents
+                .split(|&b| b == b'\n')
+                .filter(|line| !line.is_empty())
+                .map(serde_json::from_slice::<BatchRecord>)
+                .map(|record| record.map(|record| (record.start, record.target)))
+                .collect::<Result<_, _>>()
+                .map_err(anyhow::Error::from),
+            BatchFormat::Csv => csv::Reader::from_reader(contents.as_slice())
+                .deserialize::<CsvRow>()
+                .map(|row| row.map(|row| (row.start, row.target)))
+                .collect::<Result<_, _>>()
+                .map_err(anyhow::Error::from),
+        };
+        let completed = records.with_context(|| format!("Failed to decode {}", path.display()))?;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(false)
+            .open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        file.set_len(complete as u64)?;
+        file.seek(SeekFrom::End(0))?;
+
+        // A CSV file that already has rows has its header too
+        Ok(Self::with_completed(
+            Box::new(file),
+            format,
+            complete == 0,
+            completed,
+        ))
+    }
+
+    fn with_completed(
+        out: Box<dyn Write>,
+        format: BatchFormat,
+        csv_header: bool,
+        completed: HashSet<(String, String)>,
+    ) -> Self {
+        let sink = match format {
+            BatchFormat::JsonLines => Sink::JsonLines(out),
+            BatchFormat::Csv => Sink::Csv(Box::new(
+                csv::WriterBuilder::new()
+                    .has_headers(csv_header)
+                    .from_writer(out),
+            )),
+        };
+        Self { sink, completed }
+    }
+
+    /// Whether the search from `start` to `target` was already written
+    #[must_use]
+    pub fn is_completed(&self, start: &str, target: &str) -> bool {
+        self.completed
+            .contains(&(canonical_title(start), canonical_title(target)))
+    }
+
+    /// Write one record, flushed so it survives an interruption
+    ///
+    /// # Errors
+    ///
+    /// Errors if the record can't be written.
+    pub fn write(&mut self, record: &BatchRecord) -> anyhow::Result<()> {
+        match &mut self.sink {
+            Sink::JsonLines(out) => {
+                serde_json::to_writer(&mut *out, record)?;
+                out.write_all(b"\n")?;
+                out.flush()?;
+            }
+            Sink::Csv(out) => {
+                out.serialize(CsvRow::from(record))?;
+                out.flush()?;
+            }
+        }
+        self.completed
+            .insert((record.start.clone(), record.target.clone()));
+        Ok(())
+    }
+}
+
+/// Search for a path from each of `titles` to the target, at most
+/// `config.concurrency` at once, sharing `cache`. Starts already in `output`
+/// are skipped.
+///
+/// # Errors
+///
+/// Errors if a record can't be written or a search panics. A start no path is
+/// found from is written with its error instead.
+pub async fn run_batch(
+    crawler: &Crawler,
+    titles: &[String],
+    config: &BatchConfig,
+    cache: Arc<Mutex<PathCache>>,
+    output: &mut BatchOutput,
+) -> anyhow::Result<BatchSummary> {
+    let mut summary = BatchSummary::default();
+    let mut searches = JoinSet::new();
+    let mut pace = (!config.interval.is_zero()).then(|| {
+        let mut pace = tokio::time::interval(config.interval);
+        pace.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        pace
+    });
+
+    for start in titles {
+        if output.is_completed(start, &config.target) {
+            summary.skipped += 1;
+            continue;
+        }
+        while searches.len() >= config.concurrency.max(1) {
+            if let Some(record) = searches.join_next().await {
+                finish(&record?, output, &mut summary)?;
+            }
+        }
+        if let Some(pace) = &mut pace {
+            pace.tick().await;
+        }
+
+        let crawler = crawler.clone();
+        let cache = cache.clone();
+        let start = start.clone();
+        let config = config.clone();
+        searches.spawn(async move { search(&crawler, &start, &config, &cache).await });
+    }
+    while let Some(record) = searches.join_next().await {
+        finish(&record?, output, &mut summary)?;
+    }
+    Ok(summary)
+}
+
+async fn search(
+    crawler: &Crawler,
+    start: &str,
+    config: &BatchConfig,
+    cache: &Mutex<PathCache>,
+) -> BatchRecord {
+    let started = Instant::now();
+    let result = find_path(
+        crawler,
+        start,
+        &config.target,
+        config.link_mode,
+        config.budget,
+        Some(cache),
+    )
+    .await;
+    let elapsed_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+    let (path, error) = match result {
+        Ok(path) => (Some(path), None),
+        Err(e) => (None, Some(format!("{e:#}"))),
+    };
+    BatchRecord {
+        start: canonical_title(start),
+        target: canonical_title(&config.target),
+        hops: path.as_ref().map(|path| path.len() - 1),
+        path,
+        elapsed_ms,
+        error,
+    }
+}
+
+fn finish(
+    record: &BatchRecord,
+    output: &mut BatchOutput,
+    summary: &mut BatchSummary,
+) -> anyhow::Result<()> {
+    if record.error.is_some() {
+        summary.failed += 1;
+    } else {
+        summary.found += 1;
+    }
+    output.write(record)
+}
@@ -0,0 +1,107 @@
+//! Paths found by earlier runs, saved as JSON so the same query isn't crawled
+//! twice.
+
+use anyhow::Context;
+use crawler_common::LinkMode;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct Query {
+    start: String,
+    target: String,
+    link_mode: LinkMode,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    #[serde(flatten)]
+    query: Query,
+    path: Vec<String>,
+}
+
+/// Found paths keyed by start, target and link mode, kept in memory only
+/// unless loaded from a file
+#[derive(Default)]
+pub struct PathCache {
+    file: Option<PathBuf>,
+    paths: HashMap<Query, Vec<String>>,
+}
+
+impl PathCache {
+    /// Load the cache saved at `file`, which found paths are saved back to; a
+    /// missing file is an empty cache
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file exists but can't be read or decoded.
+    pub fn load(file: PathBuf) -> anyhow::Result<Self> {
+        let paths = if file.exists() {
+            let bytes = fs::read(&file)
+                .with_context(|| format!("Failed to read cache {}", file.display()))?;
+            let entries: Vec<Entry> = serde_json::from_slice(&bytes)
+                .with_context(|| format!("Failed to decode cache {}", file.display()))?;
+            entries
+                .into_iter()
+                .map(|entry| (entry.query, entry.path))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+        Ok(Self {
+            file: Some(file),
+            paths,
+        })
+    }
+
+    #[must_use]
+    pub fn get(&self, start: &str, target: &str, link_mode: LinkMode) -> Option<&Vec<String>> {
+        self.paths.get(&query(start, target, link_mode))
+    }
+
+    /// Remember a found path and save the cache
+    ///
+    /// # Errors
+    ///
+    /// Errors if the cache file can't be written.
+    pub fn insert(
+        &mut self,
+        start: &str,
+        target: &str,
+        link_mode: LinkMode,
+        path: Vec<String>,
+    ) -> anyhow::Result<()> {
+        self.paths.insert(query(start, target, link_mode), path);
+        let Some(file) = &self.file else {
+            return Ok(());
+        };
+
+        let mut entries: Vec<Entry> = self
+            .paths
+            .iter()
+            .map(|(query, path)| Entry {
+                query: query.clone(),
+                path: path.clone(),
+            })
+            .collect();
+        // Sorted so the saved file doesn't depend on hash map order
+        entries.sort_by_key(|entry| {
+            (
+                entry.query.start.clone(),
+                entry.query.target.clone(),
+                entry.query.link_mode == LinkMode::Body,
+            )
+        });
+        let json = serde_json::to_string_pretty(&entries)?;
+        fs::write(file, json + "\n")
+            .with_context(|| format!("Failed to write cache {}", file.display()))
+    }
+}
+
+fn query(start: &str, target: &str, link_mode: LinkMode) -> Query {
+    Query {
+        start: start.to_string(),
+        target: target.to_string(),
+        link_mode,
+    }
+}
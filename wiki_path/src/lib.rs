@@ -0,0 +1,12 @@
+//! Shortest link paths between Wikipedia articles, found with the crawlers
+//! for one start at a time or in batches.
+
+mod batch;
+mod cache;
+mod search;
+
+pub use batch::{
+    read_titles, run_batch, BatchConfig, BatchFormat, BatchOutput, BatchRecord, BatchSummary,
+};
+pub use cache::PathCache;
+pub use search::{find_path, Budget};
@@ -0,0 +1,280 @@
+use anyhow::Context;
+use clap::{builder::RangedU64ValueParser, Parser, Subcommand, ValueEnum};
+use crawler_common::{canonical_title, InMemoryLinks, LinkMode, Popularity, SearchOrderKind};
+use serde::Serialize;
+use server_rs::{Backend, Crawler};
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, Write},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use titles::KEVIN_BACON;
+use wiki_path::{
+    find_path, read_titles, run_batch, BatchConfig, BatchFormat, BatchOutput, Budget, PathCache,
+};
+
+#[derive(Parser)]
+#[command(about = "Shortest link paths between Wikipedia articles")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Crawler implementation to search with: blocking, async, channels,
+    /// pipelined, or graph, which needs `--graph`
+    #[arg(long, global = true, default_value_t = Backend::Async)]
+    backend: Backend,
+    /// Worker count of the async crawlers, or fetches run at once by the
+    /// pipelined one
+    #[arg(
+        long,
+        global = true,
+        default_value_t = 5,
+        value_parser = RangedU64ValueParser::<u8>::new().range(1..),
+    )]
+    workers: u8,
+    /// Order the pipelined crawler fetches pages in: breadth, prioritized,
+    /// or best-first, whose paths may not be shortest; all but breadth need
+    /// `--graph`
+    #[arg(long, global = true, default_value_t = SearchOrderKind::Breadth)]
+    order: SearchOrderKind,
+    /// Which of an article's links are followed
+    #[arg(long, global = true, value_enum, default_value_t = Links::All)]
+    links: Links,
+    /// Give up once no path of at most this many hops exists
+    #[arg(long, global = true, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    max_depth: Option<usize>,
+    /// Give up after this many seconds
+    #[arg(long, global = true)]
+    timeout: Option<u64>,
+    #[arg(long, global = true, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    /// JSON file found paths are saved to and reused from
+    #[arg(long, global = true)]
+    cache: Option<PathBuf>,
+    /// Offline link graph, a JSON object mapping titles to linked titles, to
+    /// search instead of Wikipedia
+    #[arg(long, global = true)]
+    graph: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the shortest path from an article to the target
+    Path {
+        start: String,
+        #[arg(default_value = KEVIN_BACON)]
+        target: String,
+    },
+    /// Print the titles an article links to
+    Links { title: String },
+    /// Print how many hops the shortest path from an article to the target
+    /// takes
+    Distance {
+        start: String,
+        #[arg(default_value = KEVIN_BACON)]
+        target: String,
+    },
+    /// Find paths from many articles, listed one per line, and write one
+    /// result per article
+    Batch {
+        /// File of start titles; read from stdin when absent or `-`
+        input: Option<PathBuf>,
+        /// File results are appended to, skipping articles already in it;
+        /// printed when absent
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// How results are written: jsonl or csv
+        #[arg(long, default_value_t = BatchFormat::JsonLines)]
+        output_format: BatchFormat,
+        #[arg(long, default_value = KEVIN_BACON)]
+        target: String,
+        /// Searches run at once
+        #[arg(long, default_value_t = 4)]
+        concurrency: usize,
+        /// Searches started per second, across all of them
+        #[arg(long)]
+        rate: Option<f64>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Links {
+    All,
+    Body,
+}
+
+impl From<Links> for LinkMode {
+    fn from(links: Links) -> Self {
+        match links {
+            Links::All => LinkMode::All,
+            Links::Body => LinkMode::Body,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Serialize)]
+struct PathOutput<'a> {
+    start: &'a str,
+    target: &'a str,
+    hops: usize,
+    /// False when the search order may have missed a shorter path
+    shortest: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<&'a [String]>,
+}
+
+#[derive(Serialize)]
+struct LinksOutput<'a> {
+    title: &'a str,
+    links: &'a [String],
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let code = match tokio::runtime::Runtime::new()
+        .context("Failed to start the async runtime")
+        .and_then(|runtime| runtime.block_on(run(cli)))
+    {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            1
+        }
+    };
+    io::stdout().flush().ok();
+    // Exit without dropping the runtime, which would wait on a blocking crawl
+    // that ran out of budget
+    std::process::exit(code);
+}
+
+async fn run(cli: Cli) -> anyhow::Result<()> {
+    let crawler = crawler(&cli).await?;
+    let cache = Mutex::new(match &cli.cache {
+        Some(file) => PathCache::load(file.clone())?,
+        None => PathCache::default(),
+    });
+    let budget = Budget {
+        max_depth: cli.max_depth,
+        timeout: cli.timeout.map(Duration::from_secs),
+    };
+
+    match &cli.command {
+        Command::Path { start, target } | Command::Distance { start, target } => {
+            let start = canonical_title(start);
+            let target = canonical_title(target);
+            let path = find_path(
+                &crawler,
+                &start,
+                &target,
+                cli.links.into(),
+                budget,
+                Some(&cache),
+            )
+            .await?;
+            let hops = path.len() - 1;
+            let with_path = matches!(cli.command, Command::Path { .. });
+
+            match cli.format {
+                Format::Json => print_json(&PathOutput {
+                    start: &start,
+                    target: &target,
+                    hops,
+                    shortest: crawler.search_order().finds_shortest(),
+                    path: with_path.then_some(&path),
+                })?,
+                Format::Text if with_path => println!("{}", path.join(" -> ")),
+                Format::Text => println!("{hops}"),
+            }
+        }
+        Command::Links { title } => {
+            let title = canonical_title(title);
+            let links = crawler
+                .with_link_mode(cli.links.into())
+                .linked_titles(&title)
+                .await?;
+
+            match cli.format {
+                Format::Json => print_json(&LinksOutput {
+                    title: &title,
+                    links: &links,
+                })?,
+                Format::Text => {
+                    for link in &links {
+                        println!("{link}");
+                    }
+                }
+            }
+        }
+        Command::Batch {
+            input,
+            output,
+            output_format,
+            target,
+            concurrency,
+            rate,
+        } => {
+            anyhow::ensure!(rate.is_none_or(|rate| rate > 0.0), "--rate must be above 0");
+            let titles = match input {
+                Some(input) if input.as_os_str() != "-" => read_titles(BufReader::new(
+                    File::open(input)
+                        .with_context(|| format!("Failed to open {}", input.display()))?,
+                ))?,
+                _ => read_titles(io::stdin().lock())?,
+            };
+            let mut output = match output {
+                Some(output) => BatchOutput::resume(output, *output_format)?,
+                None => BatchOutput::new(Box::new(io::stdout()), *output_format),
+            };
+            let config = BatchConfig {
+                target: target.clone(),
+                link_mode: cli.links.into(),
+                budget,
+                concurrency: *concurrency,
+                interval: rate.map_or(Duration::ZERO, |rate| Duration::from_secs_f64(1.0 / rate)),
+            };
+
+            let summary =
+                run_batch(&crawler, &titles, &config, Arc::new(cache), &mut output).await?;
+            eprintln!(
+                "Found {} paths, {} failed, {} already done",
+                summary.found, summary.failed, summary.skipped
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn crawler(cli: &Cli) -> anyhow::Result<Crawler> {
+    anyhow::ensure!(
+        cli.graph.is_some() || cli.order == SearchOrderKind::Breadth,
+        "--order {} needs --graph to score titles with",
+        cli.order
+    );
+    let crawler = match &cli.graph {
+        Some(graph) => {
+            let bytes = fs::read(graph)
+                .with_context(|| format!("Failed to read graph {}", graph.display()))?;
+            let graph: InMemoryLinks = serde_json::from_slice(&bytes)
+                .with_context(|| format!("Failed to decode graph {}", graph.display()))?;
+            // Titles many others link to are fetched first
+            let order = cli
+                .order
+                .with_heuristic(Arc::new(Popularity::from_links(&graph)));
+            Crawler::from_graph(cli.backend, cli.workers, graph).with_search_order(order)?
+        }
+        None => Crawler::new(cli.backend, cli.workers).await?,
+    };
+    Ok(crawler)
+}
+
+fn print_json(output: &impl Serialize) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(output)?);
+    Ok(())
+}
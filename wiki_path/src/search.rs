@@ -0,0 +1,106 @@
+//! Shortest path searches within depth and time budgets.
+
+use crate::PathCache;
+use anyhow::anyhow;
+use crawler_common::{canonical_title, CrawlEvent, LinkMode};
+use server_rs::Crawler;
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+use tokio::sync::Notify;
+
+/// Limits a search gives up at
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Budget {
+    /// Longest path wanted, in hops
+    pub max_depth: Option<usize>,
+    /// Time the crawl may take
+    pub timeout: Option<Duration>,
+}
+
+/// The shortest path from `start` to `target` within `budget`, from `cache`
+/// when it has one. Found paths are added to `cache`. A crawler searching in
+/// an order that may miss shorter paths neither reads nor fills the cache,
+/// which holds shortest paths only.
+///
+/// # Errors
+///
+/// Errors if no path is found within the budget, or if the cache can't be
+/// saved.
+pub async fn find_path(
+    crawler: &Crawler,
+    start: &str,
+    target: &str,
+    link_mode: LinkMode,
+    budget: Budget,
+    cache: Option<&Mutex<PathCache>>,
+) -> anyhow::Result<Vec<String>> {
+    let start = canonical_title(start);
+    let target = canonical_title(target);
+    let cache = cache.filter(|_| crawler.search_order().finds_shortest());
+    let cached = cache.and_then(|cache| lock(cache).get(&start, &target, link_mode).cloned());
+
+    let path = if let Some(path) = cached {
+        path
+    } else {
+        let crawler = crawler
+            .clone()
+            .with_link_mode(link_mode)
+            .with_target(&target);
+        let path = crawl(crawler, &start, budget).await?;
+        if let Some(cache) = cache {
+            lock(cache).insert(&start, &target, link_mode, path.clone())?;
+        }
+        path
+    };
+
+    match budget.max_depth {
+        Some(max_depth) if path.len() - 1 > max_depth => Err(no_path_within(max_depth)),
+        _ => Ok(path),
+    }
+}
+
+async fn crawl(crawler: Crawler, start: &str, budget: Budget) -> anyhow::Result<Vec<String>> {
+    // Every path of up to `depth + 1` hops has been seen once `depth` is
+    // completed, so the crawl can stop there
+    let too_deep = Arc::new(Notify::new());
+    let crawler = match budget.max_depth {
+        Some(max_depth) => {
+            let too_deep = too_deep.clone();
+            crawler.with_event_handler(move |event| {
+                if let CrawlEvent::DepthCompleted { depth, .. } = event {
+                    if depth + 1 >= max_depth {
+                        too_deep.notify_one();
+                    }
+                }
+            })
+        }
+        None => crawler,
+    };
+    let deadline = async {
+        match budget.timeout {
+            Some(timeout) => tokio::time::sleep(timeout).await,
+            None => std::future::pending().await,
+        }
+    };
+
+    tokio::select! {
+        biased;
+        result = crawler.crawl(start) => Ok(result?.path),
+        () = too_deep.notified() => Err(no_path_within(budget.max_depth.unwrap_or_default())),
+        () = deadline => Err(anyhow!(
+            "No path found within {}s",
+            budget.timeout.unwrap_or_default().as_secs()
+        )),
+    }
+}
+
+fn no_path_within(max_depth: usize) -> anyhow::Error {
+    anyhow!("No path of at most {max_depth} hops")
+}
+
+fn lock(cache: &Mutex<PathCache>) -> std::sync::MutexGuard<'_, PathCache> {
+    // Entries are inserted in one step, so a poisoned cache is still usable
+    cache.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,202 @@
+use crawler_common::InMemoryLinks;
+use server_rs::{Backend, Crawler};
+use std::{
+    fs,
+    io::Cursor,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use titles::KEVIN_BACON;
+use wiki_path::{
+    read_titles, run_batch, BatchConfig, BatchFormat, BatchOutput, BatchRecord, BatchSummary,
+    Budget, PathCache,
+};
+
+fn crawler() -> Crawler {
+    let graph = InMemoryLinks::from_edges([
+        ("A", vec![KEVIN_BACON]),
+        ("B", vec!["A"]),
+        ("C", vec!["B"]),
+        ("Island", vec![]),
+    ]);
+    Crawler::from_link_source(Backend::Async, 2, Arc::new(graph))
+}
+
+fn titles(titles: &[&str]) -> Vec<String> {
+    titles.iter().map(ToString::to_string).collect()
+}
+
+async fn run(
+    titles: &[String],
+    config: &BatchConfig,
+    output: &Path,
+    format: BatchFormat,
+) -> BatchSummary {
+    let mut output = BatchOutput::resume(output, format).unwrap();
+    let cache = Arc::new(Mutex::new(PathCache::default()));
+    run_batch(&crawler(), titles, config, cache, &mut output)
+        .await
+        .unwrap()
+}
+
+fn records(output: &Path) -> Vec<BatchRecord> {
+    let mut records: Vec<BatchRecord> = fs::read_to_string(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    records.sort_by(|a, b| a.start.cmp(&b.start));
+    records
+}
+
+#[test]
+fn reads_titles_skipping_comments_and_repeats() {
+    let input = "# Films\nA\n\n  B  \na\nC\n";
+
+    assert_eq!(
+        read_titles(Cursor::new(input)).unwrap(),
+        titles(&["A", "B", "C"])
+    );
+}
+
+#[tokio::test]
+async fn writes_a_record_per_start() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("paths.jsonl");
+
+    let summary = run(
+        &titles(&["C", "Island", "A"]),
+        &BatchConfig::default(),
+        &output,
+        BatchFormat::JsonLines,
+    )
+    .await;
+    let records = records(&output);
+
+    assert_eq!(
+        summary,
+        BatchSummary {
+            found: 2,
+            failed: 1,
+            skipped: 0
+        }
+    );
+    assert_eq!(records[0].path, Some(titles(&["A", KEVIN_BACON])));
+    assert_eq!(records[1].hops, Some(3));
+    assert_eq!(records[2].start, "Island");
+    assert_eq!(records[2].path, None);
+    assert!(records[2].error.is_some());
+}
+
+#[tokio::test]
+async fn applies_budget_per_start() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("paths.jsonl");
+    let config = BatchConfig {
+        budget: Budget {
+            max_depth: Some(2),
+            timeout: None,
+        },
+        ..BatchConfig::default()
+    };
+
+    let summary = run(
+        &titles(&["B", "C"]),
+        &config,
+        &output,
+        BatchFormat::JsonLines,
+    )
+    .await;
+    let records = records(&output);
+
+    assert_eq!(summary.found, 1);
+    assert_eq!(summary.failed, 1);
+    assert_eq!(
+        records[1].error.as_deref(),
+        Some("No path of at most 2 hops")
+    );
+}
+
+#[tokio::test]
+async fn resumes_after_interruption() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("paths.jsonl");
+    run(
+        &titles(&["A"]),
+        &BatchConfig::default(),
+        &output,
+        BatchFormat::JsonLines,
+    )
+    .await;
+    // A record cut off halfway through being written
+    let mut contents = fs::read_to_string(&output).unwrap();
+    contents.push_str("{\"start\":\"B\",\"tar");
+    fs::write(&output, contents).unwrap();
+
+    let summary = run(
+        &titles(&["A", "B"]),
+        &BatchConfig::default(),
+        &output,
+        BatchFormat::JsonLines,
+    )
+    .await;
+
+    assert_eq!(summary.skipped, 1);
+    assert_eq!(summary.found, 1);
+    assert_eq!(
+        records(&output)
+            .iter()
+            .map(|record| record.start.as_str())
+            .collect::<Vec<_>>(),
+        ["A", "B"]
+    );
+}
+
+#[tokio::test]
+async fn writes_csv_with_one_header() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("paths.csv");
+    let config = BatchConfig {
+        concurrency: 1,
+        ..BatchConfig::default()
+    };
+
+    run(&titles(&["B"]), &config, &output, BatchFormat::Csv).await;
+    let summary = run(
+        &titles(&["B", "Island"]),
+        &config,
+        &output,
+        BatchFormat::Csv,
+    )
+    .await;
+    let contents = fs::read_to_string(&output).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+
+    assert_eq!(summary.skipped, 1);
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], "start,target,hops,path,elapsed_ms,error");
+    assert!(lines[1].starts_with(&format!("B,{KEVIN_BACON},2,B -> A -> {KEVIN_BACON},")));
+    assert!(lines[2].starts_with(&format!("Island,{KEVIN_BACON},,,")));
+}
+
+#[tokio::test]
+async fn spaces_out_search_starts() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("paths.jsonl");
+    let config = BatchConfig {
+        interval: Duration::from_millis(100),
+        ..BatchConfig::default()
+    };
+
+    let started = Instant::now();
+    run(
+        &titles(&["A", "B", "C"]),
+        &config,
+        &output,
+        BatchFormat::JsonLines,
+    )
+    .await;
+
+    assert!(started.elapsed() >= Duration::from_millis(200));
+}
@@ -0,0 +1,186 @@
+use crawler_common::InMemoryLinks;
+use serde_json::{json, Value};
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+use tempfile::TempDir;
+use titles::KEVIN_BACON;
+
+fn graph(dir: &TempDir) -> String {
+    let graph = InMemoryLinks::from_edges([
+        ("Start", vec!["Middle", "Far"]),
+        ("Middle", vec![KEVIN_BACON, "Other"]),
+        ("Far", vec!["Farther"]),
+        ("Farther", vec!["Farthest"]),
+        ("Farthest", vec!["End"]),
+    ]);
+    let path = dir.path().join("graph.json");
+    std::fs::write(&path, serde_json::to_vec(&graph).unwrap()).unwrap();
+    path.display().to_string()
+}
+
+fn wiki_path(graph: &str, args: &[&str]) -> (bool, String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_wiki-path"))
+        .args(["--graph", graph])
+        .args(args)
+        .output()
+        .unwrap();
+    (
+        output.status.success(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn path_to_kevin_bacon_by_default() {
+    let dir = tempfile::tempdir().unwrap();
+    let graph = graph(&dir);
+
+    for backend in ["blocking", "async", "channels", "pipelined", "graph"] {
+        let (ok, stdout, _) = wiki_path(&graph, &["path", "Start", "--backend", backend]);
+
+        assert!(ok, "{backend}");
+        assert_eq!(stdout, format!("Start -> Middle -> {KEVIN_BACON}\n"));
+    }
+}
+
+#[test]
+fn path_to_other_target_as_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let graph = graph(&dir);
+
+    let (ok, stdout, _) = wiki_path(&graph, &["path", "start", "End", "--format", "json"]);
+
+    assert!(ok);
+    let output: Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(
+        output,
+        json!({
+            "start": "Start",
+            "target": "End",
+            "hops": 4,
+            "shortest": true,
+            "path": ["Start", "Far", "Farther", "Farthest", "End"],
+        })
+    );
+}
+
+#[test]
+fn distance_and_links() {
+    let dir = tempfile::tempdir().unwrap();
+    let graph = graph(&dir);
+
+    assert_eq!(wiki_path(&graph, &["distance", "Start"]).1, "2\n");
+    assert_eq!(
+        wiki_path(&graph, &["links", "Middle"]).1,
+        format!("{KEVIN_BACON}\nOther\n")
+    );
+}
+
+#[test]
+fn gives_up_beyond_max_depth() {
+    let dir = tempfile::tempdir().unwrap();
+    let graph = graph(&dir);
+
+    for backend in ["blocking", "async", "channels", "pipelined", "graph"] {
+        let (ok, stdout, stderr) = wiki_path(
+            &graph,
+            &[
+                "path",
+                "Start",
+                "End",
+                "--max-depth",
+                "3",
+                "--backend",
+                backend,
+            ],
+        );
+
+        assert!(!ok, "{backend}");
+        assert!(stdout.is_empty());
+        assert!(stderr.contains("No path of at most 3 hops"), "{stderr}");
+    }
+    assert!(wiki_path(&graph, &["path", "Start", "End", "--max-depth", "4"]).0);
+}
+
+#[test]
+fn best_first_paths_are_not_known_shortest() {
+    let dir = tempfile::tempdir().unwrap();
+    let graph = graph(&dir);
+    let args = [
+        "distance",
+        "Start",
+        "--order",
+        "best-first",
+        "--format",
+        "json",
+    ];
+
+    let (ok, stdout, _) = wiki_path(&graph, &[&args[..], &["--backend", "pipelined"]].concat());
+    let (other_ok, _, stderr) = wiki_path(&graph, &args);
+
+    assert!(ok);
+    let output: Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(output["hops"], 2);
+    assert_eq!(output["shortest"], false);
+    assert!(!other_ok);
+    assert!(stderr.contains("breadth first"), "{stderr}");
+}
+
+#[test]
+fn rejects_zero_workers() {
+    let dir = tempfile::tempdir().unwrap();
+    let graph = graph(&dir);
+
+    let (ok, _, stderr) = wiki_path(&graph, &["path", "Start", "--workers", "0"]);
+
+    assert!(!ok);
+    assert!(stderr.contains("--workers"), "{stderr}");
+}
+
+#[test]
+fn reuses_cached_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    let graph = graph(&dir);
+    let cache = dir.path().join("cache.json");
+    let cache = cache.to_str().unwrap();
+
+    let first = wiki_path(&graph, &["path", "Start", "--cache", cache]);
+    // The graph is emptied, so the second answer can only come from the cache
+    std::fs::write(&graph, "{}").unwrap();
+    let second = wiki_path(&graph, &["path", "Start", "--cache", cache]);
+
+    assert!(first.0);
+    assert!(second.0);
+    assert_eq!(second.1, first.1);
+}
+
+#[test]
+fn batch_from_stdin() {
+    let dir = tempfile::tempdir().unwrap();
+    let graph = graph(&dir);
+    let mut child = Command::new(env!("CARGO_BIN_EXE_wiki-path"))
+        .args(["--graph", &graph, "batch", "--output-format", "csv"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"Middle\nStart\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(stdout.lines().count(), 3);
+    assert!(stdout.contains(&format!("Start -> Middle -> {KEVIN_BACON}")));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("Found 2 paths, 0 failed, 0 already done"));
+}